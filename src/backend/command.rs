@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::NoteRef;
 use crate::backend::REMOTE_METRICS_REF;
@@ -64,6 +64,54 @@ impl CommandBackend {
         }
         cmd
     }
+
+    /// Executes a git command and returns its trimmed output
+    fn exec(&self, args: &[&str]) -> Result<String, Error> {
+        let output = self.cmd().args(args).output()?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            tracing::trace!("stderr {stderr:?}");
+            Err(Error::Failed(stderr))
+        }
+    }
+
+    /// Lists the commits with `git log`
+    fn log(&self, args: &[&str]) -> Result<Vec<Commit>, Error> {
+        let output = self
+            .cmd()
+            .arg("log")
+            // fields are separated by a null character and commits by a record separator
+            .arg("--format=format:%H%x00%ct%x00%an%x00%s%x00%b%x1e")
+            .args(args)
+            .output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            tracing::error!("something went wrong when getting commits");
+            tracing::trace!("stderr {stderr:?}");
+            Err(Error::Failed(stderr.into()))
+        } else {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            tracing::trace!("stdout {stdout:?}");
+            Ok(stdout
+                .split('\x1e')
+                .map(|item| item.trim())
+                .filter(|item| !item.is_empty())
+                .filter_map(|record| {
+                    let mut fields = record.splitn(5, '\0');
+                    Some(Commit {
+                        sha: fields.next()?.to_string(),
+                        timestamp: fields.next()?.parse().ok()?,
+                        author: fields.next()?.to_string(),
+                        summary: fields.next()?.to_string(),
+                        body: fields.next().unwrap_or_default().trim().to_string(),
+                    })
+                })
+                .collect())
+        }
+    }
 }
 
 impl super::Backend for CommandBackend {
@@ -87,6 +135,54 @@ impl super::Backend for CommandBackend {
         }
     }
 
+    fn find_first_parent(
+        &self,
+        target: &str,
+        skip: usize,
+        until: Option<i64>,
+    ) -> Result<Option<String>, Self::Err> {
+        tracing::trace!("looking for first parent of {target:?} skipping {skip} until {until:?}");
+        let mut cmd = self.cmd();
+        cmd.arg("rev-list")
+            .arg("--first-parent")
+            .arg("--max-count=1")
+            .arg(format!("--skip={skip}"));
+        if let Some(until) = until {
+            cmd.arg(format!("--until=@{until}"));
+        }
+        let output = cmd.arg(target).output()?;
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            tracing::trace!("stdout {stdout:?}");
+            Ok(Some(stdout.trim())
+                .filter(|sha| !sha.is_empty())
+                .map(String::from))
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            tracing::trace!("stderr {stderr:?}");
+            Err(Error::Failed(stderr))
+        }
+    }
+
+    fn merge_base(&self, first: &str, second: &str) -> Result<String, Self::Err> {
+        tracing::trace!("looking for merge base between {first:?} and {second:?}");
+        let output = self
+            .cmd()
+            .arg("merge-base")
+            .arg(first)
+            .arg(second)
+            .output()?;
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            tracing::trace!("stdout {stdout:?}");
+            Ok(stdout.trim().to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            tracing::trace!("stderr {stderr:?}");
+            Err(Error::Failed(stderr))
+        }
+    }
+
     fn rev_parse(&self, range: &str) -> Result<super::RevParse, Self::Err> {
         tracing::trace!("parse revision range {range:?}");
         let output = self.cmd().arg("rev-parse").arg(range).output()?;
@@ -141,6 +237,55 @@ impl super::Backend for CommandBackend {
         }
     }
 
+    fn write_notes<T: serde::Serialize>(
+        &self,
+        note_ref: &NoteRef,
+        notes: &[(String, T)],
+        message: &str,
+    ) -> Result<(), Self::Err> {
+        tracing::trace!("setting {} notes for {note_ref:?}", notes.len());
+        let note_ref = note_ref.to_string();
+        let previous = self
+            .exec(&["rev-parse", "--verify", "-q", note_ref.as_str()])
+            .ok();
+        // the notes are written in a temporary reference and then squashed in a single commit
+        let tmp_ref = format!("{note_ref}-tmp");
+        match previous {
+            Some(ref previous) => {
+                self.exec(&["update-ref", tmp_ref.as_str(), previous.as_str()])?;
+            }
+            None => {
+                let _ = self.exec(&["update-ref", "-d", tmp_ref.as_str()]);
+            }
+        }
+        let result = notes.iter().try_for_each(|(target, value)| {
+            let content = toml::to_string(value)?;
+            self.exec(&[
+                "notes",
+                "--ref",
+                tmp_ref.as_str(),
+                "add",
+                "-f",
+                "-m",
+                content.as_str(),
+                target.as_str(),
+            ])
+            .map(|_| ())
+        });
+        let result = result.and_then(|_| {
+            let tree = format!("{tmp_ref}^{{tree}}");
+            let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+            if let Some(ref previous) = previous {
+                args.extend(["-p", previous.as_str()]);
+            }
+            let commit = self.exec(&args)?;
+            self.exec(&["update-ref", note_ref.as_str(), commit.as_str()])
+                .map(|_| ())
+        });
+        self.exec(&["update-ref", "-d", tmp_ref.as_str()])?;
+        result
+    }
+
     fn remove_note(&self, target: &str, note_ref: &NoteRef) -> Result<(), Self::Err> {
         tracing::trace!("removing note for target {target:?} and {note_ref:?}");
         let output = self
@@ -263,32 +408,41 @@ impl super::Backend for CommandBackend {
     }
 
     fn get_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err> {
+        self.log(&[range])
+    }
+
+    fn get_first_parent_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err> {
+        self.log(&["--first-parent", "--reverse", range])
+    }
+
+    fn get_commit(&self, target: &str) -> Result<Commit, Self::Err> {
+        self.log(&["--max-count=1", target])?
+            .pop()
+            .ok_or_else(|| Error::Failed(format!("unable to find commit {target:?}")))
+    }
+
+    fn is_reachable(&self, target: &str) -> Result<bool, Self::Err> {
+        tracing::trace!("checking if {target:?} is reachable from a branch");
         let output = self
             .cmd()
-            .arg("log")
-            .arg("--format=format:%H:%s")
-            .arg(range)
+            .arg("for-each-ref")
+            .arg("--count=1")
+            .arg("--format=%(refname)")
+            .arg("--contains")
+            .arg(target)
+            .arg("refs/heads")
+            .arg("refs/remotes")
             .output()?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            tracing::error!("something went wrong when getting commits");
+            tracing::error!("something went wrong when looking for references");
             tracing::trace!("stderr {stderr:?}");
             Err(Error::Failed(stderr.into()))
         } else {
             let stdout = String::from_utf8_lossy(&output.stdout);
             tracing::trace!("stdout {stdout:?}");
-            Ok(stdout
-                .split('\n')
-                .map(|item| item.trim())
-                .filter(|item| !item.is_empty())
-                .filter_map(|line| {
-                    line.split_once(':').map(|(sha, summary)| Commit {
-                        sha: sha.to_string(),
-                        summary: summary.to_string(),
-                    })
-                })
-                .collect())
+            Ok(!stdout.trim().is_empty())
         }
     }
 
@@ -310,4 +464,65 @@ impl super::Backend for CommandBackend {
             Ok(PathBuf::from(stdout.trim()))
         }
     }
+
+    fn list_files(&self, pathspec: &str) -> Result<Vec<PathBuf>, Self::Err> {
+        tracing::trace!("listing tracked files matching {pathspec:?}");
+        let pathspec = format!(":(top){pathspec}");
+        let stdout = self.exec(&["ls-files", "-z", "--full-name", "--", pathspec.as_str()])?;
+        tracing::trace!("stdout {stdout:?}");
+        Ok(stdout
+            .split('\0')
+            .filter(|item| !item.is_empty())
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, Self::Err> {
+        tracing::trace!("looking for the current branch");
+        let output = self
+            .cmd()
+            .arg("symbolic-ref")
+            .arg("--quiet")
+            .arg("--short")
+            .arg("HEAD")
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        tracing::trace!("stdout {stdout:?}");
+        if output.status.success() {
+            Ok(Some(stdout.trim().to_string()))
+        } else if output.status.code() == Some(1) {
+            // the head is detached
+            Ok(None)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            tracing::trace!("stderr {stderr:?}");
+            Err(Error::Failed(stderr))
+        }
+    }
+
+    fn is_branch(&self, name: &str) -> Result<bool, Self::Err> {
+        tracing::trace!("checking if {name:?} is a branch");
+        let output = self
+            .cmd()
+            .arg("show-ref")
+            .arg("--verify")
+            .arg("--quiet")
+            .arg(format!("refs/heads/{name}"))
+            .output()?;
+        Ok(output.status.success())
+    }
+
+    fn add_worktree(&self, path: &Path, target: &str) -> Result<(), Self::Err> {
+        tracing::trace!("checking out {target:?} in a worktree at {path:?}");
+        let path = path.to_string_lossy();
+        self.exec(&["worktree", "add", "--detach", path.as_ref(), target])?;
+        Ok(())
+    }
+
+    fn remove_worktree(&self, path: &Path) -> Result<(), Self::Err> {
+        tracing::trace!("removing the worktree at {path:?}");
+        let path = path.to_string_lossy();
+        self.exec(&["worktree", "remove", "--force", path.as_ref()])?;
+        Ok(())
+    }
 }
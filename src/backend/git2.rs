@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::{Backend, Note, NoteRef, REMOTE_METRICS_REF};
 use crate::backend::RevParse;
@@ -139,6 +139,93 @@ impl Git2Backend {
             _ => auth,
         }
     }
+
+    /// Lists the commits of the range, following the first parent from the oldest when asked
+    fn walk_commits(&self, range: &str, first_parent: bool) -> Result<Vec<Commit>, Error> {
+        let mut revwalk = self
+            .repo
+            .revwalk()
+            .map_err(with_git2_error!("unable to lookup commits"))?;
+        let sorting = if first_parent {
+            revwalk
+                .simplify_first_parent()
+                .map_err(with_git2_error!("unable to follow the first parent"))?;
+            git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE
+        } else {
+            git2::Sort::TOPOLOGICAL
+        };
+        revwalk
+            .set_sorting(sorting)
+            .map_err(with_git2_error!("unable to set sorting direction"))?;
+        let revspec = self
+            .repo
+            .revparse(range.as_ref())
+            .map_err(with_git2_error!("unable to parse commit range"))?;
+        if revspec.mode().contains(git2::RevparseMode::SINGLE) {
+            let from = revspec.from().ok_or_else(|| {
+                tracing::error!("unable to get range beginning");
+                Error::Race {
+                    message: "unable to get range beginning: revspec.from is None",
+                }
+            })?;
+            revwalk
+                .push(from.id())
+                .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
+        } else {
+            let from = revspec.from().ok_or_else(|| {
+                tracing::error!("unable to get range beginning");
+                Error::Race {
+                    message: "unable to get range beginning",
+                }
+            })?;
+            let to = revspec.to().ok_or_else(|| {
+                tracing::error!("unable to get range ending");
+                Error::race("unable to get range ending: revspec.to is None")
+            })?;
+            revwalk
+                .push(to.id())
+                .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
+            if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
+                let base = self
+                    .repo
+                    .merge_base(from.id(), to.id())
+                    .map_err(with_git2_error!("unable to get merge base"))?;
+                let o = self
+                    .repo
+                    .find_object(base, Some(git2::ObjectType::Commit))
+                    .map_err(with_git2_error!("unable to get commit"))?;
+                revwalk
+                    .push(o.id())
+                    .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
+            }
+            revwalk
+                .hide(from.id())
+                .map_err(with_git2_error!("unable to hide commit id in revwalk"))?;
+        }
+
+        let mut result = Vec::new();
+        for commit_id in revwalk {
+            let commit_id =
+                commit_id.map_err(with_git2_error!("unable to get commit from revwalk"))?;
+            let commit = self
+                .repo
+                .find_commit(commit_id)
+                .map_err(with_git2_error!("unable to get commit"))?;
+            result.push(to_commit(&commit));
+        }
+
+        Ok(result)
+    }
+}
+
+fn to_commit(commit: &git2::Commit) -> Commit {
+    Commit {
+        sha: commit.id().to_string(),
+        timestamp: commit.time().seconds(),
+        author: commit.author().name().map(String::from).unwrap_or_default(),
+        summary: commit.summary().map(String::from).unwrap_or_default(),
+        body: commit.body().map(String::from).unwrap_or_default(),
+    }
 }
 
 impl Backend for Git2Backend {
@@ -259,6 +346,63 @@ impl Backend for Git2Backend {
             .collect())
     }
 
+    fn write_notes<T: serde::Serialize>(
+        &self,
+        note_ref: &NoteRef,
+        notes: &[(String, T)],
+        message: &str,
+    ) -> Result<(), Self::Err> {
+        tracing::trace!("setting {} notes for {note_ref:?}", notes.len());
+        let note_ref = note_ref.to_string();
+        let sig = self.signature()?;
+        let previous = self
+            .repo
+            .find_reference(&note_ref)
+            .ok()
+            .and_then(|reference| reference.peel_to_commit().ok());
+        // the notes are written in a temporary reference and then squashed in a single commit
+        let tmp_ref = format!("{note_ref}-tmp");
+        match previous {
+            Some(ref commit) => {
+                self.repo
+                    .reference(&tmp_ref, commit.id(), true, "prepare notes")
+                    .map_err(with_git2_error!("unable to create temporary reference"))?;
+            }
+            None => {
+                if let Ok(mut reference) = self.repo.find_reference(&tmp_ref) {
+                    reference
+                        .delete()
+                        .map_err(with_git2_error!("unable to delete temporary reference"))?;
+                }
+            }
+        }
+        for (target, value) in notes {
+            let target_id = self.revision_id(target)?;
+            let note = toml::to_string_pretty(value)?;
+            self.repo
+                .note(&sig, &sig, Some(&tmp_ref), target_id, &note, true)
+                .map_err(with_git2_error!("unable to persist metrics"))?;
+        }
+        let mut tmp = self
+            .repo
+            .find_reference(&tmp_ref)
+            .map_err(with_git2_error!("unable to find temporary reference"))?;
+        let tree = tmp
+            .peel_to_tree()
+            .map_err(with_git2_error!("unable to read notes tree"))?;
+        let parents = previous.iter().collect::<Vec<_>>();
+        let commit_id = self
+            .repo
+            .commit(None, &sig, &sig, message, &tree, &parents)
+            .map_err(with_git2_error!("unable to commit notes"))?;
+        self.repo
+            .reference(&note_ref, commit_id, true, message)
+            .map_err(with_git2_error!("unable to update notes reference"))?;
+        tmp.delete()
+            .map_err(with_git2_error!("unable to delete temporary reference"))?;
+        Ok(())
+    }
+
     fn remove_note(&self, target: &str, note_ref: &NoteRef) -> Result<(), Self::Err> {
         tracing::trace!("removing note for target {target:?} and {note_ref:?}");
         let rev_id = self.revision_id(target)?;
@@ -382,75 +526,93 @@ impl Backend for Git2Backend {
     }
 
     fn get_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err> {
+        self.walk_commits(range, false)
+    }
+
+    fn get_first_parent_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err> {
+        self.walk_commits(range, true)
+    }
+
+    fn get_commit(&self, target: &str) -> Result<Commit, Self::Err> {
+        tracing::trace!("fetching commit {target:?}");
+        let commit = self
+            .repo
+            .revparse_single(target)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(with_git2_error!("unable to get commit"))?;
+        Ok(to_commit(&commit))
+    }
+
+    fn find_first_parent(
+        &self,
+        target: &str,
+        skip: usize,
+        until: Option<i64>,
+    ) -> Result<Option<String>, Self::Err> {
+        tracing::trace!("looking for first parent of {target:?} skipping {skip} until {until:?}");
         let mut revwalk = self
             .repo
             .revwalk()
             .map_err(with_git2_error!("unable to lookup commits"))?;
         revwalk
-            .set_sorting(git2::Sort::TOPOLOGICAL)
-            .map_err(with_git2_error!("unable to set sorting direction"))?;
-        let revspec = self
-            .repo
-            .revparse(range.as_ref())
-            .map_err(with_git2_error!("unable to parse commit range"))?;
-        if revspec.mode().contains(git2::RevparseMode::SINGLE) {
-            let from = revspec.from().ok_or_else(|| {
-                tracing::error!("unable to get range beginning");
-                Error::Race {
-                    message: "unable to get range beginning: revspec.from is None",
-                }
-            })?;
-            revwalk
-                .push(from.id())
-                .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
-        } else {
-            let from = revspec.from().ok_or_else(|| {
-                tracing::error!("unable to get range beginning");
-                Error::Race {
-                    message: "unable to get range beginning",
-                }
-            })?;
-            let to = revspec.to().ok_or_else(|| {
-                tracing::error!("unable to get range ending");
-                Error::race("unable to get range ending: revspec.to is None")
-            })?;
-            revwalk
-                .push(to.id())
-                .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
-            if revspec.mode().contains(git2::RevparseMode::MERGE_BASE) {
-                let base = self
-                    .repo
-                    .merge_base(from.id(), to.id())
-                    .map_err(with_git2_error!("unable to get merge base"))?;
-                let o = self
+            .simplify_first_parent()
+            .map_err(with_git2_error!("unable to follow the first parent"))?;
+        revwalk
+            .push(self.revision_id(target)?)
+            .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
+        let mut remaining = skip;
+        for commit_id in revwalk {
+            let commit_id =
+                commit_id.map_err(with_git2_error!("unable to get commit from revwalk"))?;
+            if let Some(until) = until {
+                let commit = self
                     .repo
-                    .find_object(base, Some(git2::ObjectType::Commit))
+                    .find_commit(commit_id)
                     .map_err(with_git2_error!("unable to get commit"))?;
-                revwalk
-                    .push(o.id())
-                    .map_err(with_git2_error!("unable to push commit id in revwalk"))?;
+                if commit.time().seconds() > until {
+                    continue;
+                }
             }
-            revwalk
-                .hide(from.id())
-                .map_err(with_git2_error!("unable to hide commit id in revwalk"))?;
+            if remaining == 0 {
+                return Ok(Some(commit_id.to_string()));
+            }
+            remaining -= 1;
         }
+        Ok(None)
+    }
 
-        let mut result = Vec::new();
-        for commit_id in revwalk {
-            let commit_id =
-                commit_id.map_err(with_git2_error!("unable to get commit from revwalk"))?;
-            let commit = self
-                .repo
-                .find_commit(commit_id)
-                .map_err(with_git2_error!("unable to get commit"))?;
-            let summary = commit.summary().map(String::from).unwrap_or_default();
-            result.push(Commit {
-                sha: commit_id.to_string(),
-                summary,
-            });
-        }
+    fn merge_base(&self, first: &str, second: &str) -> Result<String, Self::Err> {
+        tracing::trace!("looking for merge base between {first:?} and {second:?}");
+        let first_id = self.revision_id(first)?;
+        let second_id = self.revision_id(second)?;
+        self.repo
+            .merge_base(first_id, second_id)
+            .map(|oid| oid.to_string())
+            .map_err(with_git2_error!("unable to find merge base"))
+    }
 
-        Ok(result)
+    fn is_reachable(&self, target: &str) -> Result<bool, Self::Err> {
+        tracing::trace!("checking if {target:?} is reachable from a branch");
+        let target_id = self.revision_id(target)?;
+        let branches = self
+            .repo
+            .branches(None)
+            .map_err(with_git2_error!("unable to list branches"))?;
+        for branch in branches {
+            let (branch, _) = branch.map_err(with_git2_error!("unable to read branch"))?;
+            let Ok(commit) = branch.get().peel_to_commit() else {
+                continue;
+            };
+            if commit.id() == target_id
+                || self
+                    .repo
+                    .graph_descendant_of(commit.id(), target_id)
+                    .map_err(with_git2_error!("unable to compare commits"))?
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
     }
 
     fn root_path(&self) -> Result<PathBuf, Self::Err> {
@@ -460,4 +622,94 @@ impl Backend for Git2Backend {
             Error::race("unable to find parent directory for .git directory")
         })
     }
+
+    fn list_files(&self, pathspec: &str) -> Result<Vec<PathBuf>, Self::Err> {
+        tracing::trace!("listing tracked files matching {pathspec:?}");
+        let index = self
+            .repo
+            .index()
+            .map_err(with_git2_error!("unable to read index"))?;
+        let spec = git2::Pathspec::new([pathspec]).map_err(with_git2_error!("invalid pathspec"))?;
+        let matches = spec
+            .match_index(&index, git2::PathspecFlags::DEFAULT)
+            .map_err(with_git2_error!("unable to match pathspec"))?;
+        Ok(matches
+            .entries()
+            .map(|entry| PathBuf::from(String::from_utf8_lossy(entry).as_ref()))
+            .collect())
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, Self::Err> {
+        tracing::trace!("looking for the current branch");
+        if self
+            .repo
+            .head_detached()
+            .map_err(with_git2_error!("unable to read head"))?
+        {
+            return Ok(None);
+        }
+        let head = self
+            .repo
+            .head()
+            .map_err(with_git2_error!("unable to read head"))?;
+        Ok(head.shorthand().map(String::from))
+    }
+
+    fn is_branch(&self, name: &str) -> Result<bool, Self::Err> {
+        tracing::trace!("checking if {name:?} is a branch");
+        match self.repo.find_branch(name, git2::BranchType::Local) {
+            Ok(_) => Ok(true),
+            Err(err) if err.code() == git2::ErrorCode::NotFound => Ok(false),
+            Err(err) => Err(Error::git2("unable to find branch", err)),
+        }
+    }
+
+    fn add_worktree(&self, path: &Path, target: &str) -> Result<(), Self::Err> {
+        tracing::trace!("checking out {target:?} in a worktree at {path:?}");
+        let name = worktree_name(path);
+        let commit = self
+            .repo
+            .revparse_single(target)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(with_git2_error!("unable to find commit"))?;
+        // libgit2 can only create a worktree on a branch, removed with the worktree
+        let branch = self
+            .repo
+            .branch(&name, &commit, true)
+            .map_err(with_git2_error!("unable to create worktree branch"))?;
+        let mut options = git2::WorktreeAddOptions::new();
+        options.reference(Some(branch.get()));
+        self.repo
+            .worktree(&name, path, Some(&options))
+            .map_err(with_git2_error!("unable to create worktree"))?;
+        Ok(())
+    }
+
+    fn remove_worktree(&self, path: &Path) -> Result<(), Self::Err> {
+        tracing::trace!("removing the worktree at {path:?}");
+        let name = worktree_name(path);
+        let worktree = self
+            .repo
+            .find_worktree(&name)
+            .map_err(with_git2_error!("unable to find worktree"))?;
+        worktree
+            .prune(Some(
+                git2::WorktreePruneOptions::new()
+                    .valid(true)
+                    .working_tree(true),
+            ))
+            .map_err(with_git2_error!("unable to remove worktree"))?;
+        self.repo
+            .find_branch(&name, git2::BranchType::Local)
+            .and_then(|mut branch| branch.delete())
+            .map_err(with_git2_error!("unable to remove worktree branch"))?;
+        Ok(())
+    }
+}
+
+/// Name of the worktree and its branch, based on the directory name
+fn worktree_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "git-metrics".into())
 }
@@ -1,11 +1,13 @@
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 use super::{NoteRef, RevParse};
 use crate::entity::config::Config;
 use crate::entity::git::Commit;
+use crate::entity::selector::glob_matches;
 
 #[derive(Debug)]
 pub(crate) struct Error {
@@ -38,10 +40,15 @@ pub(crate) struct MockBackend(Rc<MockBackendInner>);
 #[derive(Debug)]
 pub(crate) struct MockBackendInner {
     temp_dir: tempfile::TempDir,
-    commits: Vec<Commit>,
+    commits: RefCell<Vec<Commit>>,
     notes: RefCell<HashMap<String, String>>,
     rev_parses: RefCell<HashMap<String, RevParse>>,
     rev_lists: RefCell<HashMap<String, Vec<String>>>,
+    merge_bases: RefCell<HashMap<(String, String), String>>,
+    unreachable: RefCell<HashSet<String>>,
+    branch: RefCell<Option<String>>,
+    branches: RefCell<HashSet<String>>,
+    files: RefCell<Vec<PathBuf>>,
 }
 
 impl Default for MockBackendInner {
@@ -52,6 +59,11 @@ impl Default for MockBackendInner {
             notes: Default::default(),
             rev_parses: Default::default(),
             rev_lists: Default::default(),
+            merge_bases: Default::default(),
+            unreachable: Default::default(),
+            branch: Default::default(),
+            branches: Default::default(),
+            files: Default::default(),
         }
     }
 }
@@ -82,11 +94,47 @@ impl MockBackend {
         self.0.rev_parses.borrow_mut().insert(target.into(), item);
     }
 
+    pub(crate) fn set_merge_base(
+        &self,
+        first: impl Into<String>,
+        second: impl Into<String>,
+        base: impl Into<String>,
+    ) {
+        self.0
+            .merge_bases
+            .borrow_mut()
+            .insert((first.into(), second.into()), base.into());
+    }
+
+    pub(crate) fn set_unreachable(&self, target: impl Into<String>) {
+        self.0.unreachable.borrow_mut().insert(target.into());
+    }
+
+    pub(crate) fn set_commits(&self, commits: impl IntoIterator<Item = Commit>) {
+        self.0.commits.replace(commits.into_iter().collect());
+    }
+
+    pub(crate) fn set_current_branch(&self, name: impl Into<String>) {
+        self.0.branch.replace(Some(name.into()));
+    }
+
+    pub(crate) fn set_branch(&self, name: impl Into<String>) {
+        self.0.branches.borrow_mut().insert(name.into());
+    }
+
     pub(crate) fn set_config(&self, input: &str) {
         let file = self.0.temp_dir.path().join(".git-metrics.toml");
         std::fs::write(file, input).unwrap();
     }
 
+    /// Writes a tracked file, relative to the root
+    pub(crate) fn set_file(&self, path: &str, input: &str) {
+        let file = self.0.temp_dir.path().join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, input).unwrap();
+        self.0.files.borrow_mut().push(PathBuf::from(path));
+    }
+
     pub(crate) fn get_config(&self) -> Config {
         let file = self.0.temp_dir.path().join(".git-metrics.toml");
         Config::from_path(&file).unwrap()
@@ -106,6 +154,32 @@ impl super::Backend for MockBackend {
             .unwrap_or_default())
     }
 
+    fn find_first_parent(
+        &self,
+        target: &str,
+        skip: usize,
+        until: Option<i64>,
+    ) -> Result<Option<String>, Self::Err> {
+        // the revision lists are considered as the first parents
+        let mut found = Vec::new();
+        for sha in self.rev_list(target)? {
+            let timestamp = self.get_commit(&sha)?.timestamp;
+            if until.is_none_or(|until| timestamp <= until) {
+                found.push(sha);
+            }
+        }
+        Ok(found.into_iter().nth(skip))
+    }
+
+    fn merge_base(&self, first: &str, second: &str) -> Result<String, Self::Err> {
+        self.0
+            .merge_bases
+            .borrow()
+            .get(&(first.to_string(), second.to_string()))
+            .cloned()
+            .ok_or_else(|| Error::new("no merge base"))
+    }
+
     fn rev_parse(&self, range: &str) -> Result<super::RevParse, Self::Err> {
         self.0
             .rev_parses
@@ -115,8 +189,33 @@ impl super::Backend for MockBackend {
             .ok_or_else(|| Error::new("invalid range for rev_parse"))
     }
 
-    fn list_notes(&self, _note_ref: &NoteRef) -> Result<Vec<super::Note>, Self::Err> {
-        todo!()
+    fn list_notes(&self, note_ref: &NoteRef) -> Result<Vec<super::Note>, Self::Err> {
+        let suffix = format!("/{note_ref}");
+        let mut notes = self
+            .0
+            .notes
+            .borrow()
+            .keys()
+            .filter_map(|key| key.strip_suffix(suffix.as_str()))
+            .map(|commit_id| super::Note {
+                note_id: String::new(),
+                commit_id: commit_id.to_string(),
+            })
+            .collect::<Vec<_>>();
+        notes.sort_by(|first, second| first.commit_id.cmp(&second.commit_id));
+        Ok(notes)
+    }
+
+    fn write_notes<T: serde::Serialize>(
+        &self,
+        note_ref: &NoteRef,
+        notes: &[(String, T)],
+        _message: &str,
+    ) -> Result<(), Self::Err> {
+        for (target, value) in notes {
+            self.write_note(target, note_ref, value)?;
+        }
+        Ok(())
     }
 
     fn remove_note(&self, target: &str, note_ref: &NoteRef) -> Result<(), Self::Err> {
@@ -161,11 +260,66 @@ impl super::Backend for MockBackend {
         Ok(())
     }
 
-    fn get_commits(&self, _range: &str) -> Result<Vec<crate::entity::git::Commit>, Self::Err> {
-        Ok(self.0.commits.clone())
+    fn get_commits(&self, _range: &str) -> Result<Vec<Commit>, Self::Err> {
+        Ok(self.0.commits.borrow().clone())
+    }
+
+    fn get_first_parent_commits(&self, _range: &str) -> Result<Vec<Commit>, Self::Err> {
+        Ok(self.0.commits.borrow().iter().rev().cloned().collect())
+    }
+
+    fn get_commit(&self, target: &str) -> Result<Commit, Self::Err> {
+        // the commits without message don't need to be defined
+        Ok(self
+            .0
+            .commits
+            .borrow()
+            .iter()
+            .find(|commit| commit.sha == target)
+            .cloned()
+            .unwrap_or_else(|| Commit {
+                sha: target.to_string(),
+                summary: String::new(),
+                timestamp: 0,
+                author: String::new(),
+                body: String::new(),
+            }))
+    }
+
+    fn is_reachable(&self, target: &str) -> Result<bool, Self::Err> {
+        Ok(!self.0.unreachable.borrow().contains(target))
     }
 
-    fn root_path(&self) -> Result<std::path::PathBuf, Self::Err> {
+    fn root_path(&self) -> Result<PathBuf, Self::Err> {
         Ok(self.0.temp_dir.path().to_path_buf())
     }
+
+    fn list_files(&self, pathspec: &str) -> Result<Vec<PathBuf>, Self::Err> {
+        Ok(self
+            .0
+            .files
+            .borrow()
+            .iter()
+            .filter(|path| glob_matches(pathspec, &path.to_string_lossy()))
+            .cloned()
+            .collect())
+    }
+
+    fn current_branch(&self) -> Result<Option<String>, Self::Err> {
+        Ok(self.0.branch.borrow().clone())
+    }
+
+    fn is_branch(&self, name: &str) -> Result<bool, Self::Err> {
+        Ok(self.0.branches.borrow().contains(name))
+    }
+
+    fn add_worktree(&self, path: &std::path::Path, _target: &str) -> Result<(), Self::Err> {
+        std::fs::create_dir_all(path).map_err(|_| Error::new("unable to create worktree"))?;
+        Ok(())
+    }
+
+    fn remove_worktree(&self, path: &std::path::Path) -> Result<(), Self::Err> {
+        std::fs::remove_dir_all(path).map_err(|_| Error::new("unable to remove worktree"))?;
+        Ok(())
+    }
 }
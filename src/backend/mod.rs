@@ -1,5 +1,5 @@
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "impl-command")]
 mod command;
@@ -91,6 +91,17 @@ pub(crate) trait Backend {
 
     fn rev_parse(&self, range: &str) -> Result<RevParse, Self::Err>;
     fn rev_list(&self, range: &str) -> Result<Vec<String>, Self::Err>;
+    /// Walks the first parents of the target, most recent first, and returns the commit
+    /// after skipping the given number of them, only considering the ones committed
+    /// at or before the timestamp when given
+    fn find_first_parent(
+        &self,
+        target: &str,
+        skip: usize,
+        until: Option<i64>,
+    ) -> Result<Option<String>, Self::Err>;
+    /// Finds the best common ancestor of both commits
+    fn merge_base(&self, first: &str, second: &str) -> Result<String, Self::Err>;
     fn pull(&self, remote: &str, local_ref: &NoteRef) -> Result<(), Self::Err>;
     fn push(&self, remote: &str, local_ref: &NoteRef) -> Result<(), Self::Err>;
     fn read_note<T: serde::de::DeserializeOwned>(
@@ -104,8 +115,31 @@ pub(crate) trait Backend {
         note_ref: &NoteRef,
         value: &T,
     ) -> Result<(), Self::Err>;
+    /// Writes several notes at once, creating a single commit in the notes reference
+    fn write_notes<T: serde::Serialize>(
+        &self,
+        note_ref: &NoteRef,
+        notes: &[(String, T)],
+        message: &str,
+    ) -> Result<(), Self::Err>;
     fn remove_note(&self, target: &str, note_ref: &NoteRef) -> Result<(), Self::Err>;
     fn list_notes(&self, note_ref: &NoteRef) -> Result<Vec<Note>, Self::Err>;
     fn get_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err>;
+    /// Lists the commits of the range following the first parent, from the oldest
+    fn get_first_parent_commits(&self, range: &str) -> Result<Vec<Commit>, Self::Err>;
+    /// Reads a single commit, without walking its history
+    fn get_commit(&self, target: &str) -> Result<Commit, Self::Err>;
+    /// Checks if the target can be reached from any local or remote branch
+    fn is_reachable(&self, target: &str) -> Result<bool, Self::Err>;
     fn root_path(&self) -> Result<PathBuf, Self::Err>;
+    /// Tracked files matching the pathspec, relative to the root of the repository
+    fn list_files(&self, pathspec: &str) -> Result<Vec<PathBuf>, Self::Err>;
+    /// Name of the checked out branch, `None` when the head is detached
+    fn current_branch(&self) -> Result<Option<String>, Self::Err>;
+    /// Checks if the name is a local branch
+    fn is_branch(&self, name: &str) -> Result<bool, Self::Err>;
+    /// Checks out the target in a new worktree, at the given path
+    fn add_worktree(&self, path: &Path, target: &str) -> Result<(), Self::Err>;
+    /// Removes a worktree created with `add_worktree`, with its files
+    fn remove_worktree(&self, path: &Path) -> Result<(), Self::Err>;
 }
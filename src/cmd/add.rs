@@ -6,6 +6,9 @@ use crate::ExitCode;
 /// Add a metric related to the target
 #[derive(clap::Parser, Debug, Default)]
 pub struct CommandAdd {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
     /// Commit target, default to HEAD
     #[clap(long, short, default_value = "HEAD")]
     target: String,
@@ -16,6 +19,8 @@ pub struct CommandAdd {
     tag: Vec<Tag>,
     /// Value of the metric
     value: f64,
+    #[clap(flatten)]
+    replace: super::ReplaceOptions,
 }
 
 impl super::Executor for CommandAdd {
@@ -37,7 +42,11 @@ impl super::Executor for CommandAdd {
             value: self.value,
         };
         let opts = crate::service::add::Options {
+            remote: self.remote,
             target: self.target,
+            replace: self
+                .replace
+                .selectors(std::iter::once(metric.header.name.as_str())),
         };
 
         Service::new(backend).add(metric, &opts)?;
@@ -131,6 +140,71 @@ name = "my-metric"
 value = 12.34
 
 [changes.tags]
+"#
+            ))
+        );
+    }
+
+    #[test]
+    fn should_replace_metrics_with_same_name_and_tag() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "HEAD",
+            crate::backend::NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "my-metric"
+value = 1.0
+tags = { os = "linux", arch = "amd64" }
+
+[[changes]]
+action = "add"
+name = "my-metric"
+value = 2.0
+tags = { os = "macos", arch = "amd64" }
+"#,
+        );
+
+        let code = crate::Args::parse_from([
+            "_",
+            "add",
+            "my-metric",
+            "--tag",
+            "os: linux",
+            "--replace",
+            "--replace-tag",
+            "os: linux",
+            "12.34",
+        ])
+        .command
+        .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success());
+        assert!(stdout.is_empty());
+        assert!(stderr.is_empty());
+
+        assert_eq!(
+            repo.get_note("HEAD", crate::backend::NoteRef::Changes),
+            Some(String::from(
+                r#"[[changes]]
+action = "add"
+name = "my-metric"
+value = 2.0
+
+[changes.tags]
+os = "macos"
+arch = "amd64"
+
+[[changes]]
+action = "add"
+name = "my-metric"
+value = 12.34
+
+[changes.tags]
+os = "linux"
 "#
             ))
         );
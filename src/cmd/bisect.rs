@@ -0,0 +1,173 @@
+use super::format::text::PrettyTextMetricHeader;
+use super::prelude::{PrettyWriter, Tag};
+use crate::backend::Backend;
+use crate::entity::selector::MetricSelector;
+use crate::formatter::difference::ShortTextComparison;
+use crate::service::Service;
+use crate::ExitCode;
+
+const TAB: &str = "    ";
+
+/// Find the commits introducing the largest changes of a metric
+///
+/// The commits between the good and the bad commits are walked from the oldest,
+/// comparing the recorded values of each commit with the previous ones.
+/// Only the first parents are followed, a merged branch is measured through its merge commit.
+///
+///     git metrics bisect --good v1.0.0 binary-size
+///
+/// The commits without recorded values can be measured by executing a command
+/// in a temporary worktree, printing metrics in the format of the `show` command.
+///
+///     git metrics bisect --good v1.0.0 binary-size -- ./measure.sh
+#[derive(clap::Parser, Debug)]
+pub struct CommandBisect {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+    /// Commit known to be before the regression
+    #[clap(long)]
+    good: String,
+    /// Commit known to have the regression, default to HEAD
+    #[clap(long, default_value = "HEAD")]
+    bad: String,
+    /// Only consider the metrics having this tag
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// Number of changes to display
+    #[clap(long, default_value = "5")]
+    limit: usize,
+    /// Name of the metrics to follow
+    ///
+    /// The name can contain `*` to match any sequence of characters and `?` to match a single character.
+    name: String,
+    /// Command collecting the metrics of the commits without recorded values
+    #[cfg(feature = "collector-command")]
+    #[clap(last = true)]
+    command: Vec<String>,
+}
+
+impl CommandBisect {
+    #[cfg(feature = "collector-command")]
+    fn exec(&self) -> Option<&[String]> {
+        Some(self.command.as_slice()).filter(|command| !command.is_empty())
+    }
+
+    #[cfg(not(feature = "collector-command"))]
+    fn exec(&self) -> Option<&[String]> {
+        None
+    }
+}
+
+impl super::Executor for CommandBisect {
+    #[tracing::instrument(name = "bisect", skip_all, fields(good = self.good.as_str(), bad = self.bad.as_str(), name = self.name.as_str()))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let selector = MetricSelector::new(self.name.as_str()).with_tags(
+            self.tag
+                .iter()
+                .map(|tag| (tag.name.clone(), tag.value.clone()))
+                .collect(),
+        );
+        let svc = Service::new(backend);
+        let config = svc.open_config()?;
+        let result = svc.bisect(
+            &selector,
+            &crate::service::bisect::Options {
+                remote: self.remote.as_str(),
+                good: self.good.as_str(),
+                bad: self.bad.as_str(),
+                limit: self.limit,
+                exec: self.exec(),
+            },
+        )?;
+        let sha_style = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Yellow);
+        let dimmed = nu_ansi_term::Style::new().dimmed();
+        for step in result.steps.iter() {
+            stdout.write_str("* ")?;
+            stdout.set_style(sha_style.prefix())?;
+            stdout.write_str(step.commit.short_sha())?;
+            stdout.set_style(sha_style.suffix())?;
+            writeln!(stdout, " {}", step.commit.summary)?;
+            let formatter = config.formatter(step.header.name.as_str());
+            stdout.write_str(TAB)?;
+            stdout.write_element(PrettyTextMetricHeader::new(&step.header))?;
+            write!(
+                stdout,
+                " {}",
+                ShortTextComparison::new(&formatter, &step.comparison)
+            )?;
+            if step.collected {
+                stdout.set_style(dimmed.prefix())?;
+                stdout.write_str(" (collected)")?;
+                stdout.set_style(dimmed.suffix())?;
+            }
+            writeln!(stdout)?;
+        }
+        if result.steps.is_empty() {
+            writeln!(stdout, "no change found")?;
+        }
+        if !result.unmeasured.is_empty() {
+            stdout.set_style(dimmed.prefix())?;
+            write!(
+                stdout,
+                "{} commit(s) without recorded values",
+                result.unmeasured.len()
+            )?;
+            stdout.set_style(dimmed.suffix())?;
+            writeln!(stdout)?;
+        }
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::git::Commit;
+
+    #[test]
+    fn should_display_largest_changes() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_commits(["aaaaaaa1", "bbbbbbb2", "ccccccc3"].map(|sha| Commit {
+            sha: sha.into(),
+            summary: format!("commit {sha}"),
+            timestamp: 0,
+            author: String::new(),
+            body: String::new(),
+        }));
+        for (sha, value) in [("v1", 100.0), ("ccccccc3", 110.0), ("aaaaaaa1", 200.0)] {
+            repo.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"size\"\ntags = {{ os = \"linux\" }}\nvalue = {value:?}\n"),
+            );
+        }
+
+        let code = crate::Args::parse_from(["_", "bisect", "--good", "v1", "size"])
+            .command
+            .execute(repo, false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success(), "{}", String::from_utf8_lossy(&stderr));
+        assert!(stderr.is_empty());
+        let stdout = String::from_utf8_lossy(&stdout);
+        similar_asserts::assert_eq!(
+            stdout,
+            r#"* aaaaaaa commit aaaaaaa1
+    size{os="linux"} 110.00 => 200.00 Δ +90.00 (+81.82 %)
+* ccccccc commit ccccccc3
+    size{os="linux"} 100.00 => 110.00 Δ +10.00 (+10.00 %)
+1 commit(s) without recorded values
+"#
+        );
+    }
+}
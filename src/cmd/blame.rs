@@ -0,0 +1,162 @@
+use super::format::text::PrettyTextMetricHeader;
+use super::prelude::{PrettyWriter, Tag};
+use crate::backend::Backend;
+use crate::entity::selector::MetricSelector;
+use crate::formatter::difference::TextDelta;
+use crate::service::Service;
+use crate::ExitCode;
+
+const TAB: &str = "    ";
+
+/// List the commits changing the value of a metric
+///
+/// Only the first parents are followed, the changes of a merged branch are listed
+/// on its merge commit.
+///
+/// The output should be something like
+///
+///     * aaaaaaa Add the new parser (Alice)
+///         binary-size{platform.os="linux"} +96.01 kiB (+2.72 %)
+#[derive(clap::Parser, Debug, Default)]
+pub struct CommandBlame {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+    /// Commit range, default to HEAD
+    ///
+    /// Can use ranges like HEAD~20..HEAD
+    #[clap(long, short, default_value = "HEAD")]
+    target: String,
+    /// Only consider the metrics having this tag
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// Sort the changes by magnitude, the largest first
+    #[clap(long)]
+    sort: bool,
+    /// Number of changes to display
+    #[clap(long)]
+    limit: Option<usize>,
+    /// Name of the metrics to follow
+    ///
+    /// The name can contain `*` to match any sequence of characters and `?` to match a single character.
+    name: String,
+}
+
+impl super::Executor for CommandBlame {
+    #[tracing::instrument(name = "blame", skip_all, fields(target = self.target.as_str(), name = self.name.as_str()))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let selector = MetricSelector::new(self.name).with_tags(
+            self.tag
+                .into_iter()
+                .map(|tag| (tag.name, tag.value))
+                .collect(),
+        );
+        let svc = Service::new(backend);
+        let config = svc.open_config()?;
+        let result = svc.blame(
+            &selector,
+            &crate::service::blame::Options {
+                remote: self.remote.as_str(),
+                target: self.target.as_str(),
+                sort: self.sort,
+                limit: self.limit,
+            },
+        )?;
+        let sha_style = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Yellow);
+        let dimmed = nu_ansi_term::Style::new().dimmed();
+        for step in result.steps.iter() {
+            stdout.write_str("* ")?;
+            stdout.set_style(sha_style.prefix())?;
+            stdout.write_str(step.commit.short_sha())?;
+            stdout.set_style(sha_style.suffix())?;
+            write!(stdout, " {} ", step.commit.summary)?;
+            stdout.set_style(dimmed.prefix())?;
+            write!(stdout, "({})", step.commit.author)?;
+            stdout.set_style(dimmed.suffix())?;
+            writeln!(stdout)?;
+            let formatter = config.formatter(step.header.name.as_str());
+            stdout.write_str(TAB)?;
+            stdout.write_element(PrettyTextMetricHeader::new(&step.header))?;
+            match (step.comparison.delta(), step.comparison.current()) {
+                (Some(delta), _) => writeln!(
+                    stdout,
+                    " {}",
+                    TextDelta::new(&formatter.with_force_sign(true), delta)
+                )?,
+                (None, Some(current)) => writeln!(stdout, " {} (new)", formatter.format(current))?,
+                (None, None) => writeln!(stdout)?,
+            }
+        }
+        if result.steps.is_empty() {
+            writeln!(stdout, "no change found")?;
+        }
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::git::Commit;
+
+    #[test_case::test_case(&[], r#"* aaaaaaa commit aaaaaaa1 (Bob)
+    size{os="linux"} +30.00 (+25.00 %)
+* ccccccc commit ccccccc3 (Alice)
+    size{os="linux"} +20.00 (+20.00 %)
+"#; "latest first")]
+    #[test_case::test_case(&["--sort", "--limit", "1"], r#"* aaaaaaa commit aaaaaaa1 (Bob)
+    size{os="linux"} +30.00 (+25.00 %)
+"#; "sorted with limit")]
+    fn should_display_changing_commits(args: &[&str], expected: &str) {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_commits(
+            [
+                ("aaaaaaa1", "Bob"),
+                ("bbbbbbb2", "Alice"),
+                ("ccccccc3", "Alice"),
+            ]
+            .map(|(sha, author)| Commit {
+                sha: sha.into(),
+                summary: format!("commit {sha}"),
+                timestamp: 0,
+                author: author.into(),
+                body: String::new(),
+            }),
+        );
+        for (sha, value) in [
+            ("v1", 100.0),
+            ("ccccccc3", 120.0),
+            ("bbbbbbb2", 120.0),
+            ("aaaaaaa1", 150.0),
+        ] {
+            repo.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"size\"\ntags = {{ os = \"linux\" }}\nvalue = {value:?}\n"),
+            );
+        }
+
+        let code = crate::Args::parse_from(
+            ["_", "blame", "--target", "v1..HEAD", "size"]
+                .into_iter()
+                .chain(args.iter().copied()),
+        )
+        .command
+        .execute(repo, false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success(), "{}", String::from_utf8_lossy(&stderr));
+        assert!(stderr.is_empty());
+        let stdout = String::from_utf8_lossy(&stdout);
+        similar_asserts::assert_eq!(stdout, expected);
+    }
+}
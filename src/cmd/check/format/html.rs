@@ -6,6 +6,7 @@ use crate::entity::config::Config;
 use crate::formatter::metric::TextMetricHeader;
 use crate::formatter::percent::TextPercent;
 use crate::formatter::rule::TextRule;
+use crate::formatter::value::ValueFormatter;
 
 fn empty<W: WriterExt>(buf: Buffer<W, Body<'_>>) -> Buffer<W, Body<'_>> {
     buf
@@ -64,7 +65,7 @@ impl<'e> MetricCheckTable<'e> {
         &self,
         buf: Buffer<W, Body<'a>>,
         check: &RuleCheck,
-        formatter: &human_number::Formatter<'_>,
+        formatter: &ValueFormatter<'_>,
     ) -> Buffer<W, Body<'a>> {
         buf.cond(
             check.status.is_failed()
@@ -93,7 +94,12 @@ impl<'e> MetricCheckTable<'e> {
                 .content(|buf| buf.raw(check.status.status().emoji()))
                 .node("td")
                 .attr(("align", "left"))
-                .content(|buf| buf.raw(TextMetricHeader::new(&check.diff.header)))
+                .content(|buf| {
+                    buf.raw(TextMetricHeader::new(&check.diff.header))
+                        .optional(check.aggregate.as_ref(), |buf, aggregate| {
+                            buf.raw(format!(" ({aggregate})"))
+                        })
+                })
                 .node("td")
                 .attr(("align", "right"))
                 .content(|buf| {
@@ -14,8 +14,22 @@ impl<'a> MarkdownFormatter<'a> {
         &self,
         res: &CheckList,
         config: &Config,
-        stdout: W,
+        mut stdout: W,
     ) -> std::io::Result<W> {
+        if let Some(baseline) = res.baseline.as_deref() {
+            writeln!(&mut stdout, "Compared with `{baseline}`.")?;
+            writeln!(&mut stdout)?;
+        }
+        if !res.overrides.is_empty() {
+            let overrides = res
+                .overrides
+                .iter()
+                .map(|item| format!("`{item}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(&mut stdout, "Overrides applied for {overrides}.")?;
+            writeln!(&mut stdout)?;
+        }
         Ok(super::html::MetricCheckTable::new(self.params, config, &res.list).render(stdout))
     }
 }
@@ -16,6 +16,7 @@ impl Status {
             Status::Failed => "[FAILURE]",
             Status::Skip => "[SKIP]",
             Status::Success => "[SUCCESS]",
+            Status::Waived => "[WAIVED]",
         }
     }
 
@@ -30,6 +31,9 @@ impl Status {
             Status::Success => nu_ansi_term::Style::new()
                 .bold()
                 .fg(nu_ansi_term::Color::Green),
+            Status::Waived => nu_ansi_term::Style::new()
+                .bold()
+                .fg(nu_ansi_term::Color::Yellow),
         }
     }
 
@@ -38,6 +42,7 @@ impl Status {
             Status::Failed => "failed",
             Status::Skip => "skip",
             Status::Success => "check",
+            Status::Waived => "waived",
         }
     }
 }
@@ -1,12 +1,11 @@
-use human_number::Formatter;
-
 use crate::cmd::format::text::{PrettyTextMetricHeader, TAB};
 use crate::cmd::prelude::{PrettyDisplay, PrettyWriter};
 use crate::entity::check::{CheckList, MetricCheck, RuleCheck, Status};
 use crate::entity::config::Config;
 use crate::formatter::difference::ShortTextComparison;
-use crate::formatter::metric::TextMetricTags;
+use crate::formatter::metric::TextTagMatchers;
 use crate::formatter::rule::TextRule;
+use crate::formatter::value::ValueFormatter;
 
 struct TextStatus {
     value: Status,
@@ -62,7 +61,7 @@ impl<'a> TextFormatter<'a> {
     fn format_check<W: PrettyWriter>(
         &self,
         check: &RuleCheck,
-        numeric_formatter: &Formatter<'_>,
+        numeric_formatter: &ValueFormatter<'_>,
         stdout: &mut W,
     ) -> std::io::Result<()> {
         match check.status {
@@ -81,12 +80,18 @@ impl<'a> TextFormatter<'a> {
     fn format_metric<W: PrettyWriter>(
         &self,
         item: &MetricCheck,
-        numeric_formatter: Formatter<'_>,
+        numeric_formatter: ValueFormatter<'_>,
         stdout: &mut W,
     ) -> std::io::Result<()> {
         stdout.write_element(TextStatus::new(item.status.status()))?;
         stdout.write_str(" ")?;
         stdout.write_element(PrettyTextMetricHeader::new(&item.diff.header))?;
+        if let Some(aggregate) = item.aggregate.as_ref() {
+            let style = nu_ansi_term::Style::new().dimmed();
+            stdout.set_style(style.prefix())?;
+            write!(stdout, " ({aggregate})")?;
+            stdout.set_style(style.suffix())?;
+        }
         stdout.write_str(" ")?;
         stdout.write_element(ShortTextComparison::new(
             &numeric_formatter,
@@ -106,7 +111,7 @@ impl<'a> TextFormatter<'a> {
                 writeln!(
                     stdout,
                     "{TAB}# {name:?} matching tags {}",
-                    TextMetricTags::new(&subset.matching)
+                    TextTagMatchers::new(&subset.matching)
                 )?;
                 stdout.set_style(subset_style.suffix())?;
                 for check in subset.checks.iter() {
@@ -123,8 +128,20 @@ impl<'a> TextFormatter<'a> {
         config: &Config,
         mut stdout: W,
     ) -> std::io::Result<W> {
+        if let Some(baseline) = res.baseline.as_deref() {
+            let style = nu_ansi_term::Style::new().dimmed();
+            stdout.set_style(style.prefix())?;
+            writeln!(stdout, "compared with {baseline}")?;
+            stdout.set_style(style.suffix())?;
+        }
+        for item in res.overrides.iter() {
+            let style = nu_ansi_term::Style::new().dimmed();
+            stdout.set_style(style.prefix())?;
+            writeln!(stdout, "override applied for {item}")?;
+            stdout.set_style(style.suffix())?;
+        }
         for entry in res.list.iter() {
-            let formatter: Formatter = config.formatter(entry.diff.header.name.as_str());
+            let formatter: ValueFormatter = config.formatter(entry.diff.header.name.as_str());
             self.format_metric(entry, formatter, &mut stdout)?;
         }
         Ok(stdout)
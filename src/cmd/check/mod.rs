@@ -1,5 +1,6 @@
 use super::prelude::PrettyWriter;
 use crate::backend::Backend;
+use crate::entity::baseline::Baseline;
 use crate::service::Service;
 use crate::ExitCode;
 
@@ -20,6 +21,17 @@ pub struct CommandCheck {
     /// Show the skipped rules
     #[clap(long)]
     show_skipped_rules: bool,
+    /// Commit to compare with, overriding the one from the configuration
+    ///
+    /// Can be merge-base:<ref>, ref:<ref> or latest-with-metrics:<ref>
+    #[clap(long)]
+    baseline: Option<Baseline>,
+    /// Branch used to select the overrides of the configuration
+    ///
+    /// Default to the branch named by the target, or to the current branch. It should be set
+    /// when checking a detached HEAD, like in most CI jobs.
+    #[clap(long)]
+    branch: Option<String>,
     /// Commit range, default to HEAD
     ///
     /// Can use ranges like HEAD~2..HEAD
@@ -35,12 +47,22 @@ impl super::Executor for CommandCheck {
         stdout: Out,
     ) -> Result<crate::ExitCode, crate::service::Error> {
         let svc = Service::new(backend);
+        // the configured metrics are only compared with the history by `config validate`
+        let diagnostics = svc.validate_config(None)?;
+        if diagnostics.iter().any(|item| item.is_error()) {
+            return Err(crate::service::Error::InvalidConfig(diagnostics));
+        }
+        for item in diagnostics.iter() {
+            tracing::warn!("{item}");
+        }
         let config = svc.open_config()?;
         let checklist = svc.check(
             &config,
             &crate::service::check::Options {
                 remote: self.remote.as_str(),
                 target: self.target.as_str(),
+                baseline: self.baseline.as_ref(),
+                branch: self.branch.as_deref(),
             },
         )?;
 
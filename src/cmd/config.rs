@@ -0,0 +1,136 @@
+use super::prelude::PrettyWriter;
+use crate::backend::Backend;
+use crate::entity::validation::Severity;
+use crate::service::Service;
+use crate::ExitCode;
+
+impl Severity {
+    fn style(&self) -> nu_ansi_term::Style {
+        match self {
+            Self::Error => nu_ansi_term::Style::new()
+                .bold()
+                .fg(nu_ansi_term::Color::Red),
+            Self::Warning => nu_ansi_term::Style::new()
+                .bold()
+                .fg(nu_ansi_term::Color::Yellow),
+        }
+    }
+}
+
+/// Validate the configuration against the recorded metrics
+#[derive(clap::Parser, Debug, Default)]
+pub struct CommandConfigValidate {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+}
+
+impl super::Executor for CommandConfigValidate {
+    #[tracing::instrument(name = "config-validate", skip_all)]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let svc = Service::new(backend);
+        let diagnostics = svc.validate_config(Some(&self.remote))?;
+        for item in diagnostics.iter() {
+            let style = item.severity.style();
+            stdout.set_style(style.prefix())?;
+            stdout.write_str(item.severity.label())?;
+            stdout.set_style(style.suffix())?;
+            writeln!(stdout, ": {item}")?;
+        }
+        if diagnostics.iter().any(|item| item.is_error()) {
+            Ok(ExitCode::Failure)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum ConfigCommand {
+    Validate(CommandConfigValidate),
+}
+
+/// Manage the git-metrics configuration
+#[derive(clap::Parser, Debug)]
+pub struct CommandConfig {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+impl super::Executor for CommandConfig {
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        match self.command {
+            ConfigCommand::Validate(inner) => inner.execute(backend, stdout),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::CommandConfig;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::cmd::prelude::BasicWriter;
+    use crate::cmd::Executor;
+    use crate::ExitCode;
+
+    #[test]
+    fn should_report_invalid_config() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[metrics.first]
+rule = []
+"#,
+        );
+        let mut stdout = Vec::<u8>::new();
+        let code = CommandConfig::parse_from(["_", "validate"])
+            .execute(backend, BasicWriter::from(&mut stdout))
+            .unwrap();
+        assert!(matches!(code, ExitCode::Failure));
+        let stdout = String::from_utf8_lossy(&stdout);
+        similar_asserts::assert_eq!(
+            stdout,
+            "error: .git-metrics.toml: line 2, column 1: unknown field `rule`, expected one of `description`, `aliases`, `required`, `rules`, `subsets`, `unit`\n"
+        );
+    }
+
+    #[test]
+    fn should_succeed_with_warnings() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "second"
+tags = {}
+value = 80.0
+"#,
+        );
+        let mut stdout = Vec::<u8>::new();
+        let code = CommandConfig::parse_from(["_", "validate"])
+            .execute(backend, BasicWriter::from(&mut stdout))
+            .unwrap();
+        assert!(matches!(code, ExitCode::Success));
+        let stdout = String::from_utf8_lossy(&stdout);
+        similar_asserts::assert_eq!(
+            stdout,
+            "warning: .git-metrics.toml: line 1, column 11: metrics.first: no metric has been recorded with this name\n"
+        );
+    }
+}
@@ -1,9 +1,8 @@
-use human_number::Formatter;
-
 use crate::entity::config::Config;
 use crate::entity::difference::{Comparison, MetricDiff, MetricDiffList};
 use crate::formatter::difference::TextDelta;
 use crate::formatter::metric::TextMetricHeader;
+use crate::formatter::value::ValueFormatter;
 
 pub struct MarkdownFormatter<'a>(pub &'a super::Params);
 
@@ -11,7 +10,7 @@ impl MarkdownFormatter<'_> {
     fn format_entry<W: std::io::Write>(
         &self,
         entry: &MetricDiff,
-        formatter: &Formatter<'_>,
+        formatter: &ValueFormatter<'_>,
         stdout: &mut W,
     ) -> std::io::Result<()> {
         match &entry.comparison {
@@ -73,6 +72,10 @@ impl MarkdownFormatter<'_> {
         config: &Config,
         mut stdout: W,
     ) -> std::io::Result<W> {
+        if let Some(baseline) = list.baseline.as_deref() {
+            writeln!(&mut stdout, "Compared with `{baseline}`.")?;
+            writeln!(&mut stdout)?;
+        }
         writeln!(
             &mut stdout,
             "|     | Metric | Previous value | Current value | Change |"
@@ -82,7 +85,7 @@ impl MarkdownFormatter<'_> {
             "|:---:|:-------|---------------:|--------------:|-------:|"
         )?;
         for entry in list.inner().iter() {
-            let formatter: Formatter = config.formatter(entry.header.name.as_str());
+            let formatter: ValueFormatter = config.formatter(entry.header.name.as_str());
             self.format_entry(entry, &formatter, &mut stdout)?;
         }
         Ok(stdout)
@@ -99,14 +102,18 @@ mod tests {
 
     #[test]
     fn should_format_text() {
-        let list = MetricDiffList(vec![
-            MetricDiff::new(MetricHeader::new("first"), Comparison::created(10.0)),
-            MetricDiff::new(
-                MetricHeader::new("second"),
-                Comparison::new(10.0, Some(12.0)),
-            ),
-            MetricDiff::new(MetricHeader::new("third"), Comparison::new(10.0, None)),
-        ]);
+        let list = MetricDiffList {
+            baseline: None,
+            history: Default::default(),
+            list: vec![
+                MetricDiff::new(MetricHeader::new("first"), Comparison::created(10.0)),
+                MetricDiff::new(
+                    MetricHeader::new("second"),
+                    Comparison::new(10.0, Some(12.0)),
+                ),
+                MetricDiff::new(MetricHeader::new("third"), Comparison::new(10.0, None)),
+            ],
+        };
         let mut writer = BasicWriter::from(Vec::<u8>::new());
         let config = Config::default();
         super::MarkdownFormatter(&Params {
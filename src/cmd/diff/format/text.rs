@@ -1,10 +1,9 @@
-use human_number::Formatter;
-
 use crate::cmd::format::text::PrettyTextMetricHeader;
 use crate::cmd::prelude::PrettyWriter;
 use crate::entity::config::Config;
 use crate::entity::difference::{Comparison, MetricDiff, MetricDiffList};
 use crate::formatter::percent::TextPercent;
+use crate::formatter::value::ValueFormatter;
 
 pub struct TextFormatter<'a>(pub &'a super::Params);
 
@@ -12,7 +11,7 @@ impl TextFormatter<'_> {
     fn format_entry<W: PrettyWriter>(
         &self,
         entry: &MetricDiff,
-        formatter: Formatter,
+        formatter: ValueFormatter,
         stdout: &mut W,
     ) -> std::io::Result<()> {
         match &entry.comparison {
@@ -63,8 +62,14 @@ impl TextFormatter<'_> {
         config: &Config,
         mut stdout: W,
     ) -> std::io::Result<W> {
+        if let Some(baseline) = list.baseline.as_deref() {
+            let style = nu_ansi_term::Style::new().dimmed();
+            stdout.set_style(style.prefix())?;
+            writeln!(stdout, "compared with {baseline}")?;
+            stdout.set_style(style.suffix())?;
+        }
         for entry in list.inner().iter() {
-            let formatter: Formatter = config.formatter(entry.header.name.as_str());
+            let formatter: ValueFormatter = config.formatter(entry.header.name.as_str());
             self.format_entry(entry, formatter, &mut stdout)?;
         }
         Ok(stdout)
@@ -81,14 +86,18 @@ mod tests {
 
     #[test]
     fn should_format_text() {
-        let list = MetricDiffList(vec![
-            MetricDiff::new(MetricHeader::new("first"), Comparison::created(10.0)),
-            MetricDiff::new(
-                MetricHeader::new("second"),
-                Comparison::new(10.0, Some(12.0)),
-            ),
-            MetricDiff::new(MetricHeader::new("third"), Comparison::new(10.0, None)),
-        ]);
+        let list = MetricDiffList {
+            baseline: None,
+            history: Default::default(),
+            list: vec![
+                MetricDiff::new(MetricHeader::new("first"), Comparison::created(10.0)),
+                MetricDiff::new(
+                    MetricHeader::new("second"),
+                    Comparison::new(10.0, Some(12.0)),
+                ),
+                MetricDiff::new(MetricHeader::new("third"), Comparison::new(10.0, None)),
+            ],
+        };
         let writer = BasicWriter::from(Vec::<u8>::new());
         let config = Config::default();
         let writer = super::TextFormatter(&Params {
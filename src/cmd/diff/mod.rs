@@ -1,5 +1,6 @@
 use super::prelude::PrettyWriter;
 use crate::backend::Backend;
+use crate::entity::baseline::Baseline;
 use crate::service::Service;
 use crate::ExitCode;
 
@@ -14,6 +15,11 @@ pub struct CommandDiff {
     /// When enabled, the metrics prior the provided range will be displayed
     #[clap(long)]
     show_previous: bool,
+    /// Commit to compare with, overriding the one from the configuration
+    ///
+    /// Can be merge-base:<ref>, ref:<ref> or latest-with-metrics:<ref>
+    #[clap(long)]
+    baseline: Option<Baseline>,
 
     /// Output format
     #[clap(long, default_value = "text")]
@@ -38,8 +44,9 @@ impl super::Executor for CommandDiff {
         let opts = crate::service::diff::Options {
             remote: self.remote.as_str(),
             target: self.target.as_str(),
+            baseline: self.baseline.as_ref(),
         };
-        let diff = svc.diff(&opts)?;
+        let diff = svc.diff(&config, &opts)?;
         let diff = if self.show_previous {
             diff
         } else {
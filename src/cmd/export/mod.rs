@@ -1,5 +1,6 @@
 use super::prelude::PrettyWriter;
 use crate::backend::Backend;
+use crate::entity::baseline::Baseline;
 use crate::entity::config::Config;
 use crate::entity::log::LogEntry;
 use crate::service::Service;
@@ -41,6 +42,14 @@ pub struct CommandExport {
     /// Can use ranges like HEAD~2..HEAD
     #[clap(default_value = "HEAD")]
     target: String,
+    /// Commit to compare with, overriding the one from the configuration
+    ///
+    /// Can be merge-base:<ref>, ref:<ref> or latest-with-metrics:<ref>
+    #[clap(long)]
+    baseline: Option<Baseline>,
+    /// Branch used to select the overrides of the configuration, default to the current branch
+    #[clap(long)]
+    branch: Option<String>,
     /// Output format
     #[command(subcommand)]
     format: ExportFormat,
@@ -64,6 +73,8 @@ impl super::Executor for CommandExport {
             &crate::service::check::Options {
                 remote: self.remote.as_str(),
                 target: self.target.as_str(),
+                baseline: self.baseline.as_ref(),
+                branch: self.branch.as_deref(),
             },
         )?;
 
@@ -1,8 +1,7 @@
-use human_number::Formatter;
-
 use crate::cmd::prelude::{PrettyDisplay, PrettyWriter};
 use crate::entity::metric::{Metric, MetricHeader};
 use crate::formatter::metric::TextMetricTags;
+use crate::formatter::value::ValueFormatter;
 
 pub const TAB: &str = "    ";
 
@@ -29,12 +28,12 @@ impl PrettyDisplay for PrettyTextMetricHeader<'_> {
 
 pub struct PrettyTextMetric<'a> {
     value: &'a Metric,
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
 }
 
 impl<'a> PrettyTextMetric<'a> {
     #[inline]
-    pub const fn new(formatter: &'a Formatter<'a>, value: &'a Metric) -> Self {
+    pub const fn new(formatter: &'a ValueFormatter<'a>, value: &'a Metric) -> Self {
         Self { value, formatter }
     }
 }
@@ -51,11 +50,12 @@ mod tests {
     use human_number::Formatter;
 
     use crate::cmd::prelude::PrettyDisplay;
+    use crate::formatter::value::ValueFormatter;
 
     #[test]
     fn should_display_metric_with_single_tag() {
         let item = super::Metric::new("name", 12.34).with_tag("foo", "bar");
-        let formatter = Formatter::si();
+        let formatter = ValueFormatter::from(Formatter::si());
         assert_eq!(
             super::PrettyTextMetric::new(&formatter, &item)
                 .to_basic_string()
@@ -66,7 +66,7 @@ mod tests {
 
     #[test]
     fn should_display_metric_with_multiple_tags() {
-        let formatter = Formatter::si();
+        let formatter = ValueFormatter::from(Formatter::si());
         let item = super::Metric::new("name", 12.34)
             .with_tag("foo", "bar")
             .with_tag("ab", "cd");
@@ -80,7 +80,7 @@ mod tests {
 
     #[test]
     fn should_display_metric_with_empty_tags() {
-        let formatter = Formatter::si();
+        let formatter = ValueFormatter::from(Formatter::si());
         let item = super::Metric::new("name", 12.34);
         assert_eq!(
             super::PrettyTextMetric::new(&formatter, &item)
@@ -5,6 +5,8 @@ use crate::ExitCode;
 
 #[cfg(feature = "importer-lcov")]
 mod lcov;
+#[cfg(feature = "importer-text")]
+mod text;
 
 #[derive(Debug, clap::Subcommand)]
 enum CommandImporter {
@@ -13,6 +15,8 @@ enum CommandImporter {
     Noop,
     #[cfg(feature = "importer-lcov")]
     Lcov(lcov::LcovImporter),
+    #[cfg(feature = "importer-text")]
+    Text(text::TextImporter),
 }
 
 impl crate::importer::Importer for CommandImporter {
@@ -22,6 +26,8 @@ impl crate::importer::Importer for CommandImporter {
             Self::Noop => Ok(Vec::new()),
             #[cfg(feature = "importer-lcov")]
             Self::Lcov(inner) => inner.import(),
+            #[cfg(feature = "importer-text")]
+            Self::Text(inner) => inner.import(),
         }
     }
 }
@@ -29,6 +35,9 @@ impl crate::importer::Importer for CommandImporter {
 /// Import metrics in batch from source files.
 #[derive(clap::Parser, Debug)]
 pub struct CommandImport {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
     /// Commit target, default to HEAD
     #[clap(long, short, default_value = "HEAD")]
     target: String,
@@ -37,6 +46,9 @@ pub struct CommandImport {
     #[clap(long, default_value = "false")]
     dry_run: bool,
 
+    #[clap(flatten)]
+    replace: super::ReplaceOptions,
+
     #[command(subcommand)]
     importer: CommandImporter,
 }
@@ -65,13 +77,14 @@ impl crate::cmd::Executor for CommandImport {
 
         let svc = crate::service::Service::new(backend);
         let opts = crate::service::add::Options {
+            remote: self.remote,
             target: self.target,
+            replace: self
+                .replace
+                .selectors(metrics.iter().map(|metric| metric.header.name.as_str())),
         };
 
-        for metric in metrics {
-            tracing::trace!("importing {metric:?}");
-            svc.add(metric, &opts)?;
-        }
+        svc.add_all(metrics, &opts)?;
         tracing::debug!("import done");
         Ok(ExitCode::Success)
     }
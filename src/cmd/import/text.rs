@@ -0,0 +1,163 @@
+use std::path::PathBuf;
+
+use crate::importer::text::TextSource;
+
+/// Imports metrics written with the same format as the `show` command
+///
+/// Every line should look like `name{key="value", other="value"} 12.34`,
+/// with a value written without unit. Empty lines and lines starting with `#` are ignored.
+///
+/// This can be used to copy the metrics from one commit to another.
+///
+///     git metrics show --raw --target HEAD~1 | git metrics import text -
+#[derive(clap::Parser, Debug)]
+pub(super) struct TextImporter {
+    /// Path to the file, use `-` to read from the standard input
+    #[clap(default_value = "-")]
+    path: PathBuf,
+}
+
+impl TextImporter {
+    #[inline(always)]
+    fn source(self) -> TextSource {
+        if self.path.as_os_str() == "-" {
+            TextSource::Stdin
+        } else {
+            TextSource::File(self.path)
+        }
+    }
+}
+
+impl crate::importer::Importer for TextImporter {
+    fn import(self) -> Result<Vec<crate::entity::metric::Metric>, crate::importer::Error> {
+        crate::importer::text::TextImporter::new(self.source()).import()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{Backend, NoteRef};
+
+    #[test]
+    fn should_import_metrics_from_file() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        let file = repo.root_path().unwrap().join("metrics.txt");
+        std::fs::write(&file, "first{foo=\"bar\"} 12.34\nsecond 1\n").unwrap();
+
+        let code = crate::Args::parse_from(["_", "import", "text", file.to_str().unwrap()])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success(), "{}", String::from_utf8_lossy(&stderr));
+        assert_eq!(
+            repo.get_note("HEAD", NoteRef::Changes),
+            Some(String::from(
+                r#"[[changes]]
+action = "add"
+name = "first"
+value = 12.34
+
+[changes.tags]
+foo = "bar"
+
+[[changes]]
+action = "add"
+name = "second"
+value = 1.0
+
+[changes.tags]
+"#
+            ))
+        );
+    }
+
+    #[test]
+    fn should_import_raw_output_of_show() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_config(
+            r#"[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+
+[derived.double]
+expression = "binary-size * 2"
+"#,
+        );
+        repo.set_note(
+            "HEAD~1",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux", quote = "a \"b\"" }
+value = 12939837.123456
+
+[[metrics]]
+name = "ratio"
+value = 0.1
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "show", "--raw", "--target", "HEAD~1"])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+        assert!(code.is_success(), "{}", String::from_utf8_lossy(&stderr));
+
+        let file = repo.root_path().unwrap().join("metrics.txt");
+        std::fs::write(&file, &stdout).unwrap();
+        let code = crate::Args::parse_from(["_", "import", "text", file.to_str().unwrap()])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+        assert!(code.is_success(), "{}", String::from_utf8_lossy(&stderr));
+
+        similar_asserts::assert_eq!(
+            repo.get_note("HEAD", NoteRef::Changes),
+            Some(String::from(
+                r#"[[changes]]
+action = "add"
+name = "binary-size"
+value = 12939837.123456
+
+[changes.tags]
+os = "linux"
+quote = 'a "b"'
+
+[[changes]]
+action = "add"
+name = "ratio"
+value = 0.1
+
+[changes.tags]
+"#
+            ))
+        );
+    }
+
+    #[test]
+    fn should_report_invalid_line() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        let file = repo.root_path().unwrap().join("metrics.txt");
+        std::fs::write(&file, "first 1\nsecond{foo=bar} 1\n").unwrap();
+
+        let code = crate::Args::parse_from(["_", "import", "text", file.to_str().unwrap()])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(!code.is_success());
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stderr),
+            "invalid source file format\n\n\n\tline 2, column 12: expected a tag value between double quotes\n\tsecond{foo=bar} 1\n\t           ^\n"
+        );
+        assert_eq!(repo.get_note("HEAD", NoteRef::Changes), None);
+    }
+}
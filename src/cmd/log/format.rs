@@ -1,5 +1,3 @@
-use human_number::Formatter;
-
 /// The output format should be something like
 /// ```
 /// * aaaaaa commit_message
@@ -11,6 +9,7 @@ use crate::cmd::prelude::{PrettyDisplay, PrettyWriter};
 use crate::entity::config::Config;
 use crate::entity::git::Commit;
 use crate::entity::metric::{Metric, MetricStack};
+use crate::formatter::value::ValueFormatter;
 
 const TAB: &str = "    ";
 
@@ -47,7 +46,7 @@ impl TextFormatter {
     fn format_metric<W: PrettyWriter>(
         &self,
         item: &Metric,
-        formatter: &Formatter,
+        formatter: &ValueFormatter,
         stdout: &mut W,
     ) -> std::io::Result<()> {
         stdout.write_str(TAB)?;
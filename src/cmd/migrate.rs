@@ -0,0 +1,210 @@
+use super::format::text::{PrettyTextMetric, TAB};
+use super::prelude::{PrettyWriter, Tag};
+use crate::backend::Backend;
+use crate::entity::migration::Migration;
+use crate::entity::selector::MetricSelector;
+use crate::service::Service;
+use crate::ExitCode;
+
+/// Rewrite the metrics already pushed, to rename them, change their tags or scale their values
+///
+///     git metrics migrate binary-size --rename artifact.size --scale 0.001
+///
+/// All the affected notes are written in a single commit that can then be
+/// pushed with `git metrics push`. The pending changes are migrated as well.
+#[derive(clap::Parser, Debug, Default)]
+#[clap(group(
+    clap::ArgGroup::new("operation")
+        .required(true)
+        .multiple(true)
+        .args(["rename", "add_tag", "rename_tag", "drop_tag", "scale"])
+))]
+pub struct CommandMigrate {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+    /// Commit range to migrate, every commit with metrics by default
+    #[clap(long)]
+    range: Option<String>,
+    /// Only migrate the metrics having this tag
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// New name of the metrics
+    #[clap(long)]
+    rename: Option<String>,
+    /// Tag to add to the metrics, overriding the existing value
+    #[clap(long)]
+    add_tag: Vec<Tag>,
+    /// Tag to rename, with the format `old: new`
+    #[clap(long)]
+    rename_tag: Vec<Tag>,
+    /// Name of the tag to remove from the metrics
+    #[clap(long)]
+    drop_tag: Vec<String>,
+    /// Factor to apply to the values, like 0.001 to convert bytes to kilobytes
+    #[clap(long)]
+    scale: Option<f64>,
+    /// Display the metrics that would be migrated, without changing them
+    #[clap(long)]
+    dry_run: bool,
+    /// Name of the metrics to migrate
+    ///
+    /// The name can contain `*` to match any sequence of characters and `?` to match a single character.
+    name: String,
+}
+
+impl CommandMigrate {
+    fn migration(&self) -> Migration {
+        let pairs = |tags: &[Tag]| {
+            tags.iter()
+                .map(|tag| (tag.name.clone(), tag.value.clone()))
+                .collect()
+        };
+        Migration {
+            selector: MetricSelector::new(self.name.as_str()).with_tags(pairs(&self.tag)),
+            rename: self.rename.clone(),
+            add_tags: pairs(&self.add_tag),
+            rename_tags: pairs(&self.rename_tag),
+            drop_tags: self.drop_tag.clone(),
+            scale: self.scale,
+        }
+    }
+}
+
+impl super::Executor for CommandMigrate {
+    #[tracing::instrument(name = "migrate", skip_all, fields(name = self.name.as_str(), dry_run = self.dry_run))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let svc = Service::new(backend);
+        let config = svc.open_config()?;
+        let report = svc.migrate(
+            &self.migration(),
+            &crate::service::migrate::Options {
+                remote: self.remote.as_str(),
+                range: self.range.as_deref(),
+                dry_run: self.dry_run,
+            },
+        )?;
+        for item in report.iter() {
+            writeln!(
+                stdout,
+                "* {}",
+                &item.commit_sha[..item.commit_sha.len().min(7)]
+            )?;
+            for (before, after) in item.changes.iter() {
+                let formatter = config.formatter(before.header.name.as_str());
+                stdout.write_str(TAB)?;
+                stdout.write_str("- ")?;
+                stdout.write_element(PrettyTextMetric::new(&formatter, before))?;
+                stdout.write_str("\n")?;
+                let formatter = config.formatter(after.header.name.as_str());
+                stdout.write_str(TAB)?;
+                stdout.write_str("+ ")?;
+                stdout.write_element(PrettyTextMetric::new(&formatter, after))?;
+                stdout.write_str("\n")?;
+            }
+        }
+        writeln!(
+            stdout,
+            "{} commit{} {}",
+            report.len(),
+            if report.len() > 1 { "s" } else { "" },
+            if self.dry_run {
+                "would be migrated"
+            } else {
+                "migrated"
+            }
+        )?;
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+
+    #[test]
+    fn should_display_dry_run_report() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1000.0
+"#,
+        );
+
+        let code = crate::Args::parse_from([
+            "_",
+            "migrate",
+            "binary-size",
+            "--rename",
+            "artifact.size",
+            "--rename-tag",
+            "os: platform.os",
+            "--scale",
+            "0.001",
+            "--dry-run",
+        ])
+        .command
+        .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success(), "{:?}", String::from_utf8_lossy(&stderr));
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"* aaaaaaa
+    - binary-size{os="linux"} 1000.00
+    + artifact.size{platform.os="linux"} 1.00
+1 commit would be migrated
+"#
+        );
+        assert!(repo
+            .get_note("aaaaaaaaaa", NoteRef::remote_metrics("origin"))
+            .unwrap()
+            .contains("binary-size"));
+    }
+
+    #[test]
+    fn should_migrate_metrics() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1000.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "migrate", "binary-*", "--drop-tag", "os"])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success(), "{:?}", String::from_utf8_lossy(&stderr));
+        assert!(String::from_utf8_lossy(&stdout).ends_with("1 commit migrated\n"));
+        assert!(!repo
+            .get_note("aaaaaaaaaa", NoteRef::remote_metrics("origin"))
+            .unwrap()
+            .contains("linux"));
+    }
+
+    #[test]
+    fn should_require_an_operation() {
+        assert!(crate::Args::try_parse_from(["_", "migrate", "binary-size"]).is_err());
+    }
+}
@@ -7,7 +7,10 @@ use crate::error::DetailedError;
 use crate::ExitCode;
 
 mod add;
+mod bisect;
+mod blame;
 mod check;
+mod config;
 mod diff;
 #[cfg(feature = "exporter")]
 mod export;
@@ -15,10 +18,16 @@ mod export;
 mod import;
 mod init;
 mod log;
+mod migrate;
 mod pull;
 mod push;
+mod ratchet;
 mod remove;
+mod reset;
 mod show;
+mod status;
+#[cfg(feature = "collector-time")]
+mod time;
 
 mod format;
 mod prelude;
@@ -34,17 +43,26 @@ trait Executor {
 #[derive(Debug, clap::Subcommand)]
 pub(crate) enum Command {
     Add(add::CommandAdd),
+    Bisect(bisect::CommandBisect),
+    Blame(blame::CommandBlame),
     Check(check::CommandCheck),
+    Config(config::CommandConfig),
     Diff(diff::CommandDiff),
     Export(export::CommandExport),
     Init(init::CommandInit),
     #[cfg(feature = "importer")]
     Import(import::CommandImport),
     Log(log::CommandLog),
+    Migrate(migrate::CommandMigrate),
     Pull(pull::CommandPull),
     Push(push::CommandPush),
+    Ratchet(ratchet::CommandRatchet),
     Remove(remove::CommandRemove),
+    Reset(reset::CommandReset),
     Show(show::CommandShow),
+    Status(status::CommandStatus),
+    #[cfg(feature = "collector-time")]
+    Time(time::CommandTime),
 }
 
 impl Default for Command {
@@ -61,17 +79,26 @@ impl Command {
     ) -> Result<ExitCode, crate::service::Error> {
         match self {
             Self::Add(inner) => inner.execute(repo, stdout),
+            Self::Bisect(inner) => inner.execute(repo, stdout),
+            Self::Blame(inner) => inner.execute(repo, stdout),
             Self::Check(inner) => inner.execute(repo, stdout),
+            Self::Config(inner) => inner.execute(repo, stdout),
             Self::Diff(inner) => inner.execute(repo, stdout),
             Self::Export(inner) => inner.execute(repo, stdout),
             Self::Init(inner) => inner.execute(repo, stdout),
             #[cfg(feature = "importer")]
             Self::Import(inner) => inner.execute(repo, stdout),
             Self::Log(inner) => inner.execute(repo, stdout),
+            Self::Migrate(inner) => inner.execute(repo, stdout),
             Self::Pull(inner) => inner.execute(repo, stdout),
             Self::Push(inner) => inner.execute(repo, stdout),
+            Self::Ratchet(inner) => inner.execute(repo, stdout),
             Self::Remove(inner) => inner.execute(repo, stdout),
+            Self::Reset(inner) => inner.execute(repo, stdout),
             Self::Show(inner) => inner.execute(repo, stdout),
+            Self::Status(inner) => inner.execute(repo, stdout),
+            #[cfg(feature = "collector-time")]
+            Self::Time(inner) => inner.execute(repo, stdout),
         }
     }
 
@@ -92,7 +119,7 @@ impl Command {
             Ok(res) => res,
             Err(error) => {
                 error.write(stderr).expect("couldn't log error");
-                ExitCode::Failure
+                error.exit_code()
             }
         }
     }
@@ -107,3 +134,41 @@ pub(crate) struct GitCredentials {
     #[clap(long, env = "GIT_PASSWORD")]
     pub(crate) password: Option<String>,
 }
+
+#[derive(Debug, Default, clap::Parser)]
+pub(crate) struct ReplaceOptions {
+    /// Remove the existing metrics with the same name before adding the new ones
+    ///
+    /// Running the same command several times will then keep the same metrics.
+    #[clap(long)]
+    replace: bool,
+    /// Only replace the existing metrics having this tag
+    #[clap(long, requires = "replace")]
+    replace_tag: Vec<prelude::Tag>,
+}
+
+impl ReplaceOptions {
+    /// Builds the selectors of the metrics to replace, based on the names of the added metrics
+    fn selectors<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str>,
+    ) -> Vec<crate::entity::selector::MetricSelector> {
+        if !self.replace {
+            return Vec::new();
+        }
+        let tags: indexmap::IndexMap<String, String> = self
+            .replace_tag
+            .iter()
+            .map(|tag| (tag.name.clone(), tag.value.clone()))
+            .collect();
+        let mut result: Vec<crate::entity::selector::MetricSelector> = Vec::new();
+        for name in names {
+            if !result.iter().any(|selector| selector.name == name) {
+                result.push(
+                    crate::entity::selector::MetricSelector::new(name).with_tags(tags.clone()),
+                );
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,118 @@
+use super::prelude::PrettyWriter;
+use crate::backend::Backend;
+use crate::service::Service;
+use crate::ExitCode;
+
+/// Tighten the max and min rules of the configuration to the current metrics
+///
+/// The output is a diff of the configuration file, like
+///
+///     @@ line 7 @@
+///     - value = "10 MiB"
+///     + value = "7.75 MiB"
+///
+/// Only the rule values are changed, the comments and the formatting are kept,
+/// and the values written with a unit are written with the unit of the metric.
+#[derive(clap::Parser, Debug, Default)]
+pub struct CommandRatchet {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+    /// Commit target, default to HEAD
+    #[clap(long, short, default_value = "HEAD")]
+    target: String,
+    /// Ratio of the current value kept as margin, like 0.05 for 5%
+    #[clap(long, default_value = "0.0")]
+    margin: f64,
+    /// Print the changes without writing the configuration
+    #[clap(long)]
+    dry_run: bool,
+}
+
+impl super::Executor for CommandRatchet {
+    #[tracing::instrument(name = "ratchet", skip_all, fields(target = self.target.as_str()))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let svc = Service::new(backend);
+        let result = svc.ratchet(&crate::service::ratchet::Options {
+            remote: self.remote.as_str(),
+            target: self.target.as_str(),
+            margin: self.margin,
+            dry_run: self.dry_run,
+        })?;
+        let removed = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Red);
+        let added = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Green);
+        for line in result.lines.iter() {
+            writeln!(stdout, "@@ line {} @@", line.line)?;
+            stdout.set_style(removed.prefix())?;
+            write!(stdout, "- {}", line.before)?;
+            stdout.set_style(removed.suffix())?;
+            stdout.write_str("\n")?;
+            stdout.set_style(added.prefix())?;
+            write!(stdout, "+ {}", line.after)?;
+            stdout.set_style(added.suffix())?;
+            stdout.write_str("\n")?;
+        }
+        match (result.changes.len(), self.dry_run) {
+            (0, _) => writeln!(stdout, "no rule to tighten")?,
+            (count, true) => writeln!(stdout, "{count} rule(s) to tighten")?,
+            (count, false) => writeln!(stdout, "{count} rule(s) tightened")?,
+        };
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+
+    #[test]
+    fn should_print_diff_of_tightened_rules() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_config(
+            r#"# size budget
+[[metrics.first.rules]]
+type = "max"
+value = 100.0
+"#,
+        );
+        repo.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 80.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "ratchet", "--margin", "0.1", "--dry-run"])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success());
+        assert!(stderr.is_empty());
+        let stdout = String::from_utf8_lossy(&stdout);
+        similar_asserts::assert_eq!(
+            stdout,
+            r#"@@ line 4 @@
+- value = 100.0
++ value = 88.0
+1 rule(s) to tighten
+"#
+        );
+        assert_eq!(
+            repo.get_config().metrics["first"].rules,
+            vec![crate::entity::config::Rule::max(100.0)]
+        );
+    }
+}
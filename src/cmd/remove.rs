@@ -1,9 +1,11 @@
-use super::prelude::PrettyWriter;
+use super::format::text::PrettyTextMetric;
+use super::prelude::{PrettyWriter, Tag};
 use crate::backend::Backend;
+use crate::entity::selector::MetricSelector;
 use crate::service::Service;
 use crate::ExitCode;
 
-/// Remove a metric related to the target
+/// Remove the metrics related to the target matching a name and tags
 #[derive(clap::Parser, Debug, Default)]
 pub struct CommandRemove {
     /// Remote name, default to origin
@@ -12,24 +14,49 @@ pub struct CommandRemove {
     /// Commit target, default to HEAD
     #[clap(long, short, default_value = "HEAD")]
     target: String,
-    /// Index of the metric to remove
-    index: usize,
+    /// Only remove the metrics having this tag
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// Display the metrics that would be removed, without removing them
+    #[clap(long)]
+    dry_run: bool,
+    /// Name of the metrics to remove
+    ///
+    /// The name can contain `*` to match any sequence of characters and `?` to match a single character.
+    name: String,
 }
 
 impl super::Executor for CommandRemove {
-    #[tracing::instrument(name = "remove", skip_all, fields(target = self.target.as_str(), index = self.index))]
+    #[tracing::instrument(name = "remove", skip_all, fields(target = self.target.as_str(), name = self.name.as_str()))]
     fn execute<B: Backend, Out: PrettyWriter>(
         self,
         backend: B,
-        _stdout: Out,
+        mut stdout: Out,
     ) -> Result<ExitCode, crate::service::Error> {
-        Service::new(backend).remove(
-            self.index,
+        let selector = MetricSelector::new(self.name).with_tags(
+            self.tag
+                .into_iter()
+                .map(|tag| (tag.name, tag.value))
+                .collect(),
+        );
+        let svc = Service::new(backend);
+        let removed = svc.remove(
+            &selector,
             &crate::service::remove::Options {
                 remote: self.remote.as_str(),
                 target: self.target.as_str(),
+                dry_run: self.dry_run,
             },
         )?;
+        tracing::debug!("{} metrics removed", removed.len());
+        if self.dry_run {
+            let config = svc.open_config()?;
+            for metric in removed.iter() {
+                let formatter = config.formatter(metric.header.name.as_str());
+                stdout.write_element(PrettyTextMetric::new(&formatter, metric))?;
+                stdout.write_str("\n")?;
+            }
+        }
         Ok(ExitCode::Success)
     }
 }
@@ -39,6 +66,7 @@ mod tests {
     use clap::Parser;
 
     use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
 
     #[test]
     fn should_remove_metric() {
@@ -46,13 +74,74 @@ mod tests {
         let mut stderr = Vec::new();
 
         let backend = MockBackend::default();
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1.0
 
-        let code = crate::Args::parse_from(["_", "remove", "0"])
+[[metrics]]
+name = "binary-size"
+tags = { os = "macos" }
+value = 2.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "remove", "--tag", "os: linux", "binary-*"])
             .command
-            .execute(backend, false, &mut stdout, &mut stderr);
+            .execute(backend.clone(), false, &mut stdout, &mut stderr);
 
         assert!(code.is_success());
         assert!(stdout.is_empty());
         assert!(stderr.is_empty());
+        assert_eq!(
+            backend.get_note("HEAD", NoteRef::Changes),
+            Some(String::from(
+                r#"[[changes]]
+action = "remove"
+name = "binary-size"
+value = 1.0
+
+[changes.tags]
+os = "linux"
+"#
+            ))
+        );
+    }
+
+    #[test]
+    fn should_list_metrics_with_dry_run() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let backend = MockBackend::default();
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1.0
+
+[[metrics]]
+name = "binary-size"
+tags = { os = "macos" }
+value = 2.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "remove", "--dry-run", "binary-size"])
+            .command
+            .execute(backend.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success());
+        assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            "binary-size{os=\"linux\"} 1.00\nbinary-size{os=\"macos\"} 2.00\n"
+        );
+        assert!(stderr.is_empty());
+        assert_eq!(backend.get_note("HEAD", NoteRef::Changes), None);
     }
 }
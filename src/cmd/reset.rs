@@ -0,0 +1,202 @@
+use super::format::text::{PrettyTextMetric, TAB};
+use super::prelude::{PrettyWriter, Tag};
+use crate::backend::Backend;
+use crate::entity::metric::MetricChange;
+use crate::entity::selector::MetricSelector;
+use crate::service::reset::{Options, Scope};
+use crate::service::Service;
+use crate::ExitCode;
+
+/// Discard the local changes that are not pushed yet
+///
+/// The discarded changes are displayed once removed.
+#[derive(clap::Parser, Debug, Default)]
+pub struct CommandReset {
+    /// Commit target, default to HEAD
+    #[clap(long, short, default_value = "HEAD", conflicts_with = "all")]
+    target: String,
+    /// Discard the local changes of every commit
+    #[clap(long)]
+    all: bool,
+    /// Only discard the changes of the metrics having this tag
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// Only discard the changes of the metrics with this name
+    ///
+    /// The name can contain `*` to match any sequence of characters and `?` to match a single character.
+    name: Option<String>,
+}
+
+impl super::Executor for CommandReset {
+    #[tracing::instrument(name = "reset", skip_all, fields(target = self.target.as_str(), all = self.all))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let selector = if self.name.is_some() || !self.tag.is_empty() {
+            Some(
+                MetricSelector::new(self.name.unwrap_or_else(|| "*".into())).with_tags(
+                    self.tag
+                        .into_iter()
+                        .map(|tag| (tag.name, tag.value))
+                        .collect(),
+                ),
+            )
+        } else {
+            None
+        };
+        let svc = Service::new(backend);
+        let config = svc.open_config()?;
+        let discarded = svc.reset(&Options {
+            scope: if self.all {
+                Scope::All
+            } else {
+                Scope::Target(self.target.as_str())
+            },
+            selector: selector.as_ref(),
+        })?;
+        if discarded.is_empty() {
+            stdout.write_str("no change to discard\n")?;
+        }
+        for item in discarded {
+            let target = if self.all {
+                &item.target[..item.target.len().min(7)]
+            } else {
+                item.target.as_str()
+            };
+            writeln!(
+                stdout,
+                "discarded {} change{} on {target}",
+                item.changes.len(),
+                if item.changes.len() > 1 { "s" } else { "" },
+            )?;
+            for change in item.changes.iter() {
+                let (sign, metric) = match change {
+                    MetricChange::Add(metric) => ("+ ", metric),
+                    MetricChange::Remove(metric) => ("- ", metric),
+                };
+                let formatter = config.formatter(metric.header.name.as_str());
+                stdout.write_str(TAB)?;
+                stdout.write_str(sign)?;
+                stdout.write_element(PrettyTextMetric::new(&formatter, metric))?;
+                stdout.write_str("\n")?;
+            }
+        }
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+
+    #[test]
+    fn should_reset_head() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "HEAD",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+tags = { os = "linux" }
+value = 1.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+"#,
+        );
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+tags = { os = "macos" }
+value = 3.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "reset"]).command.execute(
+            repo.clone(),
+            false,
+            &mut stdout,
+            &mut stderr,
+        );
+
+        assert!(code.is_success());
+        assert!(stderr.is_empty());
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"discarded 2 changes on HEAD
+    + first{os="linux"} 1.00
+    - second 2.00
+"#
+        );
+        assert!(repo.get_note("HEAD", NoteRef::Changes).is_none());
+        assert!(repo.get_note("aaaaaaaaaa", NoteRef::Changes).is_some());
+    }
+
+    #[test]
+    fn should_reset_all_matching_tag() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "HEAD",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+tags = { os = "linux" }
+value = 1.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+"#,
+        );
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+tags = { os = "macos" }
+value = 3.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "reset", "--all", "first", "--tag", "os: macos"])
+            .command
+            .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success());
+        assert!(stderr.is_empty());
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"discarded 1 change on aaaaaaa
+    + first{os="macos"} 3.00
+"#
+        );
+        assert!(repo.get_note("HEAD", NoteRef::Changes).is_some());
+        assert!(repo.get_note("aaaaaaaaaa", NoteRef::Changes).is_none());
+    }
+
+    #[test]
+    fn should_fail_with_target_and_all() {
+        let result = crate::Args::try_parse_from(["_", "reset", "--all", "--target", "HEAD~1"]);
+        assert!(result.is_err());
+    }
+}
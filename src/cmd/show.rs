@@ -1,10 +1,14 @@
-use super::format::text::PrettyTextMetric;
+use super::format::text::{PrettyTextMetric, PrettyTextMetricHeader};
 use super::prelude::PrettyWriter;
 use crate::backend::Backend;
 use crate::service::Service;
 use crate::ExitCode;
 
 /// Display the metrics related to the target
+///
+/// With `--raw`, the output can be imported on another commit.
+///
+///     git metrics show --raw --target HEAD~1 | git metrics import text -
 #[derive(clap::Parser, Debug, Default)]
 pub struct CommandShow {
     /// Remote name, default to origin
@@ -13,6 +17,9 @@ pub struct CommandShow {
     /// Commit target, default to HEAD
     #[clap(long, short, default_value = "HEAD")]
     target: String,
+    /// Print the recorded values without unit nor rounding, and without the derived metrics
+    #[clap(long)]
+    raw: bool,
 }
 
 impl super::Executor for CommandShow {
@@ -27,8 +34,14 @@ impl super::Executor for CommandShow {
         let metrics = svc.show(&crate::service::show::Options {
             remote: self.remote.as_str(),
             target: self.target.as_str(),
+            derived: !self.raw,
         })?;
         for metric in metrics.into_metric_iter() {
+            if self.raw {
+                stdout.write_element(PrettyTextMetricHeader::new(&metric.header))?;
+                writeln!(stdout, " {}", metric.value)?;
+                continue;
+            }
             let formatter = config.formatter(metric.header.name.as_str());
             stdout.write_element(PrettyTextMetric::new(&formatter, &metric))?;
             stdout.write_str("\n")?;
@@ -105,4 +118,64 @@ value = 1.0
         let stdout = String::from_utf8_lossy(&stdout);
         assert_eq!(stdout, "foo 1.00\nfoo{bar=\"baz\"} 1.00\n");
     }
+
+    #[test]
+    fn should_display_derived_metrics() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = crate::backend::mock::MockBackend::default();
+        repo.set_config(
+            r#"[derived."coverage.ratio"]
+expression = "coverage.hit / coverage.count"
+
+[derived."binary-size.total"]
+expression = 'sum("binary-size")'
+
+[derived.missing]
+expression = "unknown * 2"
+"#,
+        );
+        repo.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "coverage.hit"
+value = 30.0
+
+[[metrics]]
+name = "coverage.count"
+value = 40.0
+
+[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 10.0
+
+[[metrics]]
+name = "binary-size"
+tags = { os = "macos" }
+value = 20.0
+"#,
+        );
+
+        let code = crate::Args::parse_from(["_", "show"]).command.execute(
+            repo,
+            false,
+            &mut stdout,
+            &mut stderr,
+        );
+
+        assert!(code.is_success(), "{:?}", String::from_utf8_lossy(&stderr));
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"coverage.hit 30.00
+coverage.count 40.00
+binary-size{os="linux"} 10.00
+binary-size{os="macos"} 20.00
+coverage.ratio 0.75
+binary-size.total 30.00
+"#
+        );
+    }
 }
@@ -0,0 +1,256 @@
+use super::format::text::{PrettyTextMetric, TAB};
+use super::prelude::{PrettyDisplay, PrettyWriter};
+use crate::backend::Backend;
+use crate::entity::metric::MetricChange;
+use crate::entity::status::{ChangeEffect, CommitStatus, PendingChange};
+use crate::formatter::value::ValueFormatter;
+use crate::service::Service;
+use crate::ExitCode;
+
+/// Display the local changes that are not pushed yet
+///
+/// The output format looks like
+///
+///     * aaaaaaa
+///         + metric_name{key="value"} 12.34 (new)
+///         - metric_name{key="other"} 23.45 (removed)
+///
+/// Each change is compared to the metrics of the remote, applying the
+/// previous changes of the same commit first.
+#[derive(clap::Parser, Debug, Default)]
+pub struct CommandStatus {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+}
+
+struct TextCommitStatus<'a> {
+    value: &'a CommitStatus,
+}
+
+impl PrettyDisplay for TextCommitStatus<'_> {
+    fn print<W: PrettyWriter>(&self, writer: &mut W) -> std::io::Result<()> {
+        let style = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Yellow);
+        writer.write_str("* ")?;
+        writer.set_style(style.prefix())?;
+        writer.write_str(self.value.short_sha())?;
+        writer.set_style(style.suffix())?;
+        if !self.value.reachable {
+            let style = nu_ansi_term::Style::new().fg(nu_ansi_term::Color::Red);
+            writer.write_str(" ")?;
+            writer.set_style(style.prefix())?;
+            writer.write_str("warning: not reachable from any branch")?;
+            writer.set_style(style.suffix())?;
+        }
+        Ok(())
+    }
+}
+
+struct TextPendingChange<'a> {
+    formatter: &'a ValueFormatter<'a>,
+    value: &'a PendingChange,
+}
+
+impl PrettyDisplay for TextPendingChange<'_> {
+    fn print<W: PrettyWriter>(&self, writer: &mut W) -> std::io::Result<()> {
+        let (sign, metric) = match self.value.change {
+            MetricChange::Add(ref metric) => ("+ ", metric),
+            MetricChange::Remove(ref metric) => ("- ", metric),
+        };
+        writer.write_str(sign)?;
+        PrettyTextMetric::new(self.formatter, metric).print(writer)?;
+        let style = nu_ansi_term::Style::new().dimmed();
+        writer.set_style(style.prefix())?;
+        match self.value.effect {
+            ChangeEffect::Created => write!(writer, " (new)")?,
+            ChangeEffect::Updated { previous } => {
+                write!(writer, " (was {})", self.formatter.format(previous))?
+            }
+            ChangeEffect::Unchanged => write!(writer, " (unchanged)")?,
+            ChangeEffect::Removed => write!(writer, " (removed)")?,
+            ChangeEffect::Ignored => write!(writer, " (no effect)")?,
+        };
+        writer.set_style(style.suffix())?;
+        Ok(())
+    }
+}
+
+impl super::Executor for CommandStatus {
+    #[tracing::instrument(name = "status", skip_all, fields(remote = self.remote.as_str()))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        self,
+        backend: B,
+        mut stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let svc = Service::new(backend);
+        let config = svc.open_config()?;
+        let list = svc.status(&crate::service::status::Options {
+            remote: self.remote.as_str(),
+        })?;
+        for item in list.iter() {
+            stdout.write_element(TextCommitStatus { value: item })?;
+            stdout.write_str("\n")?;
+            for change in item.changes.iter() {
+                let name = match change.change {
+                    MetricChange::Add(ref metric) | MetricChange::Remove(ref metric) => {
+                        metric.header.name.as_str()
+                    }
+                };
+                let formatter = config.formatter(name);
+                stdout.write_str(TAB)?;
+                stdout.write_element(TextPendingChange {
+                    formatter: &formatter,
+                    value: change,
+                })?;
+                stdout.write_str("\n")?;
+            }
+        }
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::git::Commit;
+
+    #[test]
+    fn should_return_nothing_without_changes() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note("aaaaaaaaaa", NoteRef::remote_metrics("origin"), "");
+
+        let code = crate::Args::parse_from(["_", "status"]).command.execute(
+            repo,
+            false,
+            &mut stdout,
+            &mut stderr,
+        );
+
+        assert!(code.is_success());
+        assert!(stdout.is_empty());
+        assert!(stderr.is_empty());
+    }
+
+    #[test]
+    fn should_list_pending_changes() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+value = 1.0
+
+[[metrics]]
+name = "second"
+value = 2.0
+"#,
+        );
+        repo.set_note(
+            "aaaaaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 10.0
+
+[[changes]]
+action = "add"
+name = "third"
+tags = { foo = "bar" }
+value = 3.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+"#,
+        );
+        repo.set_note(
+            "bbbbbbbbbb",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 1.0
+"#,
+        );
+        repo.set_unreachable("bbbbbbbbbb");
+
+        let code = crate::Args::parse_from(["_", "status"]).command.execute(
+            repo,
+            false,
+            &mut stdout,
+            &mut stderr,
+        );
+
+        assert!(code.is_success(), "{:?}", String::from_utf8_lossy(&stderr));
+        assert!(stderr.is_empty());
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"* aaaaaaa
+    + first 10.00 (was 1.00)
+    + third{foo="bar"} 3.00 (new)
+    - second 2.00 (removed)
+    - second 2.00 (no effect)
+* bbbbbbb warning: not reachable from any branch
+    + first 1.00 (new)
+"#
+        );
+    }
+
+    #[test]
+    fn should_list_pending_changes_from_the_oldest_commit() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+        repo.set_commits([("aaaaaaaaaa", 200_000), ("bbbbbbbbbb", 100_000)].map(
+            |(sha, timestamp)| Commit {
+                sha: sha.into(),
+                summary: String::new(),
+                timestamp,
+                author: String::new(),
+                body: String::new(),
+            },
+        ));
+        for (sha, value) in [("aaaaaaaaaa", 2.0), ("bbbbbbbbbb", 1.0)] {
+            repo.set_note(
+                sha,
+                NoteRef::Changes,
+                format!("[[changes]]\naction = \"add\"\nname = \"first\"\nvalue = {value:?}\n"),
+            );
+        }
+
+        let code = crate::Args::parse_from(["_", "status"]).command.execute(
+            repo,
+            false,
+            &mut stdout,
+            &mut stderr,
+        );
+
+        assert!(code.is_success(), "{:?}", String::from_utf8_lossy(&stderr));
+        similar_asserts::assert_eq!(
+            String::from_utf8_lossy(&stdout),
+            r#"* bbbbbbb
+    + first 1.00 (new)
+* aaaaaaa
+    + first 2.00 (new)
+"#
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::num::NonZeroUsize;
+
+use super::prelude::{PrettyWriter, Tag};
+use crate::backend::Backend;
+use crate::collector::time::{Aggregate, TimeCollector, TimeCollectorOptions};
+use crate::collector::Collector;
+use crate::ExitCode;
+
+/// Measure the execution of a command and add the result as metrics
+///
+/// The wall clock time, user time, system time and peak memory usage of the
+/// command are collected, under the `<name>.time.wall`, `<name>.time.user`,
+/// `<name>.time.system` and `<name>.memory.peak` metrics.
+///
+///     git metrics time --name build -- cargo build --release
+///
+/// If the command fails, no metric is added and git-metrics exits with the
+/// exit code of the command.
+#[derive(clap::Parser, Debug)]
+pub struct CommandTime {
+    /// Remote name, default to origin
+    #[clap(long, default_value = "origin")]
+    remote: String,
+    /// Commit target, default to HEAD
+    #[clap(long, short, default_value = "HEAD")]
+    target: String,
+    /// Prefix of the metric names
+    #[clap(long)]
+    name: String,
+    /// Tag given to the metrics
+    #[clap(long)]
+    tag: Vec<Tag>,
+    /// Number of executions before starting measuring
+    #[clap(long, default_value = "0")]
+    warmup: usize,
+    /// Number of measured executions
+    #[clap(long, default_value = "1")]
+    repeat: NonZeroUsize,
+    /// How to combine the measured executions
+    #[clap(long, value_enum, default_value = "mean")]
+    aggregate: Aggregate,
+    /// Add every measured execution as a metric, with a `sample` tag, instead of combining them
+    #[clap(long)]
+    samples: bool,
+    /// Measure the command without adding the metrics to the repository
+    #[clap(long, default_value = "false")]
+    dry_run: bool,
+    #[clap(flatten)]
+    replace: super::ReplaceOptions,
+    /// Command to execute
+    #[clap(last = true, required = true)]
+    command: Vec<String>,
+}
+
+impl CommandTime {
+    fn collector(self) -> TimeCollector {
+        let mut command = self.command.into_iter();
+        let program = command.next().unwrap_or_default();
+        TimeCollector::new(
+            program,
+            command.collect(),
+            TimeCollectorOptions {
+                name: self.name,
+                tags: self
+                    .tag
+                    .into_iter()
+                    .map(|tag| (tag.name, tag.value))
+                    .collect(),
+                warmup: self.warmup,
+                repeat: self.repeat.get(),
+                aggregate: self.aggregate,
+                samples: self.samples,
+            },
+        )
+    }
+}
+
+impl super::Executor for CommandTime {
+    #[tracing::instrument(name = "time", skip_all, fields(target = self.target.as_str(), name = self.name.as_str()))]
+    fn execute<B: Backend, Out: PrettyWriter>(
+        mut self,
+        backend: B,
+        _stdout: Out,
+    ) -> Result<ExitCode, crate::service::Error> {
+        let remote = self.remote.clone();
+        let target = self.target.clone();
+        let dry_run = self.dry_run;
+        let replace = std::mem::take(&mut self.replace);
+        let metrics = self.collector().collect()?;
+
+        tracing::debug!("{} metrics collected", metrics.len());
+
+        if dry_run {
+            for metric in metrics {
+                tracing::info!("{metric:?}");
+            }
+            tracing::debug!("dry run aborting early");
+            return Ok(ExitCode::Success);
+        }
+
+        let svc = crate::service::Service::new(backend);
+        let opts = crate::service::add::Options {
+            remote,
+            target,
+            replace: replace.selectors(metrics.iter().map(|metric| metric.header.name.as_str())),
+        };
+
+        svc.add_all(metrics, &opts)?;
+        Ok(ExitCode::Success)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use clap::Parser;
+
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+
+    #[test]
+    fn should_add_timing_metrics() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+
+        let code = crate::Args::parse_from([
+            "_",
+            "time",
+            "--name",
+            "noop",
+            "--tag",
+            "os: linux",
+            "--",
+            "true",
+        ])
+        .command
+        .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(code.is_success());
+        assert!(stdout.is_empty());
+        assert!(stderr.is_empty());
+
+        let note = repo.get_note("HEAD", NoteRef::Changes).unwrap();
+        assert_eq!(note.matches("action = \"add\"").count(), 4);
+        assert!(note.contains("name = \"noop.time.wall\""));
+        assert!(note.contains("name = \"noop.memory.peak\""));
+        assert!(note.contains("os = \"linux\""));
+    }
+
+    #[test]
+    fn should_fail_with_child_exit_code() {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let repo = MockBackend::default();
+
+        let code =
+            crate::Args::parse_from(["_", "time", "--name", "noop", "--", "sh", "-c", "exit 42"])
+                .command
+                .execute(repo.clone(), false, &mut stdout, &mut stderr);
+
+        assert!(matches!(code, crate::ExitCode::Code(42)));
+        assert!(repo.get_note("HEAD", NoteRef::Changes).is_none());
+        assert_eq!(
+            String::from_utf8_lossy(&stderr),
+            "the command failed with exit code 42\n"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::entity::metric::Metric;
+
+/// Executes a command printing metrics on its standard output, with the
+/// `name{key="value"} 12.34` format of the `show` command.
+#[derive(Debug)]
+pub(crate) struct CommandCollector {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Directory the command is executed in
+    pub current_dir: PathBuf,
+}
+
+impl CommandCollector {
+    #[inline(always)]
+    pub(crate) fn new(program: String, args: Vec<String>, current_dir: PathBuf) -> Self {
+        Self {
+            program,
+            args,
+            current_dir,
+        }
+    }
+}
+
+impl super::Collector for CommandCollector {
+    fn collect(self) -> Result<Vec<Metric>, super::Error> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.current_dir)
+            .stderr(Stdio::inherit())
+            .output()?;
+        if !output.status.success() {
+            return Err(super::Error::Failed {
+                code: output.status.code().unwrap_or(1),
+            });
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        crate::importer::text::parse(&stdout)
+            .map_err(|source| super::Error::InvalidOutput { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandCollector;
+    use crate::collector::Collector;
+
+    #[cfg(unix)]
+    #[test]
+    fn should_collect_metrics_in_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("size"), "42").unwrap();
+        let collector = CommandCollector::new(
+            "sh".into(),
+            vec![
+                "-c".into(),
+                "echo \"# measured\"; echo \"binary-size{os=\\\"linux\\\"} $(cat size)\"".into(),
+            ],
+            dir.path().to_path_buf(),
+        );
+        let metrics = collector.collect().unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].header.name, "binary-size");
+        assert_eq!(metrics[0].header.tags.get("os").unwrap(), "linux");
+        assert_eq!(metrics[0].value, 42.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_fail_with_invalid_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let collector = CommandCollector::new(
+            "echo".into(),
+            vec!["not a metric".into()],
+            dir.path().to_path_buf(),
+        );
+        let err = collector.collect().unwrap_err();
+        assert!(err.to_string().starts_with("invalid output"), "{err}");
+    }
+}
@@ -0,0 +1,51 @@
+use crate::entity::metric::Metric;
+
+#[cfg(feature = "collector-command")]
+pub(crate) mod command;
+#[cfg(feature = "collector-time")]
+pub(crate) mod time;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("the command failed with exit code {code}")]
+    Failed { code: i32 },
+    #[cfg(feature = "collector-command")]
+    #[error("invalid output of the command")]
+    InvalidOutput {
+        #[source]
+        source: crate::importer::text::ParseError,
+    },
+}
+
+impl crate::error::DetailedError for Error {
+    fn details(&self) -> Option<String> {
+        match self {
+            Self::Io { source } => Some(source.to_string()),
+            Self::Failed { .. } => None,
+            #[cfg(feature = "collector-command")]
+            Self::InvalidOutput { source } => Some(source.to_string()),
+        }
+    }
+}
+
+impl Error {
+    pub(crate) const fn exit_code(&self) -> Option<i32> {
+        match self {
+            Self::Failed { code } => Some(*code),
+            Self::Io { .. } => None,
+            #[cfg(feature = "collector-command")]
+            Self::InvalidOutput { .. } => None,
+        }
+    }
+}
+
+/// A collector produces metrics by executing something, compared to an
+/// importer that reads metrics produced by an other tool.
+pub trait Collector {
+    fn collect(self) -> Result<Vec<Metric>, Error>;
+}
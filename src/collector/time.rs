@@ -0,0 +1,303 @@
+use std::process::Command;
+use std::time::Instant;
+
+use indexmap::IndexMap;
+
+use crate::entity::metric::{Metric, MetricHeader};
+
+/// Resources used by a single execution of the command.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Measure {
+    /// Wall clock duration, in seconds
+    wall: f64,
+    /// Time spent in user mode, in seconds
+    user: Option<f64>,
+    /// Time spent in kernel mode, in seconds
+    system: Option<f64>,
+    /// Peak resident set size, in bytes
+    max_rss: Option<f64>,
+}
+
+impl Measure {
+    /// Metric name suffix with the related value
+    fn values(&self) -> [(&'static str, Option<f64>); 4] {
+        [
+            ("time.wall", Some(self.wall)),
+            ("time.user", self.user),
+            ("time.system", self.system),
+            ("memory.peak", self.max_rss),
+        ]
+    }
+}
+
+#[cfg(unix)]
+fn run(command: &mut Command) -> Result<Measure, super::Error> {
+    use std::os::unix::process::ExitStatusExt;
+
+    #[inline]
+    fn seconds(value: libc::timeval) -> f64 {
+        value.tv_sec as f64 + value.tv_usec as f64 / 1_000_000.0
+    }
+
+    let start = Instant::now();
+    let child = command.spawn()?;
+    let pid = child.id() as libc::pid_t;
+
+    let mut status: libc::c_int = 0;
+    // SAFETY: rusage is a plain C struct for which all zero is a valid value.
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: the pointers are valid for the duration of the call and the
+        // pid belongs to a child that has not been waited for yet.
+        let res = unsafe { libc::wait4(pid, &mut status, 0, &mut usage) };
+        if res >= 0 {
+            break;
+        }
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::Interrupted {
+            return Err(error.into());
+        }
+    }
+    let wall = start.elapsed().as_secs_f64();
+
+    let status = std::process::ExitStatus::from_raw(status);
+    if !status.success() {
+        let code = status
+            .code()
+            .or_else(|| status.signal().map(|signal| 128 + signal))
+            .unwrap_or(1);
+        return Err(super::Error::Failed { code });
+    }
+
+    // linux reports the max rss in kilobytes, macos in bytes
+    let max_rss = if cfg!(target_os = "macos") {
+        usage.ru_maxrss as f64
+    } else {
+        usage.ru_maxrss as f64 * 1024.0
+    };
+
+    Ok(Measure {
+        wall,
+        user: Some(seconds(usage.ru_utime)),
+        system: Some(seconds(usage.ru_stime)),
+        max_rss: Some(max_rss),
+    })
+}
+
+#[cfg(not(unix))]
+fn run(command: &mut Command) -> Result<Measure, super::Error> {
+    let start = Instant::now();
+    let status = command.status()?;
+    let wall = start.elapsed().as_secs_f64();
+    if !status.success() {
+        return Err(super::Error::Failed {
+            code: status.code().unwrap_or(1),
+        });
+    }
+    Ok(Measure {
+        wall,
+        ..Default::default()
+    })
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum Aggregate {
+    #[default]
+    Mean,
+    Median,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    fn apply(&self, mut values: Vec<f64>) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        match self {
+            Self::Mean => Some(values.iter().sum::<f64>() / values.len() as f64),
+            Self::Median if values.len().is_multiple_of(2) => {
+                let middle = values.len() / 2;
+                Some((values[middle - 1] + values[middle]) / 2.0)
+            }
+            Self::Median => Some(values[values.len() / 2]),
+            Self::Min => values.first().copied(),
+            Self::Max => values.last().copied(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TimeCollectorOptions {
+    /// Prefix of the produced metric names
+    pub name: String,
+    /// Tags added to every produced metric
+    pub tags: IndexMap<String, String>,
+    /// Number of executions that are not measured
+    pub warmup: usize,
+    /// Number of measured executions
+    pub repeat: usize,
+    /// How the measured executions are combined
+    pub aggregate: Aggregate,
+    /// Keep every measured execution as a metric tagged with its index
+    pub samples: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct TimeCollector {
+    pub program: String,
+    pub args: Vec<String>,
+    pub options: TimeCollectorOptions,
+}
+
+impl TimeCollector {
+    #[inline(always)]
+    pub(crate) fn new(program: String, args: Vec<String>, options: TimeCollectorOptions) -> Self {
+        Self {
+            program,
+            args,
+            options,
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args);
+        cmd
+    }
+
+    fn metric(&self, suffix: &str, value: f64, sample: Option<usize>) -> Metric {
+        let mut tags = self.options.tags.clone();
+        if let Some(index) = sample {
+            tags.insert("sample".into(), index.to_string());
+        }
+        Metric {
+            header: MetricHeader {
+                name: format!("{}.{suffix}", self.options.name),
+                tags,
+            },
+            value,
+        }
+    }
+
+    fn build(&self, measures: Vec<Measure>) -> Vec<Metric> {
+        let mut res = Vec::with_capacity(measures.len() * 4);
+        if self.options.samples {
+            for (index, measure) in measures.iter().enumerate() {
+                for (suffix, value) in measure.values() {
+                    if let Some(value) = value {
+                        res.push(self.metric(suffix, value, Some(index)));
+                    }
+                }
+            }
+        } else {
+            let values = measures.iter().map(Measure::values).collect::<Vec<_>>();
+            for (position, (suffix, _)) in Measure::default().values().into_iter().enumerate() {
+                let values = values
+                    .iter()
+                    .filter_map(|item| item[position].1)
+                    .collect::<Vec<_>>();
+                if let Some(value) = self.options.aggregate.apply(values) {
+                    res.push(self.metric(suffix, value, None));
+                }
+            }
+        }
+        res
+    }
+}
+
+impl super::Collector for TimeCollector {
+    fn collect(self) -> Result<Vec<Metric>, super::Error> {
+        for index in 0..self.options.warmup {
+            tracing::debug!("warmup execution {index}");
+            run(&mut self.command())?;
+        }
+        let mut measures = Vec::with_capacity(self.options.repeat);
+        for index in 0..self.options.repeat {
+            tracing::debug!("measured execution {index}");
+            let measure = run(&mut self.command())?;
+            tracing::trace!("measured {measure:?}");
+            measures.push(measure);
+        }
+        Ok(self.build(measures))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Aggregate, Measure, TimeCollector, TimeCollectorOptions};
+    use crate::collector::Collector;
+
+    fn options(repeat: usize) -> TimeCollectorOptions {
+        TimeCollectorOptions {
+            name: "build".into(),
+            tags: Default::default(),
+            warmup: 0,
+            repeat,
+            aggregate: Aggregate::Mean,
+            samples: false,
+        }
+    }
+
+    #[test_case::test_case(Aggregate::Mean, vec![1.0, 2.0, 6.0], 3.0; "mean")]
+    #[test_case::test_case(Aggregate::Median, vec![6.0, 1.0, 2.0], 2.0; "median odd")]
+    #[test_case::test_case(Aggregate::Median, vec![6.0, 1.0, 2.0, 3.0], 2.5; "median even")]
+    #[test_case::test_case(Aggregate::Min, vec![6.0, 1.0, 2.0], 1.0; "min")]
+    #[test_case::test_case(Aggregate::Max, vec![6.0, 1.0, 2.0], 6.0; "max")]
+    fn should_aggregate(aggregate: Aggregate, values: Vec<f64>, expected: f64) {
+        assert_eq!(aggregate.apply(values), Some(expected));
+    }
+
+    #[test]
+    fn should_build_samples() {
+        let mut opts = options(2);
+        opts.samples = true;
+        let collector = TimeCollector::new("true".into(), Vec::new(), opts);
+        let metrics = collector.build(vec![
+            Measure {
+                wall: 1.0,
+                ..Default::default()
+            },
+            Measure {
+                wall: 2.0,
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].header.name, "build.time.wall");
+        assert_eq!(metrics[0].header.tags.get("sample").unwrap(), "0");
+        assert_eq!(metrics[1].header.tags.get("sample").unwrap(), "1");
+        assert_eq!(metrics[1].value, 2.0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_collect_metrics() {
+        let collector = TimeCollector::new("true".into(), Vec::new(), options(2));
+        let metrics = collector.collect().unwrap();
+        let names = metrics
+            .iter()
+            .map(|m| m.header.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "build.time.wall",
+                "build.time.user",
+                "build.time.system",
+                "build.memory.peak"
+            ]
+        );
+        assert!(metrics.iter().all(|m| m.value >= 0.0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_fail_with_exit_code() {
+        let collector =
+            TimeCollector::new("sh".into(), vec!["-c".into(), "exit 3".into()], options(1));
+        let err = collector.collect().unwrap_err();
+        assert_eq!(err.exit_code(), Some(3));
+    }
+}
@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+/// Error returned when a baseline cannot be parsed
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error(
+    "invalid baseline {0:?}, expected merge-base:<ref>, ref:<ref> or latest-with-metrics:<ref>"
+)]
+pub(crate) struct ParseError(String);
+
+/// Commit the metrics of the target are compared with
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Baseline {
+    /// Common ancestor of the target and the reference, like `origin/main`
+    MergeBase(String),
+    /// The reference itself
+    Ref(String),
+    /// Latest commit of the reference history with some metrics
+    LatestWithMetrics(String),
+}
+
+impl Baseline {
+    pub fn reference(&self) -> &str {
+        match self {
+            Self::MergeBase(reference)
+            | Self::Ref(reference)
+            | Self::LatestWithMetrics(reference) => reference.as_str(),
+        }
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (kind, reference) = value
+            .split_once(':')
+            .filter(|(_, reference)| !reference.is_empty())
+            .ok_or_else(|| ParseError(value.to_string()))?;
+        match kind {
+            "merge-base" => Ok(Self::MergeBase(reference.to_string())),
+            "ref" => Ok(Self::Ref(reference.to_string())),
+            "latest-with-metrics" => Ok(Self::LatestWithMetrics(reference.to_string())),
+            _ => Err(ParseError(value.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MergeBase(reference) => write!(f, "merge-base:{reference}"),
+            Self::Ref(reference) => write!(f, "ref:{reference}"),
+            Self::LatestWithMetrics(reference) => write!(f, "latest-with-metrics:{reference}"),
+        }
+    }
+}
+
+impl serde::Serialize for Baseline {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Baseline {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Baseline::from_str(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::Baseline;
+
+    #[test_case::test_case("merge-base:origin/main", Baseline::MergeBase("origin/main".into()); "merge base")]
+    #[test_case::test_case("ref:v1.0.0", Baseline::Ref("v1.0.0".into()); "reference")]
+    #[test_case::test_case("latest-with-metrics:main", Baseline::LatestWithMetrics("main".into()); "latest with metrics")]
+    fn should_parse(input: &str, expected: Baseline) {
+        let baseline = Baseline::from_str(input).unwrap();
+        assert_eq!(baseline, expected);
+        assert_eq!(baseline.to_string(), input);
+    }
+
+    #[test_case::test_case("main"; "without kind")]
+    #[test_case::test_case("ref:"; "without reference")]
+    #[test_case::test_case("branch:main"; "unknown kind")]
+    fn should_fail_parsing(input: &str) {
+        assert!(Baseline::from_str(input).is_err());
+    }
+}
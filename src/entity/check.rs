@@ -1,23 +1,23 @@
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
 use super::config::{
-    Config, MetricConfig, Rule, RuleAbsolute, RuleChange, RuleRelative, SubsetConfig,
+    Config, MetricConfig, Rule, RuleAbsolute, RuleChange, RuleRelative, RuleWindow, SubsetConfig,
+    TagMatcher, Unit, ZeroBaseline,
 };
-use super::difference::{Comparison, Delta, MetricDiff};
+use super::difference::{Comparison, Delta, History, MetricDiff, MetricDiffList};
+use super::expression::Aggregate;
+use super::metric::MetricHeader;
+use super::waiver::Waiver;
 
 #[derive(Clone, Copy, Debug, serde::Serialize)]
-#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(test, derive(Default, PartialEq))]
 pub(crate) enum Status {
     Success,
+    #[cfg_attr(test, default)]
     Skip,
     Failed,
-}
-
-#[cfg(test)]
-impl Default for Status {
-    fn default() -> Self {
-        Self::Skip
-    }
+    /// Failed but acknowledged by a commit trailer
+    Waived,
 }
 
 impl Status {
@@ -26,6 +26,7 @@ impl Status {
             Status::Success => "✅",
             Status::Skip => "⏭️",
             Status::Failed => "⛔️",
+            Status::Waived => "🙈",
         }
     }
 
@@ -48,6 +49,7 @@ pub(crate) struct StatusCount {
     pub success: usize,
     pub neutral: usize,
     pub failed: usize,
+    pub waived: usize,
 }
 
 impl StatusCount {
@@ -56,6 +58,7 @@ impl StatusCount {
             Status::Success => self.success += 1,
             Status::Skip => self.neutral += 1,
             Status::Failed => self.failed += 1,
+            Status::Waived => self.waived += 1,
         }
     }
 
@@ -63,6 +66,7 @@ impl StatusCount {
         self.success += other.success;
         self.neutral += other.neutral;
         self.failed += other.failed;
+        self.waived += other.waived;
     }
 
     pub fn is_failed(&self) -> bool {
@@ -72,6 +76,8 @@ impl StatusCount {
     pub fn status(&self) -> Status {
         if self.failed > 0 {
             Status::Failed
+        } else if self.waived > 0 {
+            Status::Waived
         } else if self.success > 0 {
             Status::Success
         } else {
@@ -80,6 +86,39 @@ impl StatusCount {
     }
 }
 
+impl RuleRelative {
+    /// Checks the change in a direction, `1.0` for an increase and `-1.0` for a decrease
+    fn check(&self, comparison: &Comparison, direction: f64) -> Status {
+        let Comparison::Matching {
+            previous,
+            current,
+            delta,
+        } = comparison
+        else {
+            return Status::Skip;
+        };
+        let exceeded = match delta.relative {
+            Some(relative) => relative * direction > self.ratio,
+            None => match self.zero_baseline {
+                ZeroBaseline::Skip => return Status::Skip,
+                ZeroBaseline::Fail => delta.absolute != 0.0,
+                ZeroBaseline::Infinite => delta.absolute * direction > 0.0,
+            },
+        };
+        let significant = self
+            .min_absolute
+            .is_none_or(|min| delta.absolute * direction > min)
+            && self
+                .ignore_below
+                .is_none_or(|limit| previous.max(*current) >= limit);
+        if exceeded && significant {
+            Status::Failed
+        } else {
+            Status::Success
+        }
+    }
+}
+
 impl Rule {
     fn check(&self, comparison: &Comparison) -> Status {
         match self {
@@ -89,7 +128,7 @@ impl Rule {
                 {
                     Status::Failed
                 }
-                Comparison::Missing { .. } => Status::Skip,
+                Comparison::Missing { .. } | Comparison::Absent => Status::Skip,
                 _ => Status::Success,
             },
             Self::Min(RuleAbsolute { value }) => match comparison {
@@ -98,65 +137,66 @@ impl Rule {
                 {
                     Status::Failed
                 }
-                Comparison::Missing { .. } => Status::Skip,
+                Comparison::Missing { .. } | Comparison::Absent => Status::Skip,
                 _ => Status::Success,
             },
             Self::MaxIncrease(RuleChange::Absolute(RuleAbsolute { value })) => match comparison {
-                Comparison::Created { .. } | Comparison::Missing { .. } => Status::Skip,
+                Comparison::Created { .. } | Comparison::Missing { .. } | Comparison::Absent => {
+                    Status::Skip
+                }
                 Comparison::Matching {
                     delta: Delta { absolute, .. },
                     ..
                 } if absolute > value => Status::Failed,
                 _ => Status::Success,
             },
-            Self::MaxIncrease(RuleChange::Relative(RuleRelative { ratio })) => match comparison {
-                Comparison::Matching {
-                    delta:
-                        Delta {
-                            relative: Some(relative),
-                            ..
-                        },
-                    ..
-                } if relative > ratio => Status::Failed,
-                Comparison::Matching {
-                    delta:
-                        Delta {
-                            relative: Some(_), ..
-                        },
-                    ..
-                } => Status::Success,
-                _ => Status::Skip,
-            },
-            Self::MaxDecrease(RuleChange::Relative(RuleRelative { ratio })) => match comparison {
-                Comparison::Matching {
-                    delta:
-                        Delta {
-                            relative: Some(relative),
-                            ..
-                        },
-                    ..
-                } if *relative < (*ratio) * -1.0 => Status::Failed,
-                Comparison::Matching {
-                    delta:
-                        Delta {
-                            relative: Some(_), ..
-                        },
-                    ..
-                } => Status::Success,
-                _ => Status::Skip,
-            },
+            Self::MaxIncrease(RuleChange::Relative(rule)) => rule.check(comparison, 1.0),
+            Self::MaxDecrease(RuleChange::Relative(rule)) => rule.check(comparison, -1.0),
             Self::MaxDecrease(RuleChange::Absolute(RuleAbsolute { value })) => match comparison {
-                Comparison::Created { .. } | Comparison::Missing { .. } => Status::Skip,
+                Comparison::Created { .. } | Comparison::Missing { .. } | Comparison::Absent => {
+                    Status::Skip
+                }
                 Comparison::Matching {
                     delta: Delta { absolute, .. },
                     ..
                 } if *absolute < (*value) * -1.0 => Status::Failed,
                 _ => Status::Success,
             },
+            // the trend rules are checked with the history, see `check_diff`
+            Self::MaxIncreaseOver(_) => Status::Skip,
+            Self::Required => match comparison {
+                Comparison::Missing { .. } | Comparison::Absent => Status::Failed,
+                _ => Status::Success,
+            },
+        }
+    }
+
+    /// Checks the rule, comparing the trend rules with the value at the beginning of their window
+    fn check_diff(&self, diff: &MetricDiff, history: &History) -> Status {
+        match self {
+            Self::MaxIncreaseOver(RuleWindow { window, change }) => {
+                match (history.get(window, &diff.header), diff.comparison.current()) {
+                    (Some(previous), current @ Some(_)) => {
+                        Self::MaxIncrease(*change).check(&Comparison::new(previous, current))
+                    }
+                    _ => Status::Skip,
+                }
+            }
+            _ => self.check(&diff.comparison),
         }
     }
 }
 
+/// Rules to check for a comparison, a metric without current value is only checked for being required
+fn applicable_rules<'a>(
+    rules: &'a [Rule],
+    required: bool,
+    comparison: &Comparison,
+) -> impl Iterator<Item = &'a Rule> + 'a {
+    let rules = if comparison.has_current() { rules } else { &[] };
+    rules.iter().chain(required.then_some(&Rule::Required))
+}
+
 #[derive(Debug, serde::Serialize)]
 #[cfg_attr(test, derive(PartialEq))]
 pub struct RuleCheck {
@@ -164,17 +204,25 @@ pub struct RuleCheck {
     pub status: Status,
 }
 
+impl RuleCheck {
+    fn waive(&mut self) {
+        if self.status.is_failed() {
+            self.status = Status::Waived;
+        }
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 #[cfg_attr(test, derive(Default, PartialEq))]
 pub(crate) struct SubsetCheck {
-    pub matching: IndexMap<String, String>,
+    pub matching: IndexMap<String, TagMatcher>,
     pub checks: Vec<RuleCheck>,
     pub status: StatusCount,
 }
 
 #[cfg(test)]
 impl SubsetCheck {
-    pub fn with_matching<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+    pub fn with_matching<N: Into<String>>(mut self, name: N, value: &str) -> Self {
         self.matching.insert(name.into(), value.into());
         self
     }
@@ -187,12 +235,12 @@ impl SubsetCheck {
 }
 
 impl SubsetCheck {
-    fn evaluate(config: &SubsetConfig, diff: &MetricDiff) -> Self {
+    fn evaluate(config: &SubsetConfig, diff: &MetricDiff, history: &History) -> Self {
         let mut status = StatusCount::default();
         let mut checks = Vec::with_capacity(config.rules.len());
         if config.matches(&diff.header) {
-            for rule in config.rules.iter() {
-                let res = rule.check(&diff.comparison);
+            for rule in applicable_rules(&config.rules, config.required, &diff.comparison) {
+                let res = rule.check_diff(diff, history);
                 status.push(&res);
                 checks.push(RuleCheck {
                     rule: *rule,
@@ -208,6 +256,20 @@ impl SubsetCheck {
     }
 }
 
+/// Subset whose matching metrics are aggregated in a single comparison
+#[derive(Debug, serde::Serialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct AggregateCheck {
+    pub subset: String,
+    pub aggregate: Aggregate,
+}
+
+impl std::fmt::Display for AggregateCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of subset {:?}", self.aggregate, self.subset)
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 #[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct MetricCheck {
@@ -215,6 +277,9 @@ pub(crate) struct MetricCheck {
     pub checks: Vec<RuleCheck>,
     pub subsets: IndexMap<String, SubsetCheck>,
     pub status: StatusCount,
+    /// Defined when the diff is the aggregate of the metrics matching a subset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<AggregateCheck>,
 }
 
 #[cfg(test)]
@@ -225,6 +290,7 @@ impl MetricCheck {
             checks: Default::default(),
             subsets: Default::default(),
             status: StatusCount::default(),
+            aggregate: None,
         }
     }
 
@@ -249,15 +315,23 @@ impl MetricCheck {
             checks: Vec::with_capacity(0),
             subsets: IndexMap::with_capacity(0),
             status: StatusCount::default(),
+            aggregate: None,
         }
     }
 
-    fn evaluate(config: &MetricConfig, diff: MetricDiff) -> Self {
+    fn evaluate(
+        config: &MetricConfig,
+        diff: MetricDiff,
+        history: &History,
+        fail_on_missing: bool,
+    ) -> Self {
         let mut global_status = StatusCount::default();
 
+        let required = config.required
+            || (fail_on_missing && matches!(diff.comparison, Comparison::Missing { .. }));
         let mut checks = Vec::with_capacity(config.rules.len());
-        for rule in config.rules.iter() {
-            let status = rule.check(&diff.comparison);
+        for rule in applicable_rules(&config.rules, required, &diff.comparison) {
+            let status = rule.check_diff(&diff, history);
             global_status.push(&status);
             checks.push(RuleCheck {
                 rule: *rule,
@@ -266,8 +340,13 @@ impl MetricCheck {
         }
 
         let mut subsets = IndexMap::with_capacity(config.subsets.len());
-        for (name, subset) in config.subsets.iter() {
-            let res = SubsetCheck::evaluate(subset, &diff);
+        // the aggregated subsets are checked once, see `MetricCheck::aggregate`
+        for (name, subset) in config
+            .subsets
+            .iter()
+            .filter(|(_, subset)| subset.aggregate.is_none())
+        {
+            let res = SubsetCheck::evaluate(subset, &diff, history);
             global_status.extend(&res.status);
             subsets.insert(name.to_owned(), res);
         }
@@ -277,13 +356,164 @@ impl MetricCheck {
             checks,
             subsets,
             status: global_status,
+            aggregate: None,
         }
     }
+
+    /// Check of the rules of a subset against the aggregate of the matching metrics,
+    /// the trend rules being checked against the aggregate of the history
+    fn aggregate(
+        name: &str,
+        subset_name: &str,
+        config: &SubsetConfig,
+        aggregate: Aggregate,
+        list: &[MetricDiff],
+        history: &History,
+    ) -> Option<Self> {
+        let matches = |header: &MetricHeader| header.name == name && config.matches(header);
+        let matching = list
+            .iter()
+            .filter(|item| matches(&item.header))
+            .collect::<Vec<_>>();
+        let previous = aggregate.apply(
+            matching
+                .iter()
+                .filter_map(|item| item.comparison.previous()),
+        );
+        let current = aggregate.apply(matching.iter().filter_map(|item| item.comparison.current()));
+        let comparison = match (previous, current) {
+            (Some(previous), current) => Comparison::new(previous, current),
+            (None, Some(current)) => Comparison::created(current),
+            (None, None) if config.required => Comparison::Absent,
+            (None, None) => return None,
+        };
+        let diff = MetricDiff {
+            header: MetricHeader {
+                name: name.to_string(),
+                tags: Default::default(),
+            },
+            comparison,
+        };
+        let history = history.aggregate(&diff.header, aggregate, matches);
+        let mut status = StatusCount::default();
+        let mut checks = Vec::with_capacity(config.rules.len());
+        for rule in applicable_rules(&config.rules, config.required, &diff.comparison) {
+            let res = rule.check_diff(&diff, &history);
+            status.push(&res);
+            checks.push(RuleCheck {
+                rule: *rule,
+                status: res,
+            });
+        }
+        Some(Self {
+            diff,
+            checks,
+            subsets: IndexMap::with_capacity(0),
+            status,
+            aggregate: Some(AggregateCheck {
+                subset: subset_name.to_string(),
+                aggregate,
+            }),
+        })
+    }
+
+    /// Marks the failed rules as waived when a waiver allows the change of the metric
+    fn waive(&mut self, waivers: &[&Waiver], unit: &Unit) {
+        if !waivers
+            .iter()
+            .any(|waiver| waiver.allows(&self.diff.comparison, unit))
+        {
+            return;
+        }
+        let mut status = StatusCount::default();
+        for check in self.checks.iter_mut() {
+            check.waive();
+            status.push(&check.status);
+        }
+        for subset in self.subsets.values_mut() {
+            subset.status = StatusCount::default();
+            for check in subset.checks.iter_mut() {
+                check.waive();
+                subset.status.push(&check.status);
+            }
+            status.extend(&subset.status);
+        }
+        self.status = status;
+    }
+
+    /// Check of a metric without any configuration that disappeared
+    fn missing(diff: MetricDiff) -> Self {
+        let mut status = StatusCount::default();
+        status.push(&Status::Failed);
+        Self {
+            diff,
+            checks: vec![RuleCheck {
+                rule: Rule::Required,
+                status: Status::Failed,
+            }],
+            subsets: IndexMap::with_capacity(0),
+            status,
+            aggregate: None,
+        }
+    }
+
+    /// Check of a required metric, or subset, without any value
+    fn absent(name: &str, config: &MetricConfig, existing: &[&MetricHeader]) -> Option<Self> {
+        let failed = || RuleCheck {
+            rule: Rule::Required,
+            status: Status::Failed,
+        };
+        let mut status = StatusCount::default();
+        let mut checks = Vec::new();
+        if config.required && existing.is_empty() {
+            status.push(&Status::Failed);
+            checks.push(failed());
+        }
+        let mut subsets = IndexMap::new();
+        // the aggregated subsets are reported by `MetricCheck::aggregate`
+        for (subset_name, subset) in config
+            .subsets
+            .iter()
+            .filter(|(_, subset)| subset.aggregate.is_none())
+        {
+            if subset.required && !existing.iter().any(|header| subset.matches(header)) {
+                let mut subset_status = StatusCount::default();
+                subset_status.push(&Status::Failed);
+                status.extend(&subset_status);
+                subsets.insert(
+                    subset_name.to_owned(),
+                    SubsetCheck {
+                        matching: subset.matching.clone(),
+                        checks: vec![failed()],
+                        status: subset_status,
+                    },
+                );
+            }
+        }
+        status.is_failed().then(|| Self {
+            diff: MetricDiff {
+                header: MetricHeader {
+                    name: name.to_string(),
+                    tags: Default::default(),
+                },
+                comparison: Comparison::Absent,
+            },
+            checks,
+            subsets,
+            status,
+            aggregate: None,
+        })
+    }
 }
 
 #[derive(Debug, serde::Serialize)]
 #[cfg_attr(test, derive(Default, PartialEq))]
 pub(crate) struct CheckList {
+    /// Commit the metrics are compared with
+    pub baseline: Option<String>,
+    /// Overrides of the configuration applied to the rules
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<String>,
     pub status: StatusCount,
     pub list: Vec<MetricCheck>,
 }
@@ -298,21 +528,124 @@ impl CheckList {
 }
 
 impl CheckList {
-    pub fn evaluate(config: &Config, diff: Vec<MetricDiff>) -> Self {
+    pub fn with_overrides(mut self, overrides: Vec<String>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Waives the failed rules of the metrics with a change allowed by a waiver
+    pub fn with_waivers(mut self, config: &Config, waivers: &[Waiver]) -> Self {
+        if waivers.is_empty() {
+            return self;
+        }
+        let default_unit = Unit::default();
+        let mut status = StatusCount::default();
+        for check in self.list.iter_mut() {
+            let name = check.diff.header.name.as_str();
+            let matching = waivers
+                .iter()
+                .filter(|waiver| waiver.metric == name)
+                .collect::<Vec<_>>();
+            if !matching.is_empty() {
+                let unit = config
+                    .metric(name)
+                    .map(|config| &config.unit)
+                    .unwrap_or(&default_unit);
+                check.waive(&matching, unit);
+            }
+            status.extend(&check.status);
+        }
+        self.status = status;
+        self
+    }
+
+    /// Checks the metrics against the rules.
+    ///
+    /// The metrics missing on the target are only kept when they are required.
+    pub fn evaluate(config: &Config, diff: MetricDiffList) -> Self {
+        let MetricDiffList {
+            baseline,
+            history,
+            list: diff,
+        } = diff;
+        let fail_on_missing = config.check.fail_on_missing;
         let mut list = Vec::with_capacity(diff.len());
         let mut status = StatusCount::default();
 
-        for item in diff.into_iter() {
-            if let Some(config) = config.metrics.get(&item.header.name) {
-                let check = MetricCheck::evaluate(config, item);
+        for (name, metric_config) in config.metrics.iter() {
+            let existing = diff
+                .iter()
+                .filter(|item| item.header.name == *name)
+                .map(|item| &item.header)
+                .collect::<Vec<_>>();
+            if let Some(check) = MetricCheck::absent(name, metric_config, &existing) {
                 status.extend(&check.status);
                 list.push(check);
-            } else {
-                list.push(MetricCheck::neutral(item));
+            }
+        }
+        for pattern in config.patterns.iter() {
+            let existing = diff
+                .iter()
+                .filter(|item| pattern.pattern.matches(&item.header.name))
+                .map(|item| &item.header)
+                .collect::<Vec<_>>();
+            let name = pattern.pattern.to_string();
+            if let Some(check) = MetricCheck::absent(&name, &pattern.metric, &existing) {
+                status.extend(&check.status);
+                list.push(check);
+            }
+        }
+
+        let mut aggregates = Vec::new();
+        // the configured metrics without any value can have a required aggregated subset
+        let names = diff
+            .iter()
+            .map(|item| item.header.name.as_str())
+            .chain(config.metrics.keys().map(String::as_str))
+            .collect::<IndexSet<_>>();
+        for name in names {
+            let Some(metric_config) = config.metric(name) else {
+                continue;
+            };
+            for (subset_name, subset) in metric_config.subsets.iter() {
+                if let Some(aggregate) = subset.aggregate {
+                    aggregates.extend(MetricCheck::aggregate(
+                        name,
+                        subset_name,
+                        subset,
+                        aggregate,
+                        &diff,
+                        &history,
+                    ));
+                }
             }
         }
 
-        Self { status, list }
+        for item in diff.into_iter() {
+            let missing = !item.comparison.has_current();
+            let check = match config.metric(&item.header.name) {
+                Some(config) => MetricCheck::evaluate(config, item, &history, fail_on_missing),
+                None if missing && fail_on_missing => MetricCheck::missing(item),
+                None => MetricCheck::neutral(item),
+            };
+            if missing && !check.status.is_failed() {
+                continue;
+            }
+            status.extend(&check.status);
+            list.push(check);
+        }
+
+        for check in aggregates {
+            status.extend(&check.status);
+            list.push(check);
+        }
+
+        Self {
+            baseline,
+            overrides: Vec::new(),
+            status,
+            list,
+        }
     }
 }
 
@@ -383,6 +716,55 @@ mod tests {
         assert_eq!(rule.check(&Comparison::new(10.0, None)), Status::Skip);
     }
 
+    #[test]
+    fn should_check_relative_increase_with_guards() {
+        let rule = Rule::MaxIncrease(RuleChange::Relative(RuleRelative {
+            ratio: 0.1,
+            min_absolute: Some(5.0),
+            ignore_below: Some(20.0),
+            zero_baseline: ZeroBaseline::Skip,
+        }));
+        // tiny metric going from 2 to 3
+        assert_eq!(
+            rule.check(&Comparison::new(2.0, Some(3.0))),
+            Status::Success
+        );
+        // small absolute increase on a bigger metric
+        assert_eq!(
+            rule.check(&Comparison::new(30.0, Some(34.0))),
+            Status::Success
+        );
+        assert_eq!(
+            rule.check(&Comparison::new(30.0, Some(40.0))),
+            Status::Failed
+        );
+        // big absolute increase staying below the limit
+        assert_eq!(
+            rule.check(&Comparison::new(1.0, Some(19.0))),
+            Status::Success
+        );
+        assert_eq!(rule.check(&Comparison::new(0.0, Some(40.0))), Status::Skip);
+    }
+
+    #[test_case::test_case(ZeroBaseline::Skip, 10.0, Status::Skip; "skip on increase")]
+    #[test_case::test_case(ZeroBaseline::Fail, 10.0, Status::Failed; "fail on increase")]
+    #[test_case::test_case(ZeroBaseline::Fail, -10.0, Status::Failed; "fail on decrease")]
+    #[test_case::test_case(ZeroBaseline::Fail, 0.0, Status::Success; "fail without change")]
+    #[test_case::test_case(ZeroBaseline::Infinite, 10.0, Status::Failed; "infinite on increase")]
+    #[test_case::test_case(ZeroBaseline::Infinite, -10.0, Status::Success; "infinite on decrease")]
+    fn should_check_relative_increase_from_zero(
+        zero_baseline: ZeroBaseline,
+        current: f64,
+        expected: Status,
+    ) {
+        let rule = Rule::MaxIncrease(RuleChange::Relative(RuleRelative {
+            ratio: 0.1,
+            zero_baseline,
+            ..Default::default()
+        }));
+        assert_eq!(rule.check(&Comparison::new(0.0, Some(current))), expected);
+    }
+
     #[test]
     fn should_check_max_relative_decrease() {
         let rule = Rule::max_relative_decrease(0.1);
@@ -417,4 +799,16 @@ mod tests {
         );
         assert_eq!(rule.check(&Comparison::new(10.0, None)), Status::Skip);
     }
+
+    #[test]
+    fn should_check_required() {
+        let rule = Rule::Required;
+        assert_eq!(rule.check(&Comparison::created(0.0)), Status::Success);
+        assert_eq!(
+            rule.check(&Comparison::new(20.0, Some(15.0))),
+            Status::Success
+        );
+        assert_eq!(rule.check(&Comparison::new(10.0, None)), Status::Failed);
+        assert_eq!(rule.check(&Comparison::Absent), Status::Failed);
+    }
 }
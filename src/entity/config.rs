@@ -1,35 +1,184 @@
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use human_number::Formatter;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
 
-use super::metric::MetricHeader;
+use super::baseline::Baseline;
+use super::expression::{Aggregate, Expression};
+use super::metric::{Metric, MetricChange, MetricHeader, MetricStack};
+use super::validation::KeyPath;
+use crate::formatter::value::ValueFormatter;
+
+mod include;
+
+pub(crate) use include::NESTED_PATHSPEC;
 
 const NO_SCALE: human_number::Scales<'static> = human_number::Scales::new(&[], &[]);
 
 #[inline]
-fn undefined_unit_formatter() -> human_number::Formatter<'static> {
-    human_number::Formatter::new(NO_SCALE, human_number::Options::default())
+fn undefined_unit_formatter() -> ValueFormatter<'static> {
+    human_number::Formatter::new(NO_SCALE, human_number::Options::default()).into()
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct RuleAbsolute {
     pub value: f64,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+/// Behaviour of a relative rule when the previous value is zero and no ratio can be computed
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ZeroBaseline {
+    /// The rule is skipped
+    #[default]
+    Skip,
+    /// The rule fails when the value changes
+    Fail,
+    /// The change is considered infinite, the rule fails when the value changes in its direction
+    Infinite,
+}
+
+impl ZeroBaseline {
+    fn is_skip(&self) -> bool {
+        matches!(self, Self::Skip)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct RuleRelative {
     pub ratio: f64,
+    /// The rule only fails when the absolute change is also greater than this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_absolute: Option<f64>,
+    /// The rule only fails when the previous or current value is at least this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_below: Option<f64>,
+    #[serde(default, skip_serializing_if = "ZeroBaseline::is_skip")]
+    pub zero_baseline: ZeroBaseline,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
-#[serde(untagged)]
+#[serde(untagged, try_from = "RuleChangeFields")]
 pub(crate) enum RuleChange {
     Absolute(RuleAbsolute),
     Relative(RuleRelative),
 }
 
+/// Fields of the rules comparing with the baseline, to report the unknown ones
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleChangeFields {
+    value: Option<f64>,
+    ratio: Option<f64>,
+    min_absolute: Option<f64>,
+    ignore_below: Option<f64>,
+    #[serde(default)]
+    zero_baseline: ZeroBaseline,
+}
+
+impl TryFrom<RuleChangeFields> for RuleChange {
+    type Error = &'static str;
+
+    fn try_from(fields: RuleChangeFields) -> Result<Self, Self::Error> {
+        let relative_only = fields.min_absolute.is_some()
+            || fields.ignore_below.is_some()
+            || !fields.zero_baseline.is_skip();
+        match (fields.value, fields.ratio) {
+            (Some(value), None) if !relative_only => Ok(Self::Absolute(RuleAbsolute { value })),
+            (Some(_), None) => {
+                Err("`min_absolute`, `ignore_below` and `zero_baseline` only apply with `ratio`")
+            }
+            (None, Some(ratio)) => Ok(Self::Relative(RuleRelative {
+                ratio,
+                min_absolute: fields.min_absolute,
+                ignore_below: fields.ignore_below,
+                zero_baseline: fields.zero_baseline,
+            })),
+            (Some(_), Some(_)) => Err("expected either `value` or `ratio`, not both"),
+            (None, None) => Err("missing field `value` or `ratio`"),
+        }
+    }
+}
+
+/// History considered by a trend rule, following the first parents of the baseline
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "WindowValue", into = "WindowValue")]
+pub(crate) enum Window {
+    Commits(usize),
+    Days(u64),
+}
+
+/// Window written as a number of commits, or a number of days like `"30d"`
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum WindowValue {
+    Commits(usize),
+    Duration(String),
+}
+
+impl TryFrom<WindowValue> for Window {
+    type Error = String;
+
+    fn try_from(value: WindowValue) -> Result<Self, Self::Error> {
+        match value {
+            WindowValue::Commits(count) => Ok(Self::Commits(count)),
+            WindowValue::Duration(input) => ["days", "day", "d"]
+                .iter()
+                .find_map(|suffix| input.trim().strip_suffix(suffix))
+                .and_then(|days| days.trim().parse::<u64>().ok())
+                .map(Self::Days)
+                .ok_or_else(|| {
+                    format!("invalid window {input:?}, expected a number of commits or days like \"30d\"")
+                }),
+        }
+    }
+}
+
+impl From<Window> for WindowValue {
+    fn from(value: Window) -> Self {
+        match value {
+            Window::Commits(count) => Self::Commits(count),
+            Window::Days(days) => Self::Duration(format!("{days}d")),
+        }
+    }
+}
+
+impl std::fmt::Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Commits(1) => f.write_str("1 commit"),
+            Self::Commits(count) => write!(f, "{count} commits"),
+            Self::Days(1) => f.write_str("1 day"),
+            Self::Days(days) => write!(f, "{days} days"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "toml::Table")]
+pub(crate) struct RuleWindow {
+    pub window: Window,
+    #[serde(flatten)]
+    pub change: RuleChange,
+}
+
+/// The flattened change cannot report the unknown fields, the window is split from the change
+impl TryFrom<toml::Table> for RuleWindow {
+    type Error = toml::de::Error;
+
+    fn try_from(mut table: toml::Table) -> Result<Self, Self::Error> {
+        let window = table
+            .remove("window")
+            .ok_or_else(|| <Self::Error as serde::de::Error>::missing_field("window"))?;
+        Ok(Self {
+            window: window.try_into()?,
+            change: toml::Value::Table(table).try_into()?,
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub(crate) enum Rule {
@@ -37,6 +186,11 @@ pub(crate) enum Rule {
     Min(RuleAbsolute),
     MaxIncrease(RuleChange),
     MaxDecrease(RuleChange),
+    /// The increase compared with the value at the beginning of the window
+    MaxIncreaseOver(RuleWindow),
+    /// The metric should exist on the target, only set with the `required` option
+    #[serde(skip_deserializing)]
+    Required,
 }
 
 #[cfg(test)]
@@ -50,7 +204,10 @@ impl Rule {
     }
 
     pub fn max_relative_increase(ratio: f64) -> Self {
-        Self::MaxIncrease(RuleChange::Relative(RuleRelative { ratio }))
+        Self::MaxIncrease(RuleChange::Relative(RuleRelative {
+            ratio,
+            ..Default::default()
+        }))
     }
 
     pub fn min(value: f64) -> Self {
@@ -62,23 +219,113 @@ impl Rule {
     }
 
     pub fn max_relative_decrease(ratio: f64) -> Self {
-        Self::MaxDecrease(RuleChange::Relative(RuleRelative { ratio }))
+        Self::MaxDecrease(RuleChange::Relative(RuleRelative {
+            ratio,
+            ..Default::default()
+        }))
+    }
+}
+
+/// Condition on the value of a tag
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TagCondition {
+    /// The tag value matches the regular expression
+    Regex(#[serde(with = "serde_regex")] regex::Regex),
+    /// The tag is missing or doesn't match
+    Not(Box<TagMatcher>),
+    /// The tag is defined, or not
+    Exists(bool),
+}
+
+impl PartialEq for TagCondition {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Regex(first), Self::Regex(second)) => first.as_str() == second.as_str(),
+            (Self::Not(first), Self::Not(second)) => first == second,
+            (Self::Exists(first), Self::Exists(second)) => first == second,
+            _ => false,
+        }
+    }
+}
+
+/// Matcher for the value of a tag, in a subset
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub(crate) enum TagMatcher {
+    /// The tag has exactly this value
+    Exact(String),
+    /// The tag has one of those values
+    AnyOf(Vec<String>),
+    Condition(TagCondition),
+}
+
+impl From<&str> for TagMatcher {
+    fn from(value: &str) -> Self {
+        Self::Exact(value.to_string())
+    }
+}
+
+impl TagMatcher {
+    pub fn matches(&self, value: Option<&str>) -> bool {
+        match (self, value) {
+            (Self::Exact(expected), Some(value)) => expected == value,
+            (Self::AnyOf(expected), Some(value)) => expected.iter().any(|item| item == value),
+            (Self::Condition(TagCondition::Regex(regex)), Some(value)) => regex.is_match(value),
+            (Self::Condition(TagCondition::Not(inner)), value) => !inner.matches(value),
+            (Self::Condition(TagCondition::Exists(expected)), value) => {
+                *expected == value.is_some()
+            }
+            (_, None) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for TagMatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact(value) => write!(f, "{value:?}"),
+            Self::AnyOf(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{value:?}")?;
+                }
+                f.write_str("]")
+            }
+            Self::Condition(TagCondition::Regex(regex)) => write!(f, "/{}/", regex.as_str()),
+            Self::Condition(TagCondition::Not(inner)) => write!(f, "!{inner}"),
+            Self::Condition(TagCondition::Exists(true)) => f.write_str("*"),
+            Self::Condition(TagCondition::Exists(false)) => f.write_str("!*"),
+        }
     }
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct SubsetConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub(crate) matching: IndexMap<String, TagMatcher>,
+    /// A metric matching the subset should exist on the target
     #[serde(default)]
-    pub(crate) matching: IndexMap<String, String>,
+    pub required: bool,
     #[serde(default)]
     pub rules: Vec<Rule>,
+    /// Checks the rules once, against the aggregate of the matching metrics,
+    /// the trend rules against the aggregate of the history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<Aggregate>,
 }
 
 impl SubsetConfig {
     pub fn matches(&self, header: &MetricHeader) -> bool {
         self.matching
             .iter()
-            .all(|(key, value)| header.tags.get(key).map(|v| v == value).unwrap_or(false))
+            .all(|(key, matcher)| matcher.matches(header.tags.get(key).map(String::as_str)))
     }
 }
 
@@ -88,9 +335,42 @@ pub enum UnitScale {
     #[default]
     SI,
     Binary,
+    /// Durations expressed in the base unit
+    Duration,
+    /// Ratios displayed as percentages
+    Percent,
+}
+
+/// Unit of the values of a metric with the duration scale
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationBase {
+    Ns,
+    #[serde(rename = "µs", alias = "us")]
+    Us,
+    Ms,
+    #[default]
+    S,
+}
+
+impl DurationBase {
+    const fn factor(self) -> f64 {
+        match self {
+            Self::Ns => 1.0e-9,
+            Self::Us => 1.0e-6,
+            Self::Ms => 1.0e-3,
+            Self::S => 1.0,
+        }
+    }
+
+    /// Converts a value expressed in this unit to seconds
+    pub fn to_seconds(self, value: f64) -> f64 {
+        value * self.factor()
+    }
 }
 
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct Unit {
     #[serde(default)]
     pub scale: Option<UnitScale>,
@@ -98,6 +378,9 @@ pub(crate) struct Unit {
     pub suffix: Option<String>,
     #[serde(default)]
     pub decimals: Option<usize>,
+    /// Unit of the values with the duration scale, in seconds by default
+    #[serde(default)]
+    pub base: Option<DurationBase>,
 }
 
 #[cfg(test)]
@@ -107,6 +390,7 @@ impl Unit {
             scale: Some(scale),
             suffix: suffix.map(|v| v.into()),
             decimals: None,
+            base: None,
         }
     }
 
@@ -120,12 +404,212 @@ impl Unit {
     }
 }
 
+/// Ratio computed by the lcov importer, like `coverage.lines.percentage`
+fn is_coverage_ratio(metric_name: &str) -> bool {
+    metric_name
+        .strip_prefix("coverage.")
+        .and_then(|name| name.strip_suffix(".percentage"))
+        .is_some_and(|kind| !kind.is_empty() && !kind.contains('.'))
+}
+
+const SI_PREFIXES: &[(&str, f64)] = &[
+    ("n", 1.0e-9),
+    ("µ", 1.0e-6),
+    ("μ", 1.0e-6),
+    ("u", 1.0e-6),
+    ("m", 1.0e-3),
+    ("k", 1.0e3),
+    ("M", 1.0e6),
+    ("G", 1.0e9),
+    ("T", 1.0e12),
+    ("P", 1.0e15),
+];
+
+const BINARY_PREFIXES: &[(&str, f64)] = &[
+    ("Ki", 1024.0),
+    ("ki", 1024.0),
+    ("Mi", 1048576.0),
+    ("Gi", 1073741824.0),
+    ("Ti", 1099511627776.0),
+    ("Pi", 1125899906842624.0),
+];
+
+/// Duration units, with their value in seconds
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("ns", 1.0e-9),
+    ("µs", 1.0e-6),
+    ("μs", 1.0e-6),
+    ("us", 1.0e-6),
+    ("ms", 1.0e-3),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("min", 60.0),
+    ("h", 3600.0),
+    ("d", 86400.0),
+];
+
+/// Splits the number at the beginning of the input, with an optional exponent like `1.5e6`.
+///
+/// The exponent needs digits, so `3events` keeps its `events` suffix.
+fn split_number(input: &str) -> (&str, &str) {
+    let bytes = input.as_bytes();
+    let mut end = usize::from(matches!(bytes.first(), Some(b'-' | b'+')));
+    while bytes
+        .get(end)
+        .is_some_and(|c| c.is_ascii_digit() || matches!(c, b'.' | b'_'))
+    {
+        end += 1;
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let mut exponent = end + 1;
+        if matches!(bytes.get(exponent), Some(b'-' | b'+')) {
+            exponent += 1;
+        }
+        if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+            end = exponent;
+            while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+                end += 1;
+            }
+        }
+    }
+    input.split_at(end)
+}
+
+fn parse_number(number: &str, input: &str) -> Result<f64, UnitError> {
+    number
+        .replace('_', "")
+        .parse::<f64>()
+        .map_err(|_| UnitError::InvalidNumber(input.to_string()))
+}
+
+/// Error returned when a value with a unit cannot be converted for a metric
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub(crate) enum UnitError {
+    #[error("invalid number in {0:?}")]
+    InvalidNumber(String),
+    #[error("unit {unit:?} is not compatible with the metric, expected {expected}")]
+    Incompatible { unit: String, expected: String },
+}
+
 impl Unit {
-    pub fn formater(&self) -> human_number::Formatter<'_> {
+    fn prefixes(&self) -> &'static [(&'static str, f64)] {
+        match self.scale {
+            Some(UnitScale::SI) => SI_PREFIXES,
+            Some(UnitScale::Binary) => BINARY_PREFIXES,
+            Some(UnitScale::Duration | UnitScale::Percent) | None => &[],
+        }
+    }
+
+    fn expected(&self) -> String {
+        let suffix = self.suffix.as_deref().unwrap_or_default();
+        match self.scale {
+            Some(UnitScale::SI) => format!("an SI prefix followed by {suffix:?}"),
+            Some(UnitScale::Binary) => format!("a binary prefix followed by {suffix:?}"),
+            Some(UnitScale::Duration) => "a duration unit like \"ms\", \"s\" or \"h\"".to_string(),
+            Some(UnitScale::Percent) => "\"%\"".to_string(),
+            None if suffix.is_empty() => "no unit".to_string(),
+            None => format!("{suffix:?}"),
+        }
+    }
+
+    /// Parses a duration made of one or several components, like `"12m 34s"`
+    fn parse_duration(&self, input: &str) -> Result<f64, UnitError> {
+        let base = self.base.unwrap_or_default();
+        let (sign, mut rest) = match input.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let mut seconds = 0.0;
+        while !rest.is_empty() {
+            let (number, tail) = split_number(rest);
+            let number = parse_number(number, input)?;
+            let tail = tail.trim_start();
+            let end = tail
+                .find(|c: char| c.is_whitespace() || c.is_ascii_digit())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(end);
+            let factor = DURATION_UNITS
+                .iter()
+                .find(|(name, _)| *name == unit)
+                .map(|(_, factor)| *factor)
+                .ok_or_else(|| UnitError::Incompatible {
+                    unit: unit.to_string(),
+                    expected: self.expected(),
+                })?;
+            seconds += number * factor;
+            rest = tail.trim_start();
+        }
+        Ok(sign * seconds / base.to_seconds(1.0))
+    }
+
+    /// Parses a value written with a unit, like `"10 MiB"`, into the value stored for the metric
+    pub(crate) fn parse(&self, input: &str) -> Result<f64, UnitError> {
+        let input = input.trim();
+        let (number, unit) = split_number(input);
+        let number = parse_number(number, input)?;
+        let unit = unit.trim();
+        let suffix = self.suffix.as_deref().unwrap_or_default();
+        if unit.is_empty() {
+            return Ok(number);
+        }
+        match self.scale {
+            Some(UnitScale::Duration) => return self.parse_duration(input),
+            Some(UnitScale::Percent) if unit == "%" => return Ok(number / 100.0),
+            _ if unit == suffix => return Ok(number),
+            _ => {}
+        }
+        self.prefixes()
+            .iter()
+            .find(|(prefix, _)| unit.strip_prefix(prefix) == Some(suffix))
+            .map(|(_, factor)| number * factor)
+            .ok_or_else(|| UnitError::Incompatible {
+                unit: unit.to_string(),
+                expected: self.expected(),
+            })
+    }
+
+    /// Unit to write the value with, like `"MiB"`, with the factor converting a number
+    /// written with this unit into the value stored for the metric
+    pub(crate) fn written_unit(&self, value: f64) -> (String, f64) {
+        let (units, fallback, base) = match self.scale {
+            Some(UnitScale::Percent) => return ("%".to_string(), 0.01),
+            Some(UnitScale::Duration) => (
+                DURATION_UNITS,
+                ("s", 1.0),
+                self.base.unwrap_or_default().to_seconds(1.0),
+            ),
+            _ => (self.prefixes(), ("", 1.0), 1.0),
+        };
+        let value = (value * base).abs();
+        // the largest unit not greater than the value, the first one when several have the same factor
+        let (unit, factor) = units
+            .iter()
+            .copied()
+            .chain(std::iter::once(fallback))
+            .filter(|(_, factor)| *factor <= value)
+            .fold(None, |best: Option<(&str, f64)>, item| match best {
+                Some(best) if best.1 >= item.1 => Some(best),
+                _ => Some(item),
+            })
+            .unwrap_or(fallback);
+        match self.scale {
+            Some(UnitScale::Duration) => (unit.to_string(), factor / base),
+            _ => (
+                format!("{unit}{}", self.suffix.as_deref().unwrap_or_default()),
+                factor,
+            ),
+        }
+    }
+
+    pub fn formater(&self) -> ValueFormatter<'_> {
         let mut formatter = match self.scale {
             Some(UnitScale::SI) => human_number::Formatter::si(),
             Some(UnitScale::Binary) => human_number::Formatter::binary(),
-            None => undefined_unit_formatter(),
+            Some(UnitScale::Duration) => {
+                return ValueFormatter::duration(self.base.unwrap_or_default(), self.decimals)
+            }
+            Some(UnitScale::Percent) => return ValueFormatter::percent(self.decimals),
+            None => human_number::Formatter::new(NO_SCALE, human_number::Options::default()),
         };
         if let Some(ref unit) = self.suffix {
             formatter.set_unit(unit.as_str());
@@ -133,13 +617,49 @@ impl Unit {
         if let Some(decimals) = self.decimals {
             formatter.set_decimals(decimals);
         }
-        formatter
+        formatter.into()
     }
 }
 
-#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
-#[cfg_attr(test, derive(Default))]
+/// Previous name of a metric, with the tag keys that have been renamed since
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged, deny_unknown_fields)]
+pub(crate) enum MetricAlias {
+    Name(String),
+    Detailed {
+        name: String,
+        /// Previous tag key with the related current key
+        #[serde(default)]
+        tags: IndexMap<String, String>,
+    },
+}
+
+impl MetricAlias {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Detailed { name, .. } => name.as_str(),
+        }
+    }
+
+    fn tag_key<'a>(&'a self, key: &'a str) -> &'a str {
+        match self {
+            Self::Detailed { tags, .. } => tags.get(key).map(String::as_str).unwrap_or(key),
+            Self::Name(_) => key,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct MetricConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Previous names of the metric, for the values recorded before a rename
+    #[serde(default)]
+    pub aliases: Vec<MetricAlias>,
+    /// The metric should exist on the target
+    #[serde(default)]
+    pub required: bool,
     #[serde(default)]
     pub rules: Vec<Rule>,
     #[serde(default)]
@@ -156,10 +676,173 @@ impl MetricConfig {
     }
 }
 
+mod serde_regex {
+    pub(super) fn serialize<S: serde::Serializer>(
+        value: &regex::Regex,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value.as_str())
+    }
+
+    pub(super) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<regex::Regex, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        regex::Regex::new(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Pattern matching the metric names
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum NamePattern {
+    /// `*` matches any sequence of characters and `?` matches a single character
+    Glob(String),
+    Regex(#[serde(with = "serde_regex")] regex::Regex),
+}
+
+impl NamePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => super::selector::glob_matches(pattern, name),
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl std::fmt::Display for NamePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Glob(pattern) => f.write_str(pattern),
+            Self::Regex(regex) => f.write_str(regex.as_str()),
+        }
+    }
+}
+
+/// Configuration shared by all the metrics matching a pattern
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "toml::Table")]
+pub(crate) struct PatternConfig {
+    #[serde(flatten)]
+    pub pattern: NamePattern,
+    #[serde(flatten)]
+    pub metric: MetricConfig,
+}
+
+/// The flattened fields cannot report the unknown ones, the pattern is split from the metric
+impl TryFrom<toml::Table> for PatternConfig {
+    type Error = toml::de::Error;
+
+    fn try_from(mut table: toml::Table) -> Result<Self, Self::Error> {
+        let pattern = ["glob", "regex"]
+            .into_iter()
+            .filter_map(|key| table.remove_entry(key))
+            .collect::<toml::Table>();
+        Ok(Self {
+            pattern: toml::Value::Table(pattern).try_into()?,
+            metric: toml::Value::Table(table).try_into()?,
+        })
+    }
+}
+
+/// Metric computed from the other metrics of the same commit
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct DerivedConfig {
+    pub expression: Expression,
+    /// Tags given to the computed metric
+    #[serde(default)]
+    pub tags: IndexMap<String, String>,
+}
+
+/// Rules replacing or extending the ones of a metric
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MetricOverride {
+    /// Replaces the rules of the metric instead of adding to them
+    #[serde(default)]
+    pub replace: bool,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+/// Rules applied only on some branches or baselines
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct OverrideConfig {
+    /// Glob matching the name of the checked branch, like `release/*`
+    ///
+    /// The checked branch is the one named by the target or the current one, unless set with `--branch`.
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Glob matching the reference of the baseline, like `origin/release/*`
+    #[serde(default)]
+    pub baseline: Option<String>,
+    #[serde(default)]
+    pub metrics: IndexMap<String, MetricOverride>,
+}
+
+impl OverrideConfig {
+    /// Every defined condition should match
+    pub fn matches(&self, branch: Option<&str>, baseline: Option<&str>) -> bool {
+        let condition_matches = |pattern: Option<&String>, value: Option<&str>| match pattern {
+            Some(pattern) => {
+                value.is_some_and(|value| super::selector::glob_matches(pattern, value))
+            }
+            None => true,
+        };
+        condition_matches(self.branch.as_ref(), branch)
+            && condition_matches(self.baseline.as_ref(), baseline)
+    }
+}
+
+impl std::fmt::Display for OverrideConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.branch.as_deref(), self.baseline.as_deref()) {
+            (Some(branch), Some(baseline)) => {
+                write!(f, "branch {branch} with baseline {baseline}")
+            }
+            (Some(branch), None) => write!(f, "branch {branch}"),
+            (None, Some(baseline)) => write!(f, "baseline {baseline}"),
+            (None, None) => f.write_str("always"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CheckConfig {
+    /// Fails when a metric existing before is missing on the target
+    #[serde(default)]
+    pub fail_on_missing: bool,
+    /// Commit to compare with, used by the check and diff commands when no baseline is provided
+    #[serde(default)]
+    pub baseline: Option<Baseline>,
+}
+
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
 pub(crate) struct Config {
+    /// Configuration files to merge, relative to the repository root, like `services/*/metrics.toml`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Loads the tracked `.git-metrics.toml` files of the subdirectories, their rules only
+    /// apply to the metrics having a `path` tag with the subdirectory
+    #[serde(default)]
+    pub discover: bool,
+    #[serde(default)]
+    pub check: CheckConfig,
     #[serde(default)]
     pub metrics: IndexMap<String, MetricConfig>,
+    /// Used for the metrics without an entry in `metrics`, the first matching pattern is used
+    #[serde(default)]
+    pub patterns: Vec<PatternConfig>,
+    /// Metrics computed in this order, a derived metric can use the previous ones
+    #[serde(default)]
+    pub derived: IndexMap<String, DerivedConfig>,
+    /// Rules replacing or extending the ones of the metrics on some branches
+    #[serde(default)]
+    pub overrides: Vec<OverrideConfig>,
 }
 
 #[cfg(test)]
@@ -171,10 +854,31 @@ impl Config {
 }
 
 const fn sample() -> &'static str {
-    r#"# # For every metric you want to monitor, you need to add an entry
+    r#"# # Other configuration files to merge, relative to the repository root (optional)
+# include = ["services/*/metrics.toml"]
+# # Loads the tracked .git-metrics.toml files of the subdirectories, their rules only apply
+# # to the metrics having a "path" tag with the subdirectory, like "services/api" (optional)
+# discover = true
+#
+# [check]
+# # Fails when a metric existing before is missing on the target (optional)
+# fail_on_missing = true
+# # Commit to compare with, can be "merge-base:<ref>", "ref:<ref>" or "latest-with-metrics:<ref>" (optional)
+# baseline = "merge-base:origin/main"
+#
+# # For every metric you want to monitor, you need to add an entry
+# [metrics.metric_name]
+# # The metric should exist on the target (optional)
+# required = true
+# # Previous names of the metric, to keep comparing with the values recorded before a rename (optional)
+# aliases = ["old_metric_name", { name = "older_metric_name", tags = { "old.tag" = "new.tag" } }]
+#
 # [metrics.metric_name.unit]
-# # This scale can be "si" for International System of Units or "binary" (optional)
+# # This scale can be "si" for International System of Units, "binary", "duration"
+# # to display values like "12m 34s" or "percent" to display ratios like "85.00 %" (optional)
 # scale = "si"
+# # Unit of the values with the "duration" scale, can be "ns", "µs", "ms" or "s" (optional)
+# base = "s"
 # # Any string that will be added as a suffix (optional)
 # suffix = "B"
 # # Number of decimals for every number (optional)
@@ -186,6 +890,11 @@ const fn sample() -> &'static str {
 # value = 12.34
 #
 # [[metrics.metric_name.rules]]
+# type = "max"
+# # the values can also be written with the unit of the metric
+# value = "12.34 kB"
+#
+# [[metrics.metric_name.rules]]
 # type = "min"
 # value = 1.234
 #
@@ -203,11 +912,77 @@ const fn sample() -> &'static str {
 # type = "max-increase"
 # # the metric cannot increase of more than 5%
 # ratio = 0.05
+# # but an increase of up to 1.234 is accepted (optional)
+# min_absolute = 1.234
+# # and the rule is ignored while the metric stays below 100 (optional)
+# ignore_below = 100
+# # when the previous value is zero, the rule can "skip", "fail" when the value changes
+# # or consider it as an "infinite" increase (optional)
+# zero_baseline = "infinite"
 #
 # [[metrics.metric_name.rules]]
 # type = "max-increase"
 # # the metric cannot increase of more than 1.234
 # value = 1.234
+#
+# [[metrics.metric_name.rules]]
+# type = "max-increase-over"
+# # the metric cannot increase of more than 10% compared with the value 50 commits
+# # before the baseline, the window can also be a number of days like "30d"
+# window = 50
+# ratio = 0.1
+#
+# # Subsets apply more rules to the metrics with matching tags
+# [metrics.metric_name.subsets.linux_without_musl]
+# # A tag can match a value, a list of values, a "regex", the negation of a matcher with "not"
+# # or be required to be defined with "exists"
+# matching = { "platform.os" = "linux", "platform.libc" = { not = "musl" }, "platform.arch" = ["amd64", "arm64"] }
+# # A metric matching the subset should exist on the target (optional)
+# required = true
+#
+# [[metrics.metric_name.subsets.linux_without_musl.rules]]
+# type = "max"
+# value = 12.34
+#
+# # Subsets can also check the "sum", "mean", "min" or "max" of the matching metrics,
+# # like the total size of all the chunks, reported as a single entry
+# [metrics.metric_name.subsets.all_chunks]
+# matching = { chunk = { exists = true } }
+# aggregate = "sum"
+# rules = [{ type = "max", value = 1234.0 }]
+#
+# # Metrics computed from the other metrics of the same commit, that can have rules like any other metric
+# [derived."coverage.lines.ratio"]
+# # Metrics with other characters than letters, digits, "_" and "." are written between double quotes
+# # and tags can be specified like "binary-size"{os="linux"}.
+# # The sum, avg, min and max functions aggregate the metrics matching a name, with wildcards, and tags.
+# expression = "coverage.lines.hit / coverage.lines.count"
+# # Tags of the computed metric (optional)
+# tags = { unit = "ratio" }
+#
+# # The metrics without an entry can use the configuration of the first matching pattern
+# [[patterns]]
+# # The pattern can be a "glob", where "*" matches any sequence of characters, or a "regex"
+# glob = "coverage.*.percentage"
+# unit = { scale = "percent" }
+#
+# [[patterns.rules]]
+# type = "max-decrease"
+# ratio = 0.01
+#
+# # Rules applied to some metrics when the condition matches
+# [[overrides]]
+# # Glob matching the checked branch and/or the reference of the baseline, like "origin/release/*" (optional)
+# # The checked branch is the one named by the target or the current one, set it with --branch on a detached HEAD
+# branch = "release/*"
+#
+# [overrides.metrics.metric_name]
+# # Replaces the rules of the metric instead of adding to them (optional)
+# replace = true
+#
+# [[overrides.metrics.metric_name.rules]]
+# type = "max"
+# value = 10.0
 "#
 }
 
@@ -218,47 +993,361 @@ impl Config {
         Config::from_str(content.as_str())
     }
 
-    fn config_path(root: &Path) -> PathBuf {
+    pub(crate) fn config_path(root: &Path) -> PathBuf {
         root.join(".git-metrics.toml")
     }
 
     pub(crate) fn from_root_path(root: &Path) -> std::io::Result<Config> {
         let config_path = Self::config_path(root);
         if config_path.is_file() {
-            Config::from_path(&config_path)
+            Config::from_path(&config_path)?.with_includes(root)
         } else {
             Ok(Default::default())
         }
     }
 
-    pub(crate) fn formatter(&self, metric_name: &str) -> Formatter<'_> {
-        if let Some(config) = self.metrics.get(metric_name) {
+    /// Finds the configuration of a metric.
+    ///
+    /// An entry with the exact name comes first, then an entry having the name
+    /// as alias and finally the first pattern matching the name.
+    pub(crate) fn metric(&self, metric_name: &str) -> Option<&MetricConfig> {
+        self.metrics
+            .get(metric_name)
+            .or_else(|| {
+                self.metrics.values().find(|config| {
+                    config
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.name() == metric_name)
+                })
+            })
+            .or_else(|| {
+                self.patterns
+                    .iter()
+                    .find(|item| item.pattern.matches(metric_name))
+                    .map(|item| &item.metric)
+            })
+    }
+
+    /// Builds the header of the metric when the given one is recorded with an alias
+    pub(crate) fn canonical_header(&self, header: &MetricHeader) -> Option<MetricHeader> {
+        if self.metrics.contains_key(&header.name) {
+            return None;
+        }
+        self.metrics.iter().find_map(|(name, config)| {
+            let alias = config
+                .aliases
+                .iter()
+                .find(|alias| alias.name() == header.name)?;
+            Some(MetricHeader {
+                name: name.clone(),
+                tags: header
+                    .tags
+                    .iter()
+                    .map(|(key, value)| (alias.tag_key(key).to_string(), value.clone()))
+                    .collect(),
+            })
+        })
+    }
+
+    /// Adds the derived metrics that can be computed with the given metrics
+    pub(crate) fn with_derived(&self, stack: MetricStack) -> MetricStack {
+        self.derived.iter().fold(stack, |stack, (name, derived)| {
+            match derived.expression.evaluate(&stack) {
+                Some(value) => stack.with_change(MetricChange::Add(Metric {
+                    header: MetricHeader {
+                        name: name.clone(),
+                        tags: derived.tags.clone(),
+                    },
+                    value,
+                })),
+                None => stack,
+            }
+        })
+    }
+
+    /// Formatter of the metric values, the coverage ratios of the lcov importer
+    /// are displayed as percentages when not configured
+    pub(crate) fn formatter(&self, metric_name: &str) -> ValueFormatter<'_> {
+        if let Some(config) = self.metric(metric_name) {
             config.unit.formater()
+        } else if is_coverage_ratio(metric_name) {
+            ValueFormatter::percent(None)
         } else {
             undefined_unit_formatter()
         }
     }
 
+    /// Windows of the trend rules, to know which history should be loaded
+    pub(crate) fn windows(&self) -> IndexSet<Window> {
+        self.metrics
+            .values()
+            .chain(self.patterns.iter().map(|item| &item.metric))
+            .flat_map(|metric| {
+                metric.rules.iter().chain(
+                    metric
+                        .subsets
+                        .values()
+                        .flat_map(|subset| subset.rules.iter()),
+                )
+            })
+            .filter_map(|rule| match rule {
+                Rule::MaxIncreaseOver(inner) => Some(inner.window),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Entry configuring the metric, like `metric` finds it.
+    ///
+    /// The configuration of a metric matching a pattern is copied in a new entry.
+    fn metric_entry(&mut self, name: String) -> &mut MetricConfig {
+        let key = match self.metrics.get_index_of(&name) {
+            Some(index) => index,
+            None => match self.metrics.values().position(|config| {
+                config
+                    .aliases
+                    .iter()
+                    .any(|alias| alias.name() == name.as_str())
+            }) {
+                Some(index) => index,
+                None => {
+                    let config = self.metric(&name).cloned().unwrap_or_default();
+                    self.metrics.insert_full(name, config).0
+                }
+            },
+        };
+        &mut self.metrics[key]
+    }
+
+    /// Applies the overrides matching the branch and the baseline reference,
+    /// returning the description of the applied ones
+    pub(crate) fn with_overrides(
+        mut self,
+        branch: Option<&str>,
+        baseline: Option<&str>,
+    ) -> (Self, Vec<String>) {
+        let mut applied = Vec::new();
+        for item in std::mem::take(&mut self.overrides) {
+            if !item.matches(branch, baseline) {
+                continue;
+            }
+            applied.push(item.to_string());
+            for (name, metric) in item.metrics {
+                let target = self.metric_entry(name);
+                if metric.replace {
+                    target.rules = metric.rules;
+                } else {
+                    target.rules.extend(metric.rules);
+                }
+            }
+        }
+        (self, applied)
+    }
+
     pub(crate) fn write_sample(root: &Path) -> std::io::Result<()> {
         let config_path = Self::config_path(root);
         std::fs::write(&config_path, sample())
     }
 }
 
+/// Error returned when the configuration cannot be parsed
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ParseError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Value(#[from] ValueError),
+}
+
+/// Error returned when a value of the configuration is invalid, with the path of the value
+#[derive(Debug, thiserror::Error)]
+#[error("{path}: {message}")]
+pub(crate) struct ValueError {
+    pub path: KeyPath,
+    pub message: String,
+}
+
+impl ValueError {
+    fn new(path: KeyPath, message: impl ToString) -> Self {
+        Self {
+            path,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Parses a configuration source, converting the rule values written with a unit
+fn parse_source<T: serde::de::DeserializeOwned>(source: &str) -> Result<T, ParseError> {
+    let mut table: toml::Table = toml::de::from_str(source)?;
+    let changed = resolve_rule_values(&mut table)?;
+    parse_resolved(source, table, changed)
+}
+
+/// Deserializes the table of the source, once its rule values have been converted
+fn parse_resolved<T: serde::de::DeserializeOwned>(
+    source: &str,
+    table: toml::Table,
+    changed: bool,
+) -> Result<T, ParseError> {
+    if changed {
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|err: toml::de::Error| {
+                // the errors unrelated to the unit values can be located in the source
+                match toml::de::from_str::<T>(source) {
+                    Err(located) if located.message() == err.message() => located.into(),
+                    _ => err.into(),
+                }
+            })
+    } else {
+        // deserializing from the source keeps the location of the errors
+        Ok(toml::de::from_str(source)?)
+    }
+}
+
+impl Config {
+    pub(crate) fn parse(source: &str) -> Result<Self, ParseError> {
+        parse_source(source)
+    }
+}
+
 impl FromStr for Config {
     type Err = std::io::Error;
 
     fn from_str(source: &str) -> Result<Self, Self::Err> {
-        use std::io::{Error, ErrorKind};
+        Config::parse(source)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// Fields of the rules that can be written with the unit of the metric
+const RULE_VALUE_FIELDS: &[&str] = &["value", "min_absolute", "ignore_below"];
+
+fn resolve_rules(
+    path: &KeyPath,
+    unit: &Unit,
+    rules: Option<&mut toml::Value>,
+) -> Result<bool, ValueError> {
+    let Some(toml::Value::Array(rules)) = rules else {
+        return Ok(false);
+    };
+    let mut changed = false;
+    for (index, rule) in rules.iter_mut().enumerate() {
+        let Some(rule) = rule.as_table_mut() else {
+            continue;
+        };
+        for field in RULE_VALUE_FIELDS {
+            if let Some(toml::Value::String(input)) = rule.get(*field) {
+                let value = unit.parse(input).map_err(|err| {
+                    ValueError::new(path.key("rules").index(index).key(*field), err)
+                })?;
+                rule.insert(field.to_string(), toml::Value::Float(value));
+                changed = true;
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Converts the rule values of a metric and its subsets using the given unit
+fn resolve_metric_rules(
+    path: &KeyPath,
+    unit: &Unit,
+    metric: &mut toml::Table,
+) -> Result<bool, ValueError> {
+    let mut changed = resolve_rules(path, unit, metric.get_mut("rules"))?;
+    if let Some(toml::Value::Table(subsets)) = metric.get_mut("subsets") {
+        for (name, subset) in subsets.iter_mut() {
+            let path = path.key("subsets").key(name.as_str());
+            changed |= resolve_rules(&path, unit, subset.get_mut("rules"))?;
+        }
+    }
+    Ok(changed)
+}
+
+fn resolve_metric_rule_values(
+    path: &KeyPath,
+    metric: &mut toml::Value,
+) -> Result<bool, ValueError> {
+    let Some(metric) = metric.as_table_mut() else {
+        return Ok(false);
+    };
+    let unit: Unit = match metric.get("unit") {
+        Some(unit) => unit
+            .clone()
+            .try_into()
+            .map_err(|err| ValueError::new(path.key("unit"), err))?,
+        None => Unit::default(),
+    };
+    resolve_metric_rules(path, &unit, metric)
+}
 
-        toml::de::from_str(source).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+/// Converts the rule values written with a unit, like `"10 MiB"`, using the unit of their metric.
+///
+/// Returns `true` when a value has been converted.
+fn resolve_rule_values(table: &mut toml::Table) -> Result<bool, ValueError> {
+    let mut changed = false;
+    if let Some(toml::Value::Table(metrics)) = table.get_mut("metrics") {
+        for (name, metric) in metrics.iter_mut() {
+            changed |= resolve_metric_rule_values(
+                &KeyPath::default().key("metrics").key(name.as_str()),
+                metric,
+            )?;
+        }
+    }
+    if let Some(toml::Value::Array(patterns)) = table.get_mut("patterns") {
+        for (index, pattern) in patterns.iter_mut().enumerate() {
+            changed |= resolve_metric_rule_values(
+                &KeyPath::default().key("patterns").index(index),
+                pattern,
+            )?;
+        }
+    }
+    if !table.contains_key("overrides") {
+        return Ok(changed);
+    }
+    // the rules of the overrides use the unit of the overridden metric, found like its other settings
+    let metrics = table.get("metrics").cloned().map(toml::Value::try_into);
+    let patterns = table.get("patterns").cloned().map(toml::Value::try_into);
+    let (Ok(metrics), Ok(patterns)) = (metrics.transpose(), patterns.transpose()) else {
+        // the invalid metrics are reported when deserializing the whole configuration
+        return Ok(changed);
+    };
+    let lookup = Config {
+        metrics: metrics.unwrap_or_default(),
+        patterns: patterns.unwrap_or_default(),
+        ..Default::default()
+    };
+    if let Some(toml::Value::Array(overrides)) = table.get_mut("overrides") {
+        for (index, item) in overrides.iter_mut().enumerate() {
+            let Some(toml::Value::Table(metrics)) = item.get_mut("metrics") else {
+                continue;
+            };
+            for (name, metric) in metrics.iter_mut() {
+                let path = KeyPath::default()
+                    .key("overrides")
+                    .index(index)
+                    .key("metrics")
+                    .key(name.as_str());
+                let unit = lookup
+                    .metric(name)
+                    .map(|config| config.unit.clone())
+                    .unwrap_or_default();
+                changed |= resolve_rules(&path, &unit, metric.get_mut("rules"))?;
+            }
+        }
     }
+    Ok(changed)
 }
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
+    use super::UnitScale;
+    use crate::entity::metric::MetricHeader;
+
     #[test]
     fn should_parse_config() {
         let without_comment = super::sample()
@@ -311,4 +1400,380 @@ mod tests {
             &["binary_size"],
         );
     }
+
+    #[test]
+    fn should_deserialize_window_rules() {
+        let config = super::Config::from_str(
+            r#"[metrics.binary_size]
+rules = [{ type = "max-increase-over", window = 50, ratio = 0.1 }, { type = "max-increase-over", window = "30d", value = 1.0 }]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.metrics["binary_size"].rules,
+            vec![
+                super::Rule::MaxIncreaseOver(super::RuleWindow {
+                    window: super::Window::Commits(50),
+                    change: super::RuleChange::Relative(super::RuleRelative {
+                        ratio: 0.1,
+                        ..Default::default()
+                    }),
+                }),
+                super::Rule::MaxIncreaseOver(super::RuleWindow {
+                    window: super::Window::Days(30),
+                    change: super::RuleChange::Absolute(super::RuleAbsolute { value: 1.0 }),
+                }),
+            ]
+        );
+        assert_eq!(
+            config.windows().into_iter().collect::<Vec<_>>(),
+            vec![super::Window::Commits(50), super::Window::Days(30)]
+        );
+        let err = super::Config::from_str(
+            r#"[metrics.binary_size]
+rules = [{ type = "max-increase-over", window = "a month", ratio = 0.1 }]
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid window"), "{err}");
+    }
+
+    #[test]
+    fn should_not_deserialize_required_rule() {
+        let err = super::Config::from_str(
+            r#"[metrics.binary_size]
+rules = [{ type = "required" }]
+"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown variant `required`"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn should_resolve_aliases() {
+        let config = super::Config::from_str(
+            r#"[metrics."artifact.size"]
+aliases = ["binary-size", { name = "bin-size", tags = { os = "platform.os" } }]
+unit = { scale = "binary" }
+"#,
+        )
+        .unwrap();
+        assert!(config.metric("binary-size").is_some());
+        assert!(config.metric("other").is_none());
+        assert_eq!(
+            config.canonical_header(&MetricHeader::new("bin-size").with_tag("os", "linux")),
+            Some(MetricHeader::new("artifact.size").with_tag("platform.os", "linux"))
+        );
+        assert_eq!(
+            config.canonical_header(&MetricHeader::new("artifact.size")),
+            None
+        );
+        assert_eq!(
+            config.formatter("binary-size").format(1024.0).to_string(),
+            "1.00 ki"
+        );
+    }
+
+    #[test]
+    fn should_use_patterns_after_exact_names() {
+        let config = super::Config::from_str(
+            r#"[metrics."coverage.lines.percentage"]
+unit = { suffix = "L" }
+
+[[patterns]]
+regex = "^coverage\\.(lines|branches)\\."
+unit = { suffix = "R" }
+
+[[patterns]]
+glob = "coverage.*"
+unit = { suffix = "G" }
+
+[[patterns.rules]]
+type = "max"
+value = 1.0
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .formatter("coverage.lines.percentage")
+                .format(1.0)
+                .to_string(),
+            "1.00 L"
+        );
+        assert_eq!(
+            config
+                .formatter("coverage.branches.percentage")
+                .format(1.0)
+                .to_string(),
+            "1.00 R"
+        );
+        assert_eq!(
+            config
+                .formatter("coverage.functions.percentage")
+                .format(1.0)
+                .to_string(),
+            "1.00 G"
+        );
+        assert_eq!(
+            config
+                .metric("coverage.functions.percentage")
+                .unwrap()
+                .rules
+                .len(),
+            1
+        );
+        assert!(config.metric("binary-size").is_none());
+    }
+
+    #[test]
+    fn should_fail_with_invalid_regex() {
+        let err = super::Config::from_str(
+            r#"[[patterns]]
+regex = "coverage.("
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("regex parse error"), "{err}");
+    }
+
+    #[test_case::test_case(r#""linux""#, Some("linux"), true; "exact matching")]
+    #[test_case::test_case(r#""linux""#, Some("macos"), false; "exact not matching")]
+    #[test_case::test_case(r#""linux""#, None, false; "exact missing")]
+    #[test_case::test_case(r#"["linux", "macos"]"#, Some("macos"), true; "list matching")]
+    #[test_case::test_case(r#"["linux", "macos"]"#, Some("win"), false; "list not matching")]
+    #[test_case::test_case(r#"{ regex = "^linux-" }"#, Some("linux-gnu"), true; "regex matching")]
+    #[test_case::test_case(r#"{ regex = "^linux-" }"#, Some("gnu-linux"), false; "regex not matching")]
+    #[test_case::test_case(r#"{ not = "musl" }"#, Some("gnu"), true; "not matching")]
+    #[test_case::test_case(r#"{ not = "musl" }"#, None, true; "not missing")]
+    #[test_case::test_case(r#"{ not = ["musl", "uclibc"] }"#, Some("musl"), false; "not list")]
+    #[test_case::test_case(r#"{ exists = true }"#, Some("any"), true; "exists defined")]
+    #[test_case::test_case(r#"{ exists = true }"#, None, false; "exists missing")]
+    #[test_case::test_case(r#"{ exists = false }"#, None, true; "not exists missing")]
+    fn should_match_tag(matcher: &str, value: Option<&str>, expected: bool) {
+        let subset: super::SubsetConfig =
+            toml::from_str(&format!("matching = {{ key = {matcher} }}")).unwrap();
+        assert_eq!(subset.matching["key"].matches(value), expected);
+    }
+
+    #[test]
+    fn should_display_tag_matchers() {
+        let subset: super::SubsetConfig = toml::from_str(
+            r#"matching = { a = "x", b = ["y", "z"], c = { regex = "^w" }, d = { not = { exists = true } } }"#,
+        )
+        .unwrap();
+        let displayed = subset
+            .matching
+            .values()
+            .map(|matcher| matcher.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(displayed, vec![r#""x""#, r#"["y", "z"]"#, "/^w/", "!*"]);
+    }
+
+    #[test_case::test_case(UnitScale::Binary, "B", "10 MiB", 10485760.0; "binary with prefix")]
+    #[test_case::test_case(UnitScale::Binary, "B", "1.5KiB", 1536.0; "binary without space")]
+    #[test_case::test_case(UnitScale::Binary, "B", "512 B", 512.0; "binary without prefix")]
+    #[test_case::test_case(UnitScale::SI, "s", "250ms", 0.25; "si with sub unit")]
+    #[test_case::test_case(UnitScale::SI, "ms", "250ms", 250.0; "si with exact suffix")]
+    #[test_case::test_case(UnitScale::SI, "%", "85%", 85.0; "percent suffix")]
+    #[test_case::test_case(UnitScale::SI, "B", "1_000", 1000.0; "without unit")]
+    #[test_case::test_case(UnitScale::Duration, "", "250ms", 0.25; "duration in seconds")]
+    #[test_case::test_case(UnitScale::Duration, "", "12m", 720.0; "duration in minutes")]
+    #[test_case::test_case(UnitScale::Duration, "", "12m 34s", 754.0; "compound duration")]
+    #[test_case::test_case(UnitScale::Duration, "", "1d 1h 1m 1s", 90061.0; "duration with every unit")]
+    #[test_case::test_case(UnitScale::Duration, "", "2m5.5s", 125.5; "compound duration without space")]
+    #[test_case::test_case(UnitScale::Duration, "", "-1m 30s", -90.0; "negative compound duration")]
+    #[test_case::test_case(UnitScale::Duration, "", "1.5e3ms", 1.5; "duration with exponent")]
+    #[test_case::test_case(UnitScale::SI, "B", "1e6", 1e6; "exponent without unit")]
+    #[test_case::test_case(UnitScale::SI, "B", "2.5E-3 kB", 2.5; "exponent with unit")]
+    #[test_case::test_case(UnitScale::SI, "events", "3events", 3.0; "suffix looking like an exponent")]
+    #[test_case::test_case(UnitScale::Percent, "", "85%", 0.85; "percent")]
+    #[test_case::test_case(UnitScale::Percent, "", "0.85", 0.85; "percent as ratio")]
+    fn should_parse_unit_value(scale: UnitScale, suffix: &str, input: &str, expected: f64) {
+        let unit = super::Unit::new(scale, Some(suffix));
+        assert_eq!(unit.parse(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn should_fail_parsing_incompatible_unit() {
+        let unit = super::Unit::binary().with_suffix("B");
+        assert_eq!(
+            unit.parse("10 MB").unwrap_err().to_string(),
+            "unit \"MB\" is not compatible with the metric, expected a binary prefix followed by \"B\""
+        );
+        assert_eq!(
+            unit.parse("ten MiB").unwrap_err().to_string(),
+            "invalid number in \"ten MiB\""
+        );
+        let unit = super::Unit::new(UnitScale::Duration, Some(""));
+        assert_eq!(
+            unit.parse("12m 34").unwrap_err().to_string(),
+            "unit \"\" is not compatible with the metric, expected a duration unit like \"ms\", \"s\" or \"h\""
+        );
+    }
+
+    #[test]
+    fn should_resolve_rule_values_with_unit() {
+        let config = super::Config::from_str(
+            r#"[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+rules = [
+    { type = "max", value = "10 MiB" },
+    { type = "max-increase", ratio = 0.1, min_absolute = "1 KiB" },
+]
+
+[metrics.binary-size.subsets.linux]
+matching = { os = "linux" }
+rules = [{ type = "min", value = "512 B" }]
+
+[[patterns]]
+glob = "build-time.*"
+unit = { scale = "si", suffix = "s" }
+rules = [{ type = "max", value = "250ms" }]
+"#,
+        )
+        .unwrap();
+        let metric = config.metric("binary-size").unwrap();
+        assert_eq!(metric.rules[0], super::Rule::max(10485760.0));
+        assert_eq!(
+            metric.rules[1],
+            super::Rule::MaxIncrease(super::RuleChange::Relative(super::RuleRelative {
+                ratio: 0.1,
+                min_absolute: Some(1024.0),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(metric.subsets["linux"].rules[0], super::Rule::min(512.0));
+        assert_eq!(
+            config.metric("build-time.debug").unwrap().rules[0],
+            super::Rule::max(0.25)
+        );
+    }
+
+    #[test]
+    fn should_locate_incompatible_rule_value() {
+        let err = super::Config::from_str(
+            r#"[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+rules = [{ type = "min", value = 1.0 }, { type = "max", value = "10 MB" }]
+"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "metrics.binary-size.rules[1].value: unit \"MB\" is not compatible with the metric, expected a binary prefix followed by \"B\""
+        );
+    }
+
+    #[test]
+    fn should_format_with_duration_and_percent_scales() {
+        let config = super::Config::from_str(
+            r#"[metrics.build-time]
+unit = { scale = "duration", base = "ms" }
+rules = [{ type = "max", value = "2m" }]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.metric("build-time").unwrap().rules[0],
+            super::Rule::max(120_000.0)
+        );
+        assert_eq!(
+            config.formatter("build-time").format(754_000.0).to_string(),
+            "12m 34s"
+        );
+        assert_eq!(
+            config
+                .formatter("coverage.lines.percentage")
+                .format(0.85)
+                .to_string(),
+            "85.00 %"
+        );
+        assert_eq!(
+            config
+                .formatter("coverage.lines.hit")
+                .format(85.0)
+                .to_string(),
+            "85.00"
+        );
+    }
+
+    #[test_case::test_case(Some("release/1.0"), None, &[10.0], &["branch release/*"]; "replaced on release branch")]
+    #[test_case::test_case(Some("feature/foo"), None, &[100.0, 50.0], &["branch feature/*"]; "extended on feature branch")]
+    #[test_case::test_case(None, Some("origin/main"), &[100.0, 50.0], &["baseline origin/main"]; "extended with main baseline")]
+    #[test_case::test_case(None, None, &[100.0], &[]; "no override")]
+    fn should_apply_overrides(
+        branch: Option<&str>,
+        baseline: Option<&str>,
+        rules: &[f64],
+        expected: &[&str],
+    ) {
+        let config = super::Config::from_str(
+            r#"[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+rules = [{ type = "max", value = 100.0 }]
+
+[[overrides]]
+branch = "release/*"
+metrics.binary-size = { replace = true, rules = [{ type = "max", value = "10 B" }] }
+
+[[overrides]]
+branch = "feature/*"
+metrics.binary-size.rules = [{ type = "max", value = 50.0 }]
+
+[[overrides]]
+baseline = "origin/main"
+metrics.binary-size.rules = [{ type = "max", value = 50.0 }]
+"#,
+        )
+        .unwrap();
+        let (config, applied) = config.with_overrides(branch, baseline);
+        assert_eq!(
+            config.metrics["binary-size"].rules,
+            rules
+                .iter()
+                .map(|value| super::Rule::max(*value))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(applied, expected);
+    }
+
+    #[test]
+    fn should_apply_overrides_through_aliases_and_patterns() {
+        let config = super::Config::from_str(
+            r#"[metrics.binary-size]
+aliases = ["size"]
+unit = { scale = "binary", suffix = "B" }
+rules = [{ type = "max", value = 100.0 }]
+
+[[patterns]]
+glob = "bundle.*"
+unit = { scale = "binary", suffix = "B" }
+rules = [{ type = "max", value = "2 KiB" }]
+
+[[overrides]]
+branch = "feature/*"
+metrics.size.rules = [{ type = "max", value = "1 KiB" }]
+metrics."bundle.main".rules = [{ type = "max", value = "512 B" }]
+"#,
+        )
+        .unwrap();
+        let (config, _) = config.with_overrides(Some("feature/foo"), None);
+        assert_eq!(
+            config.metrics["binary-size"].rules,
+            vec![super::Rule::max(100.0), super::Rule::max(1024.0)]
+        );
+        assert!(!config.metrics.contains_key("size"));
+        assert_eq!(
+            config.metrics["bundle.main"].rules,
+            vec![super::Rule::max(2048.0), super::Rule::max(512.0)]
+        );
+        assert_eq!(
+            config.patterns[0].metric.rules,
+            vec![super::Rule::max(2048.0)]
+        );
+    }
 }
@@ -0,0 +1,422 @@
+//! Loading of the included and nested configuration files
+
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+use super::{
+    Config, DerivedConfig, KeyPath, MetricConfig, ParseError, PatternConfig, SubsetConfig,
+    TagMatcher, ValueError,
+};
+use crate::entity::selector::glob_matches;
+
+/// Name of the tag scoping the metrics of the nested configuration files
+const PATH_TAG: &str = "path";
+
+/// Pathspec of the nested configuration files, in the subdirectories
+pub(crate) const NESTED_PATHSPEC: &str = "*/.git-metrics.toml";
+
+/// Content of an included configuration file
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IncludedConfig {
+    #[serde(default)]
+    metrics: IndexMap<String, MetricConfig>,
+    #[serde(default)]
+    patterns: Vec<PatternConfig>,
+    #[serde(default)]
+    derived: IndexMap<String, DerivedConfig>,
+}
+
+/// Content of a nested configuration file, found in a subdirectory
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NestedConfig {
+    #[serde(default)]
+    metrics: IndexMap<String, MetricConfig>,
+}
+
+fn invalid_data(path: &Path, message: impl std::fmt::Display) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("{}: {message}", path.display()),
+    )
+}
+
+/// Path relative to the root, with `/` as separator
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|item| item.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Lists the files matching the pattern, where `*` and `?` don't match `/`
+fn expand(root: &Path, pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let mut candidates = vec![root.to_path_buf()];
+    for segment in pattern.split('/').filter(|item| !item.is_empty()) {
+        let mut next = Vec::new();
+        for dir in candidates {
+            if !segment.contains(['*', '?']) {
+                next.push(dir.join(segment));
+            } else if dir.is_dir() {
+                let mut names = std::fs::read_dir(&dir)?
+                    .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                    .filter(|name| glob_matches(segment, name))
+                    .collect::<Vec<_>>();
+                names.sort();
+                next.extend(names.into_iter().map(|name| dir.join(name)));
+            }
+        }
+        candidates = next;
+    }
+    Ok(candidates
+        .into_iter()
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+/// Merges the entries, failing when an entry is defined in several files
+fn merge_entries<T>(
+    target: &mut IndexMap<String, T>,
+    origins: &mut IndexMap<String, PathBuf>,
+    kind: &str,
+    path: &Path,
+    entries: IndexMap<String, T>,
+) -> std::io::Result<()> {
+    for (name, entry) in entries {
+        if target.contains_key(&name) {
+            let origin = origins
+                .get(&name)
+                .map(|origin| origin.display().to_string())
+                .unwrap_or_else(|| ".git-metrics.toml".into());
+            return Err(invalid_data(
+                path,
+                format!("{kind} {name:?} is already defined in {origin}"),
+            ));
+        }
+        origins.insert(name.clone(), path.to_path_buf());
+        target.insert(name, entry);
+    }
+    Ok(())
+}
+
+impl Config {
+    /// Lists the included files, in the order they are merged
+    pub(crate) fn included_files(&self, root: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for pattern in self.include.iter() {
+            for path in expand(root, pattern)? {
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Parses an included file, only defining metrics, patterns and derived metrics
+    pub(crate) fn parse_included(source: &str) -> Result<Config, ParseError> {
+        let included: IncludedConfig = super::parse_source(source)?;
+        Ok(Config {
+            metrics: included.metrics,
+            patterns: included.patterns,
+            derived: included.derived,
+            ..Default::default()
+        })
+    }
+
+    /// Parses a nested file, only defining the rules of the metrics with the units of this configuration
+    pub(crate) fn parse_nested(&self, source: &str) -> Result<Config, ParseError> {
+        let mut table: toml::Table = toml::de::from_str(source)?;
+        let mut changed = false;
+        if let Some(toml::Value::Table(metrics)) = table.get_mut("metrics") {
+            for (name, metric) in metrics.iter_mut() {
+                let path = KeyPath::default().key("metrics").key(name.as_str());
+                let Some(metric) = metric.as_table_mut() else {
+                    continue;
+                };
+                if let Some(key) = ["unit", "aliases"]
+                    .iter()
+                    .find(|key| metric.contains_key(**key))
+                {
+                    return Err(ValueError::new(
+                        path.key(*key),
+                        "can only be defined in the root configuration",
+                    )
+                    .into());
+                }
+                let unit = self
+                    .metrics
+                    .get(name)
+                    .map(|config| config.unit.clone())
+                    .unwrap_or_default();
+                changed |= super::resolve_metric_rules(&path, &unit, metric)?;
+            }
+        }
+        let nested: NestedConfig = super::parse_resolved(source, table, changed)?;
+        Ok(Config {
+            metrics: nested.metrics,
+            ..Default::default()
+        })
+    }
+
+    /// Merges the included files
+    pub(super) fn with_includes(mut self, root: &Path) -> std::io::Result<Self> {
+        let mut metric_origins = IndexMap::new();
+        let mut derived_origins = IndexMap::new();
+        for path in self.included_files(root)? {
+            let name = PathBuf::from(relative(root, &path));
+            let source = std::fs::read_to_string(&path)?;
+            let included = Self::parse_included(&source).map_err(|err| invalid_data(&name, err))?;
+            merge_entries(
+                &mut self.metrics,
+                &mut metric_origins,
+                "metric",
+                &name,
+                included.metrics,
+            )?;
+            merge_entries(
+                &mut self.derived,
+                &mut derived_origins,
+                "derived metric",
+                &name,
+                included.derived,
+            )?;
+            self.patterns.extend(included.patterns);
+        }
+        Ok(self)
+    }
+
+    /// Merges the nested files, relative to the root, as subsets matching the `path` tag
+    pub(crate) fn with_nested(mut self, root: &Path, files: &[PathBuf]) -> std::io::Result<Self> {
+        for name in files {
+            let Some(scope) = name.parent().filter(|item| !item.as_os_str().is_empty()) else {
+                continue;
+            };
+            let scope = relative(root, scope);
+            let source = std::fs::read_to_string(root.join(name))?;
+            self.parse_nested(&source)
+                .map_err(|err| err.to_string())
+                .and_then(|nested| self.add_nested(&scope, nested.metrics))
+                .map_err(|err| invalid_data(name, err))?;
+        }
+        Ok(self)
+    }
+
+    /// Adds the rules of the nested metrics as subsets matching the `path` tag
+    fn add_nested(
+        &mut self,
+        scope: &str,
+        metrics: IndexMap<String, MetricConfig>,
+    ) -> Result<(), String> {
+        for (name, metric) in metrics {
+            let target = self.metrics.entry(name.clone()).or_default();
+            let subsets = std::iter::once((
+                scope.to_string(),
+                SubsetConfig {
+                    description: metric.description,
+                    matching: Default::default(),
+                    required: metric.required,
+                    rules: metric.rules,
+                    aggregate: None,
+                },
+            ))
+            .chain(
+                metric
+                    .subsets
+                    .into_iter()
+                    .map(|(subset_name, subset)| (format!("{scope}/{subset_name}"), subset)),
+            );
+            for (subset_name, mut subset) in subsets {
+                if subset.rules.is_empty() && !subset.required {
+                    continue;
+                }
+                if target.subsets.contains_key(&subset_name) {
+                    return Err(format!(
+                        "metrics.{name}.subsets.{subset_name}: the subset is already defined"
+                    ));
+                }
+                subset
+                    .matching
+                    .insert(PATH_TAG.to_string(), TagMatcher::from(scope));
+                target.subsets.insert(subset_name, subset);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::entity::config::{Config, Rule, TagMatcher};
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn should_merge_included_files() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            root.path(),
+            ".git-metrics.toml",
+            r#"include = ["services/*/metrics.toml"]
+
+[[metrics.first.rules]]
+type = "max"
+value = 10.0
+"#,
+        );
+        write(
+            root.path(),
+            "services/b/metrics.toml",
+            r#"[metrics.third]
+unit = { scale = "binary", suffix = "B" }
+
+[[metrics.third.rules]]
+type = "max"
+value = "1 MiB"
+"#,
+        );
+        write(
+            root.path(),
+            "services/a/metrics.toml",
+            r#"[[metrics.second.rules]]
+type = "min"
+value = 1.0
+"#,
+        );
+        let config = Config::from_root_path(root.path()).unwrap();
+        assert_eq!(
+            config.metrics.keys().collect::<Vec<_>>(),
+            vec!["first", "second", "third"]
+        );
+        assert_eq!(
+            config.metrics["third"].rules,
+            vec![Rule::max(1024.0 * 1024.0)]
+        );
+    }
+
+    #[test]
+    fn should_fail_with_conflicting_includes() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            root.path(),
+            ".git-metrics.toml",
+            r#"include = ["services/*/metrics.toml"]
+"#,
+        );
+        write(root.path(), "services/a/metrics.toml", "[metrics.first]\n");
+        write(root.path(), "services/b/metrics.toml", "[metrics.first]\n");
+        let err = Config::from_root_path(root.path()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "services/b/metrics.toml: metric \"first\" is already defined in services/a/metrics.toml"
+        );
+    }
+
+    #[test]
+    fn should_reject_settings_in_included_files() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            root.path(),
+            ".git-metrics.toml",
+            "include = [\"other.toml\"]\n",
+        );
+        write(
+            root.path(),
+            "other.toml",
+            "[check]\nfail_on_missing = true\n",
+        );
+        let err = Config::from_root_path(root.path()).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.starts_with("other.toml: TOML parse error"),
+            "{message}"
+        );
+        assert!(message.contains("unknown field `check`"), "{message}");
+    }
+
+    #[test]
+    fn should_scope_nested_files_with_path() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            root.path(),
+            ".git-metrics.toml",
+            r#"discover = true
+
+[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+"#,
+        );
+        write(
+            root.path(),
+            "services/api/.git-metrics.toml",
+            r#"[[metrics.binary-size.rules]]
+type = "max"
+value = "10 MiB"
+
+[metrics.binary-size.subsets.linux]
+matching = { "platform.os" = "linux" }
+rules = [{ type = "max", value = "8 MiB" }]
+"#,
+        );
+        let config = Config::from_root_path(root.path())
+            .unwrap()
+            .with_nested(
+                root.path(),
+                &[
+                    PathBuf::from(".git-metrics.toml"),
+                    PathBuf::from("services/api/.git-metrics.toml"),
+                ],
+            )
+            .unwrap();
+        let subsets = &config.metrics["binary-size"].subsets;
+        assert_eq!(
+            subsets.keys().collect::<Vec<_>>(),
+            vec!["services/api", "services/api/linux"]
+        );
+        assert_eq!(
+            subsets["services/api"].rules,
+            vec![Rule::max(10.0 * 1024.0 * 1024.0)]
+        );
+        assert_eq!(
+            subsets["services/api/linux"].matching.get("path"),
+            Some(&TagMatcher::from("services/api"))
+        );
+        assert_eq!(
+            subsets["services/api/linux"].matching.get("platform.os"),
+            Some(&TagMatcher::from("linux"))
+        );
+    }
+
+    #[test]
+    fn should_reject_unit_in_nested_files() {
+        let root = tempfile::tempdir().unwrap();
+        write(root.path(), ".git-metrics.toml", "discover = true\n");
+        write(
+            root.path(),
+            "services/api/.git-metrics.toml",
+            "[metrics.binary-size]\nunit = { scale = \"binary\" }\n",
+        );
+        let err = Config::from_root_path(root.path())
+            .unwrap()
+            .with_nested(
+                root.path(),
+                &[PathBuf::from("services/api/.git-metrics.toml")],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "services/api/.git-metrics.toml: metrics.binary-size.unit: can only be defined in the root configuration"
+        );
+    }
+}
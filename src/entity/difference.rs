@@ -1,3 +1,7 @@
+use indexmap::IndexMap;
+
+use super::config::{Config, Window};
+use super::expression::Aggregate;
 use super::metric::{MetricHeader, MetricStack};
 
 #[derive(Debug, serde::Serialize)]
@@ -37,6 +41,8 @@ pub(crate) enum Comparison {
         current: f64,
         delta: Delta,
     },
+    /// A required metric that exists neither before nor on the target
+    Absent,
 }
 
 #[cfg(test)]
@@ -83,7 +89,7 @@ impl Comparison {
         match self {
             Self::Created { current } => Some(*current),
             Self::Matching { current, .. } => Some(*current),
-            Self::Missing { .. } => None,
+            Self::Missing { .. } | Self::Absent => None,
         }
     }
 
@@ -109,41 +115,118 @@ impl MetricDiff {
     }
 }
 
-pub(crate) struct MetricDiffList(pub(crate) Vec<MetricDiff>);
+/// Moves the values recorded with an alias to the actual metric name.
+///
+/// When a value is recorded with both names, the one with the actual name is kept.
+fn resolve_aliases(config: &Config, stack: MetricStack) -> IndexMap<MetricHeader, f64> {
+    let mut result = IndexMap::new();
+    for (header, value) in stack.into_inner() {
+        match config.canonical_header(&header) {
+            Some(canonical) => {
+                result.entry(canonical).or_insert(value);
+            }
+            None => {
+                result.insert(header, value);
+            }
+        }
+    }
+    result
+}
+
+/// Values of the metrics at the beginning of the windows of the trend rules
+#[derive(Debug, Default)]
+pub(crate) struct History(IndexMap<Window, IndexMap<MetricHeader, f64>>);
+
+impl History {
+    pub fn with_window(mut self, config: &Config, window: Window, stack: MetricStack) -> Self {
+        self.0.insert(window, resolve_aliases(config, stack));
+        self
+    }
+
+    pub fn get(&self, window: &Window, header: &MetricHeader) -> Option<f64> {
+        self.0.get(window)?.get(header).copied()
+    }
+
+    /// History of the aggregate of the matching metrics, recorded under the given header
+    pub fn aggregate(
+        &self,
+        header: &MetricHeader,
+        aggregate: Aggregate,
+        matches: impl Fn(&MetricHeader) -> bool,
+    ) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter_map(|(window, values)| {
+                    let value = aggregate.apply(
+                        values
+                            .iter()
+                            .filter(|(header, _)| matches(header))
+                            .map(|(_, value)| *value),
+                    )?;
+                    Some((*window, IndexMap::from_iter([(header.clone(), value)])))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MetricDiffList {
+    /// Commit the metrics are compared with
+    pub baseline: Option<String>,
+    pub history: History,
+    pub list: Vec<MetricDiff>,
+}
 
 impl MetricDiffList {
-    pub fn new(previous: MetricStack, mut current: MetricStack) -> Self {
+    pub fn new(config: &Config, previous: MetricStack, current: MetricStack) -> Self {
+        let previous = resolve_aliases(config, previous);
+        let mut current = resolve_aliases(config, current);
         let mut result = Vec::new();
-        for (header, previous_value) in previous.into_inner().into_iter() {
-            let current_value = current.remove_entry(&header).map(|(_, value)| value);
+        for (header, previous_value) in previous.into_iter() {
+            let current_value = current.shift_remove(&header);
             result.push(MetricDiff {
                 header,
                 comparison: Comparison::new(previous_value, current_value),
             });
         }
-        for (header, value) in current.into_inner().into_iter() {
+        for (header, value) in current.into_iter() {
             result.push(MetricDiff {
                 header,
                 comparison: Comparison::created(value),
             });
         }
-        Self(result)
+        Self {
+            baseline: None,
+            history: History::default(),
+            list: result,
+        }
+    }
+
+    pub fn with_baseline(mut self, baseline: Option<String>) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = history;
+        self
     }
 
     pub fn remove_missing(self) -> Self {
-        Self(
-            self.0
+        Self {
+            baseline: self.baseline,
+            history: self.history,
+            list: self
+                .list
                 .into_iter()
                 .filter(|m| m.comparison.has_current())
                 .collect(),
-        )
+        }
     }
 
     pub fn inner(&self) -> &[MetricDiff] {
-        &self.0
-    }
-
-    pub fn into_inner(self) -> Vec<MetricDiff> {
-        self.0
+        &self.list
     }
 }
@@ -0,0 +1,388 @@
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+
+use super::metric::{MetricHeader, MetricStack};
+use super::selector::MetricSelector;
+
+/// Error returned when an expression cannot be parsed
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("column {column}: {message}")]
+pub(crate) struct ParseError {
+    pub column: usize,
+    pub message: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Aggregate {
+    Sum,
+    #[serde(rename = "mean", alias = "avg")]
+    Avg,
+    Min,
+    Max,
+}
+
+impl std::fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Sum => "sum",
+            Self::Avg => "mean",
+            Self::Min => "min",
+            Self::Max => "max",
+        })
+    }
+}
+
+impl Aggregate {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sum" => Some(Self::Sum),
+            "mean" | "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn apply(&self, values: impl Iterator<Item = f64>) -> Option<f64> {
+        let (count, result) = values.fold((0usize, None), |(count, acc), value| {
+            let next = match (self, acc) {
+                (_, None) => value,
+                (Self::Sum | Self::Avg, Some(acc)) => acc + value,
+                (Self::Min, Some(acc)) => f64::min(acc, value),
+                (Self::Max, Some(acc)) => f64::max(acc, value),
+            };
+            (count + 1, Some(next))
+        });
+        match self {
+            Self::Avg => result.map(|sum| sum / count as f64),
+            _ => result,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Number(f64),
+    /// Value of the metric with exactly this name and tags
+    Metric(MetricHeader),
+    /// Aggregation of the metrics matching the selector
+    Aggregate(Aggregate, MetricSelector),
+    Negate(Box<Node>),
+    Binary(Operator, Box<Node>, Box<Node>),
+}
+
+impl Node {
+    fn evaluate(&self, metrics: &MetricStack) -> Option<f64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            Self::Metric(header) => metrics.get(header),
+            Self::Aggregate(aggregate, selector) => aggregate.apply(
+                metrics
+                    .iter()
+                    .filter(|(header, _)| selector.matches(header))
+                    .map(|(_, value)| *value),
+            ),
+            Self::Negate(inner) => inner.evaluate(metrics).map(|value| -value),
+            Self::Binary(operator, left, right) => {
+                let left = left.evaluate(metrics)?;
+                let right = right.evaluate(metrics)?;
+                Some(match operator {
+                    Operator::Add => left + right,
+                    Operator::Sub => left - right,
+                    Operator::Mul => left * right,
+                    Operator::Div => left / right,
+                })
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, offset: usize, message: &'static str) -> ParseError {
+        ParseError {
+            column: self.input[..offset].chars().count() + 1,
+            message,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.offset..];
+        let trimmed = rest.trim_start();
+        self.offset += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn consume(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.offset += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, accept: impl Fn(char) -> bool) -> &'a str {
+        let start = self.offset;
+        let rest = &self.input[start..];
+        let length = rest.find(|c| !accept(c)).unwrap_or(rest.len());
+        self.offset += length;
+        &self.input[start..self.offset]
+    }
+
+    fn quoted(&mut self) -> Result<String, ParseError> {
+        let start = self.offset;
+        self.offset += 1;
+        let mut value = String::new();
+        let mut chars = self.input[self.offset..].chars();
+        loop {
+            match chars.next() {
+                Some('"') => {
+                    self.offset += 1;
+                    return Ok(value);
+                }
+                Some('\\') => match chars.next() {
+                    Some(escaped @ ('"' | '\\')) => {
+                        self.offset += 2;
+                        value.push(escaped);
+                    }
+                    _ => return Err(self.error(self.offset, "invalid escape sequence")),
+                },
+                Some(other) => {
+                    self.offset += other.len_utf8();
+                    value.push(other);
+                }
+                None => return Err(self.error(start, "unterminated string")),
+            }
+        }
+    }
+
+    /// A metric name, either as an identifier or between double quotes
+    fn name(&mut self) -> Result<String, ParseError> {
+        match self.peek() {
+            Some('"') => self.quoted(),
+            Some(c) if c.is_alphabetic() || c == '_' => Ok(self
+                .take_while(|c| c.is_alphanumeric() || c == '_' || c == '.')
+                .to_string()),
+            _ => Err(self.error(self.offset, "expected a metric name")),
+        }
+    }
+
+    fn tags(&mut self) -> Result<IndexMap<String, String>, ParseError> {
+        let mut tags = IndexMap::new();
+        if !self.consume('{') {
+            return Ok(tags);
+        }
+        if self.consume('}') {
+            return Ok(tags);
+        }
+        loop {
+            let key = self.name()?;
+            if !self.consume('=') {
+                return Err(self.error(self.offset, "expected '=' after the tag name"));
+            }
+            if self.peek() != Some('"') {
+                return Err(self.error(self.offset, "expected a tag value between double quotes"));
+            }
+            let value = self.quoted()?;
+            tags.insert(key, value);
+            if self.consume('}') {
+                return Ok(tags);
+            }
+            if !self.consume(',') {
+                return Err(self.error(self.offset, "expected ',' or '}' after the tag value"));
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Result<Node, ParseError> {
+        match self.peek() {
+            Some('-') => {
+                self.offset += 1;
+                Ok(Node::Negate(Box::new(self.factor()?)))
+            }
+            Some('(') => {
+                self.offset += 1;
+                let node = self.expression()?;
+                if !self.consume(')') {
+                    return Err(self.error(self.offset, "expected ')'"));
+                }
+                Ok(node)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.offset;
+                let value = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                value
+                    .parse::<f64>()
+                    .map(Node::Number)
+                    .map_err(|_| self.error(start, "invalid number"))
+            }
+            Some(_) => {
+                let start = self.offset;
+                let name = self.name()?;
+                if self.peek() == Some('(') {
+                    let aggregate = Aggregate::from_name(&name)
+                        .ok_or_else(|| self.error(start, "unknown function"))?;
+                    self.offset += 1;
+                    let name = self.name()?;
+                    let tags = self.tags()?;
+                    if !self.consume(')') {
+                        return Err(self.error(self.offset, "expected ')'"));
+                    }
+                    Ok(Node::Aggregate(
+                        aggregate,
+                        MetricSelector::new(name).with_tags(tags),
+                    ))
+                } else {
+                    let tags = self.tags()?;
+                    Ok(Node::Metric(MetricHeader { name, tags }))
+                }
+            }
+            None => Err(self.error(self.offset, "unexpected end of expression")),
+        }
+    }
+
+    fn term(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some('*') => Operator::Mul,
+                Some('/') => Operator::Div,
+                _ => return Ok(node),
+            };
+            self.offset += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.factor()?));
+        }
+    }
+
+    fn expression(&mut self) -> Result<Node, ParseError> {
+        let mut node = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some('+') => Operator::Add,
+                Some('-') => Operator::Sub,
+                _ => return Ok(node),
+            };
+            self.offset += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.term()?));
+        }
+    }
+}
+
+/// Arithmetic expression computing a value based on other metrics.
+///
+/// Metrics are referenced by their name, between double quotes when it contains
+/// other characters than letters, digits, `_` and `.`, followed by their tags, like
+/// `"binary-size"{os="linux"}`. The `sum`, `mean` (or `avg`), `min` and `max` functions
+/// aggregate the metrics matching a name, that can contain wildcards, and some tags.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Expression {
+    source: String,
+    root: Node,
+}
+
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            input: source,
+            offset: 0,
+        };
+        let root = parser.expression()?;
+        if parser.peek().is_some() {
+            return Err(parser.error(parser.offset, "unexpected content"));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+}
+
+impl Expression {
+    /// Computes the value, returns `None` when a metric is missing or the result is not a finite number
+    pub(crate) fn evaluate(&self, metrics: &MetricStack) -> Option<f64> {
+        self.root
+            .evaluate(metrics)
+            .filter(|value| value.is_finite())
+    }
+}
+
+impl serde::Serialize for Expression {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Expression {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Expression::from_str(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::Expression;
+    use crate::entity::metric::{Metric, MetricStack};
+
+    fn stack() -> MetricStack {
+        MetricStack::from_iter(
+            vec![
+                Metric::new("coverage.lines.hit", 75.0),
+                Metric::new("coverage.lines.count", 100.0),
+                Metric::new("binary-size", 10.0).with_tag("os", "linux"),
+                Metric::new("binary-size", 30.0).with_tag("os", "macos"),
+                Metric::new("zero", 0.0),
+            ]
+            .into_iter(),
+        )
+    }
+
+    #[test_case::test_case("coverage.lines.hit / coverage.lines.count", Some(0.75); "ratio")]
+    #[test_case::test_case("1 + 2 * 3", Some(7.0); "precedence")]
+    #[test_case::test_case("(1 + 2) * 3", Some(9.0); "parenthesis")]
+    #[test_case::test_case("-coverage.lines.hit - -1", Some(-74.0); "negation")]
+    #[test_case::test_case(r#""binary-size"{os="macos"}"#, Some(30.0); "quoted with tags")]
+    #[test_case::test_case(r#""binary-size""#, None; "without the tags")]
+    #[test_case::test_case(r#"sum("binary-size")"#, Some(40.0); "sum")]
+    #[test_case::test_case(r#"mean("binary-*")"#, Some(20.0); "mean with glob")]
+    #[test_case::test_case(r#"avg("binary-*")"#, Some(20.0); "avg with glob")]
+    #[test_case::test_case(r#"max("binary-size") - min("binary-size")"#, Some(20.0); "max and min")]
+    #[test_case::test_case(r#"sum("binary-size"{os="linux"})"#, Some(10.0); "sum with tags")]
+    #[test_case::test_case("sum(missing)", None; "sum without metrics")]
+    #[test_case::test_case("missing + 1", None; "missing metric")]
+    #[test_case::test_case("1 / zero", None; "division by zero")]
+    fn should_evaluate(input: &str, expected: Option<f64>) {
+        let expression = Expression::from_str(input).unwrap();
+        assert_eq!(expression.evaluate(&stack()), expected);
+    }
+
+    #[test_case::test_case("1 +", 4, "unexpected end of expression"; "missing operand")]
+    #[test_case::test_case("(1 + 2", 7, "expected ')'"; "unclosed parenthesis")]
+    #[test_case::test_case("foo(bar)", 1, "unknown function"; "unknown function")]
+    #[test_case::test_case("foo bar", 5, "unexpected content"; "two names")]
+    #[test_case::test_case("foo{a=b}", 7, "expected a tag value between double quotes"; "unquoted tag")]
+    #[test_case::test_case("\"foo", 1, "unterminated string"; "unterminated string")]
+    fn should_fail_parsing(input: &str, column: usize, message: &str) {
+        let err = Expression::from_str(input).unwrap_err();
+        assert_eq!((err.column, err.message), (column, message));
+    }
+}
@@ -1,8 +1,16 @@
-#[derive(Debug, serde::Serialize)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone, Debug, serde::Serialize)]
 pub(crate) struct Commit {
     pub sha: String,
     pub summary: String,
+    /// Date of the commit, in seconds since the epoch
+    #[serde(skip)]
+    pub timestamp: i64,
+    /// Name of the author of the commit
+    #[serde(skip)]
+    pub author: String,
+    /// Message after the summary, with the trailers
+    #[serde(skip)]
+    pub body: String,
 }
 
 impl Commit {
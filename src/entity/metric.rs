@@ -34,8 +34,12 @@ impl MetricStack {
         self.inner.extend(other.inner);
     }
 
-    pub(crate) fn remove_entry(&mut self, header: &MetricHeader) -> Option<(MetricHeader, f64)> {
-        self.inner.shift_remove_entry(header)
+    pub(crate) fn get(&self, header: &MetricHeader) -> Option<f64> {
+        self.inner.get(header).copied()
+    }
+
+    pub(crate) fn iter(&self) -> indexmap::map::Iter<'_, MetricHeader, f64> {
+        self.inner.iter()
     }
 
     pub(crate) fn with_change(mut self, change: MetricChange) -> Self {
@@ -71,18 +75,12 @@ impl MetricStack {
         self.into_metric_iter().collect()
     }
 
-    pub(crate) fn at(&self, index: usize) -> Option<(&MetricHeader, f64)> {
-        self.inner
-            .get_index(index)
-            .map(|(header, value)| (header, *value))
-    }
-
     pub(crate) fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
 }
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 #[serde(tag = "action", rename_all = "lowercase")]
 pub(crate) enum MetricChange {
     Add(Metric),
@@ -121,8 +119,7 @@ impl Hash for MetricHeader {
     }
 }
 
-#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Metric {
     #[serde(flatten)]
     pub header: MetricHeader,
@@ -0,0 +1,105 @@
+use indexmap::IndexMap;
+
+use super::metric::Metric;
+use super::selector::MetricSelector;
+
+/// Transformation applied to the metrics matching a selector
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Migration {
+    pub selector: MetricSelector,
+    /// New name of the metric
+    pub rename: Option<String>,
+    /// Tags to add, overriding the existing ones
+    pub add_tags: IndexMap<String, String>,
+    /// Tag names to change, from the key to the value
+    pub rename_tags: IndexMap<String, String>,
+    /// Tag names to remove
+    pub drop_tags: Vec<String>,
+    /// Factor applied to the value
+    pub scale: Option<f64>,
+}
+
+impl Migration {
+    /// Applies the migration to the metric, returns `None` when the metric is not affected
+    pub(crate) fn apply(&self, metric: &Metric) -> Option<Metric> {
+        if !self.selector.matches(&metric.header) {
+            return None;
+        }
+        let mut result = metric.clone();
+        if let Some(ref name) = self.rename {
+            result.header.name = name.clone();
+        }
+        for (from, to) in self.rename_tags.iter() {
+            if let Some(index) = result.header.tags.get_index_of(from) {
+                let value = result.header.tags.shift_remove_index(index).unwrap().1;
+                result.header.tags.shift_insert(index, to.clone(), value);
+            }
+        }
+        for key in self.drop_tags.iter() {
+            result.header.tags.shift_remove(key);
+        }
+        for (key, value) in self.add_tags.iter() {
+            result.header.tags.insert(key.clone(), value.clone());
+        }
+        if let Some(scale) = self.scale {
+            result.value *= scale;
+        }
+        (result != *metric).then_some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Migration;
+    use crate::entity::metric::Metric;
+    use crate::entity::selector::MetricSelector;
+
+    #[test]
+    fn should_ignore_other_metrics() {
+        let migration = Migration {
+            selector: MetricSelector::new("binary-size"),
+            rename: Some("artifact.size".into()),
+            ..Default::default()
+        };
+        assert_eq!(migration.apply(&Metric::new("other", 1.0)), None);
+    }
+
+    #[test]
+    fn should_ignore_unchanged_metrics() {
+        let migration = Migration {
+            selector: MetricSelector::new("binary-size"),
+            drop_tags: vec!["os".into()],
+            ..Default::default()
+        };
+        assert_eq!(migration.apply(&Metric::new("binary-size", 1.0)), None);
+    }
+
+    #[test]
+    fn should_transform_metric() {
+        let migration = Migration {
+            selector: MetricSelector::new("binary-*"),
+            rename: Some("artifact.size".into()),
+            add_tags: [("unit".to_string(), "kB".to_string())]
+                .into_iter()
+                .collect(),
+            rename_tags: [("os".to_string(), "platform.os".to_string())]
+                .into_iter()
+                .collect(),
+            drop_tags: vec!["arch".into()],
+            scale: Some(0.001),
+        };
+        let metric = Metric::new("binary-size", 2048.0)
+            .with_tag("os", "linux")
+            .with_tag("arch", "amd64")
+            .with_tag("profile", "release");
+        assert_eq!(
+            migration.apply(&metric),
+            Some(
+                Metric::new("artifact.size", 2.048)
+                    .with_tag("platform.os", "linux")
+                    .with_tag("profile", "release")
+                    .with_tag("unit", "kB")
+            )
+        );
+    }
+}
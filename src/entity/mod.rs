@@ -1,6 +1,15 @@
+pub(crate) mod baseline;
 pub(crate) mod check;
 pub(crate) mod config;
 pub(crate) mod difference;
+pub(crate) mod expression;
 pub(crate) mod git;
 pub(crate) mod log;
 pub(crate) mod metric;
+pub(crate) mod migration;
+pub(crate) mod ratchet;
+pub(crate) mod selector;
+pub(crate) mod status;
+pub(crate) mod step;
+pub(crate) mod validation;
+pub(crate) mod waiver;
@@ -0,0 +1,368 @@
+use super::config::{Config, ParseError, Rule, Unit};
+use super::metric::{MetricHeader, MetricStack};
+use super::validation::{find_value_mut, KeyPath};
+
+/// Error returned when the configuration cannot be tightened
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    #[error(transparent)]
+    Config(#[from] ParseError),
+    #[error(transparent)]
+    Document(#[from] toml_edit::TomlError),
+}
+
+/// Rule value tightened to the current value of the metric
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Tightening {
+    /// Path of the rule value
+    pub path: KeyPath,
+    pub previous: f64,
+    pub value: f64,
+}
+
+/// Line of the configuration changed by the ratchet
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct LineChange {
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// Rule that can be tightened, before rounding the value as it is written
+struct Candidate<'c> {
+    path: KeyPath,
+    previous: f64,
+    /// Current value of the metric, with the margin
+    current: f64,
+    /// A max rule is rounded up and a min rule down
+    max: bool,
+    unit: &'c Unit,
+}
+
+impl Candidate<'_> {
+    /// Value written in place of the original one, with the value it stands for
+    fn replacement(&self, original: &toml_edit::Value) -> Option<(toml_edit::Value, f64)> {
+        match original {
+            // the values written with a unit keep being written with the unit of the metric
+            toml_edit::Value::String(_) => {
+                let (unit, factor) = self.unit.written_unit(self.current);
+                let number = round(self.current / factor, self.max);
+                let written = format!("{number} {unit}");
+                Some((written.trim_end().into(), number * factor))
+            }
+            toml_edit::Value::Float(_) | toml_edit::Value::Integer(_) => {
+                let number = round(self.current, self.max);
+                Some((number.into(), number))
+            }
+            _ => None,
+        }
+    }
+
+    fn tightens(&self, value: f64) -> bool {
+        if self.max {
+            value < self.previous
+        } else {
+            value > self.previous
+        }
+    }
+}
+
+/// Rounds the value to 4 significant digits without truncating the integer part,
+/// away from the recorded value
+fn round(value: f64, up: bool) -> f64 {
+    if value == 0.0 || !value.is_finite() {
+        return value;
+    }
+    let decimals = (3 - value.abs().log10().floor() as i32).clamp(0, 12);
+    let factor = 10f64.powi(decimals);
+    // the epsilon avoids moving to the next step because of the float precision
+    if up {
+        (value * factor - 1e-6).ceil() / factor
+    } else {
+        (value * factor + 1e-6).floor() / factor
+    }
+}
+
+/// Lists the min and max rules with the current values including the margin
+fn tighten_rules<'c, 'h>(
+    path: &KeyPath,
+    rules: &[Rule],
+    unit: &'c Unit,
+    values: impl Iterator<Item = &'h f64> + Clone,
+    margin: f64,
+    result: &mut Vec<Candidate<'c>>,
+) {
+    for (index, rule) in rules.iter().enumerate() {
+        let candidate = match rule {
+            Rule::Max(inner) => values
+                .clone()
+                .copied()
+                .reduce(f64::max)
+                .map(|current| (inner.value, current + current.abs() * margin, true)),
+            Rule::Min(inner) => values
+                .clone()
+                .copied()
+                .reduce(f64::min)
+                .map(|current| (inner.value, current - current.abs() * margin, false)),
+            _ => None,
+        };
+        if let Some((previous, current, max)) = candidate {
+            result.push(Candidate {
+                path: path.key("rules").index(index).key("value"),
+                previous,
+                current,
+                max,
+                unit,
+            });
+        }
+    }
+}
+
+/// Tightens the min and max rules of the configuration source to the given metrics,
+/// with a margin relative to the current values.
+///
+/// Only the rule values are replaced, so the comments and the formatting are kept.
+pub(crate) fn ratchet(
+    source: &str,
+    metrics: &MetricStack,
+    margin: f64,
+) -> Result<(String, Vec<Tightening>), Error> {
+    let config = Config::parse(source)?;
+    let headers: Vec<(MetricHeader, f64)> = metrics
+        .iter()
+        .map(|(header, value)| {
+            let header = config
+                .canonical_header(header)
+                .unwrap_or_else(|| header.clone());
+            (header, *value)
+        })
+        .collect();
+
+    let mut candidates = Vec::new();
+    for (name, metric) in config.metrics.iter() {
+        let path = KeyPath::default().key("metrics").key(name.as_str());
+        let values = headers
+            .iter()
+            .filter(|(header, _)| header.name == *name)
+            .map(|(_, value)| value);
+        tighten_rules(
+            &path,
+            &metric.rules,
+            &metric.unit,
+            values,
+            margin,
+            &mut candidates,
+        );
+        for (subset_name, subset) in metric.subsets.iter() {
+            let values = headers
+                .iter()
+                .filter(|(header, _)| header.name == *name && subset.matches(header))
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>();
+            // the rules of an aggregated subset are checked against the aggregate
+            let values = match subset.aggregate {
+                Some(aggregate) => aggregate.apply(values.into_iter()).into_iter().collect(),
+                None => values,
+            };
+            tighten_rules(
+                &path.key("subsets").key(subset_name.as_str()),
+                &subset.rules,
+                &metric.unit,
+                values.iter(),
+                margin,
+                &mut candidates,
+            );
+        }
+    }
+
+    let mut document: toml_edit::DocumentMut = source.parse()?;
+    let mut changes = Vec::new();
+    for candidate in candidates {
+        let Some(value) = find_value_mut(document.as_item_mut(), &candidate.path) else {
+            continue;
+        };
+        let Some((replacement, tightened)) = candidate
+            .replacement(value)
+            .filter(|(_, tightened)| candidate.tightens(*tightened))
+        else {
+            continue;
+        };
+        let decor = value.decor().clone();
+        *value = replacement;
+        *value.decor_mut() = decor;
+        changes.push(Tightening {
+            path: candidate.path,
+            previous: candidate.previous,
+            value: tightened,
+        });
+    }
+    Ok((document.to_string(), changes))
+}
+
+/// Lists the lines that differ, the ratchet never adds nor removes lines
+pub(crate) fn changed_lines(before: &str, after: &str) -> Vec<LineChange> {
+    before
+        .lines()
+        .zip(after.lines())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .map(|(index, (before, after))| LineChange {
+            line: index + 1,
+            before: before.to_string(),
+            after: after.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_lines, ratchet};
+    use crate::entity::metric::{Metric, MetricStack};
+
+    #[test]
+    fn should_tighten_rules_keeping_formatting() {
+        let source = r#"# budgets of the project
+[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+
+[[metrics.binary-size.rules]]
+type = "max"
+value = "10 MiB" # hard limit
+
+[[metrics.binary-size.rules]]
+type = "max-increase"
+ratio = 0.1
+
+[metrics.binary-size.subsets.linux]
+matching = { "platform.os" = "linux" }
+rules = [{ type = "max", value = 2000.0 }]
+
+[metrics.coverage]
+rules = [
+    { type = "min", value = 0.5 },   # never below half
+]
+"#;
+        let metrics = MetricStack::from_iter(
+            [
+                Metric::new("binary-size", 1000.0).with_tag("platform.os", "linux"),
+                Metric::new("binary-size", 1500.0).with_tag("platform.os", "macos"),
+                Metric::new("coverage", 0.8512),
+            ]
+            .into_iter(),
+        );
+        let (result, changes) = ratchet(source, &metrics, 0.1).unwrap();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(
+            changes[0].path.to_string(),
+            "metrics.binary-size.rules[0].value"
+        );
+        assert_eq!(changes[0].previous, 10.0 * 1024.0 * 1024.0);
+        assert_eq!(changes[0].value, 1.612 * 1024.0);
+        similar_asserts::assert_eq!(
+            result,
+            r#"# budgets of the project
+[metrics.binary-size]
+unit = { scale = "binary", suffix = "B" }
+
+[[metrics.binary-size.rules]]
+type = "max"
+value = "1.612 KiB" # hard limit
+
+[[metrics.binary-size.rules]]
+type = "max-increase"
+ratio = 0.1
+
+[metrics.binary-size.subsets.linux]
+matching = { "platform.os" = "linux" }
+rules = [{ type = "max", value = 1100.0 }]
+
+[metrics.coverage]
+rules = [
+    { type = "min", value = 0.766 },   # never below half
+]
+"#
+        );
+        let lines = changed_lines(source, &result);
+        assert_eq!(
+            lines.iter().map(|item| item.line).collect::<Vec<_>>(),
+            vec![7, 15, 19]
+        );
+    }
+
+    #[test_case::test_case(r#"{ scale = "binary", suffix = "B" }"#, "max", "\"10 MiB\"", 3_000_000.0, "\"2.862 MiB\""; "binary")]
+    #[test_case::test_case(r#"{ scale = "si", suffix = "B" }"#, "max", "\"10 MB\"", 1_234_567.0, "\"1.235 MB\""; "si")]
+    #[test_case::test_case(r#"{ scale = "duration", base = "ms" }"#, "max", "\"2 s\"", 1500.0, "\"1.5 s\""; "duration")]
+    #[test_case::test_case(r#"{ scale = "percent" }"#, "min", "\"90 %\"", 0.9512, "\"95.12 %\""; "percent")]
+    #[test_case::test_case(r#"{ scale = "binary", suffix = "B" }"#, "max", "10485760", 3_000_000.0, "3000000.0"; "number")]
+    fn should_keep_the_unit_of_string_values(
+        unit: &str,
+        kind: &str,
+        value: &str,
+        current: f64,
+        expected: &str,
+    ) {
+        let source = format!(
+            "[metrics.first]\nunit = {unit}\nrules = [{{ type = \"{kind}\", value = {value} }}]\n"
+        );
+        let metrics = MetricStack::from_iter(std::iter::once(Metric::new("first", current)));
+        let (result, changes) = ratchet(&source, &metrics, 0.0).unwrap();
+        assert_eq!(changes.len(), 1);
+        similar_asserts::assert_eq!(
+            result,
+            format!(
+                "[metrics.first]\nunit = {unit}\nrules = [{{ type = \"{kind}\", value = {expected} }}]\n"
+            )
+        );
+    }
+
+    #[test]
+    fn should_tighten_aggregated_subsets_to_the_aggregate() {
+        let source = r#"[metrics."bundle.size".subsets.total]
+matching = { chunk = { exists = true } }
+aggregate = "sum"
+rules = [{ type = "max", value = 1000.0 }]
+
+[metrics."bundle.size".subsets.largest]
+matching = { chunk = { exists = true } }
+rules = [{ type = "max", value = 1000.0 }]
+"#;
+        let metrics = MetricStack::from_iter(
+            [
+                Metric::new("bundle.size", 120.0).with_tag("chunk", "main"),
+                Metric::new("bundle.size", 140.0).with_tag("chunk", "vendor"),
+            ]
+            .into_iter(),
+        );
+        let (result, changes) = ratchet(source, &metrics, 0.0).unwrap();
+        assert_eq!(
+            changes.iter().map(|item| item.value).collect::<Vec<_>>(),
+            vec![260.0, 140.0]
+        );
+        similar_asserts::assert_eq!(
+            result,
+            r#"[metrics."bundle.size".subsets.total]
+matching = { chunk = { exists = true } }
+aggregate = "sum"
+rules = [{ type = "max", value = 260.0 }]
+
+[metrics."bundle.size".subsets.largest]
+matching = { chunk = { exists = true } }
+rules = [{ type = "max", value = 140.0 }]
+"#
+        );
+    }
+
+    #[test]
+    fn should_not_loosen_rules() {
+        let source = r#"[metrics.first]
+rules = [{ type = "max", value = 100.0 }, { type = "min", value = 90.0 }]
+
+[metrics.second]
+rules = [{ type = "max", value = 100.0 }]
+"#;
+        let metrics = MetricStack::from_iter(std::iter::once(Metric::new("first", 95.0)));
+        let (result, changes) = ratchet(source, &metrics, 0.1).unwrap();
+        assert!(changes.is_empty());
+        assert_eq!(result, source);
+    }
+}
@@ -0,0 +1,129 @@
+use indexmap::IndexMap;
+
+use super::metric::{Metric, MetricHeader, MetricStack};
+
+/// Checks if the value matches the pattern, where `*` matches any sequence of
+/// characters and `?` matches a single character.
+pub(crate) fn glob_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p_idx, mut v_idx) = (0, 0);
+    // position of the last star in the pattern and the value position it matched
+    let mut backtrack: Option<(usize, usize)> = None;
+    while v_idx < value.len() {
+        match pattern.get(p_idx) {
+            Some('*') => {
+                backtrack = Some((p_idx, v_idx));
+                p_idx += 1;
+            }
+            Some('?') => {
+                p_idx += 1;
+                v_idx += 1;
+            }
+            Some(c) if *c == value[v_idx] => {
+                p_idx += 1;
+                v_idx += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p_idx = star + 1;
+                    v_idx = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p_idx..].iter().all(|c| *c == '*')
+}
+
+/// Selects the metrics with a given name, having at least the given tags.
+///
+/// The name can contain `*` and `?` wildcards.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct MetricSelector {
+    pub name: String,
+    pub tags: IndexMap<String, String>,
+}
+
+impl MetricSelector {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self {
+            name: name.into(),
+            tags: Default::default(),
+        }
+    }
+
+    pub fn with_tags(mut self, tags: IndexMap<String, String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn matches(&self, header: &MetricHeader) -> bool {
+        glob_matches(&self.name, &header.name)
+            && self
+                .tags
+                .iter()
+                .all(|(key, value)| header.tags.get(key) == Some(value))
+    }
+
+    /// Keeps the metrics of the stack matching the selector
+    pub fn select(&self, stack: MetricStack) -> Vec<Metric> {
+        stack
+            .into_metric_iter()
+            .filter(|metric| self.matches(&metric.header))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::MetricSelector;
+    use crate::entity::metric::MetricHeader;
+
+    #[test_case::test_case("foo", "foo", true; "exact")]
+    #[test_case::test_case("foo", "foobar", false; "prefix only")]
+    #[test_case::test_case("foo*", "foobar", true; "star suffix")]
+    #[test_case::test_case("*bar", "foobar", true; "star prefix")]
+    #[test_case::test_case("coverage.*.percentage", "coverage.lines.percentage", true; "star middle")]
+    #[test_case::test_case("coverage.*.percentage", "coverage.lines.count", false; "star middle mismatch")]
+    #[test_case::test_case("a*b*c", "aXbYbZc", true; "multiple stars")]
+    #[test_case::test_case("a*b*c", "aXbYbZ", false; "multiple stars mismatch")]
+    #[test_case::test_case("fo?", "foo", true; "question mark")]
+    #[test_case::test_case("fo?", "fo", false; "question mark missing char")]
+    #[test_case::test_case("*", "", true; "star empty")]
+    fn should_match_glob(pattern: &str, value: &str, expected: bool) {
+        assert_eq!(super::glob_matches(pattern, value), expected);
+    }
+
+    #[test]
+    fn should_match_by_name() {
+        let selector = MetricSelector::new("foo");
+        assert!(selector.matches(&MetricHeader::new("foo")));
+        assert!(selector.matches(&MetricHeader::new("foo").with_tag("a", "b")));
+        assert!(!selector.matches(&MetricHeader::new("bar")));
+    }
+
+    #[test]
+    fn should_match_by_pattern() {
+        let selector = MetricSelector::new("binary-*");
+        assert!(selector.matches(&MetricHeader::new("binary-size")));
+        assert!(!selector.matches(&MetricHeader::new("size")));
+    }
+
+    #[test]
+    fn should_match_by_name_and_tags() {
+        let selector =
+            MetricSelector::new("foo").with_tags(IndexMap::from_iter([("a".into(), "b".into())]));
+        assert!(!selector.matches(&MetricHeader::new("foo")));
+        assert!(selector.matches(&MetricHeader::new("foo").with_tag("a", "b")));
+        assert!(selector.matches(
+            &MetricHeader::new("foo")
+                .with_tag("c", "d")
+                .with_tag("a", "b")
+        ));
+        assert!(!selector.matches(&MetricHeader::new("foo").with_tag("a", "c")));
+    }
+}
@@ -0,0 +1,125 @@
+use super::metric::{MetricChange, MetricStack};
+
+/// Effect of a local change compared to the metrics known before applying it
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ChangeEffect {
+    /// The metric didn't exist and will be created
+    Created,
+    /// The metric existed with a different value that will be replaced
+    Updated { previous: f64 },
+    /// The metric already exists with the same value
+    Unchanged,
+    /// The metric exists with the same value and will be removed
+    Removed,
+    /// The metric doesn't exist with this value, the removal does nothing
+    Ignored,
+}
+
+impl ChangeEffect {
+    fn compute(stack: &MetricStack, change: &MetricChange) -> Self {
+        match change {
+            MetricChange::Add(metric) => match stack.get(&metric.header) {
+                None => Self::Created,
+                Some(previous) if previous == metric.value => Self::Unchanged,
+                Some(previous) => Self::Updated { previous },
+            },
+            MetricChange::Remove(metric) => match stack.get(&metric.header) {
+                Some(previous) if previous == metric.value => Self::Removed,
+                _ => Self::Ignored,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PendingChange {
+    pub change: MetricChange,
+    pub effect: ChangeEffect,
+}
+
+/// Local changes attached to a commit that are not pushed yet
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CommitStatus {
+    pub commit_sha: String,
+    /// The commit can be reached from a local or remote branch
+    pub reachable: bool,
+    pub changes: Vec<PendingChange>,
+}
+
+impl CommitStatus {
+    /// Computes the effect of every change, applying them one after the other on top of the remote metrics
+    pub(crate) fn new(
+        commit_sha: String,
+        reachable: bool,
+        remote: MetricStack,
+        changes: Vec<MetricChange>,
+    ) -> Self {
+        let mut stack = remote;
+        let changes = changes
+            .into_iter()
+            .map(|change| {
+                let effect = ChangeEffect::compute(&stack, &change);
+                stack = std::mem::take(&mut stack).with_change(change.clone());
+                PendingChange { change, effect }
+            })
+            .collect();
+        Self {
+            commit_sha,
+            reachable,
+            changes,
+        }
+    }
+
+    pub(crate) fn short_sha(&self) -> &str {
+        &self.commit_sha[..self.commit_sha.len().min(7)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeEffect, CommitStatus};
+    use crate::entity::metric::{Metric, MetricChange, MetricStack};
+
+    #[test]
+    fn should_compute_effects() {
+        let remote = MetricStack::from_iter(
+            vec![
+                Metric::new("updated", 1.0),
+                Metric::new("unchanged", 2.0),
+                Metric::new("removed", 3.0),
+            ]
+            .into_iter(),
+        );
+        let status = CommitStatus::new(
+            "aaaaaaaaaa".into(),
+            true,
+            remote,
+            vec![
+                MetricChange::Add(Metric::new("created", 1.0)),
+                MetricChange::Add(Metric::new("updated", 10.0)),
+                MetricChange::Add(Metric::new("unchanged", 2.0)),
+                MetricChange::Remove(Metric::new("removed", 3.0)),
+                MetricChange::Remove(Metric::new("unchanged", 3.0)),
+                MetricChange::Remove(Metric::new("removed", 3.0)),
+                MetricChange::Add(Metric::new("created", 2.0)),
+            ],
+        );
+        let effects = status
+            .changes
+            .into_iter()
+            .map(|item| item.effect)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            effects,
+            vec![
+                ChangeEffect::Created,
+                ChangeEffect::Updated { previous: 1.0 },
+                ChangeEffect::Unchanged,
+                ChangeEffect::Removed,
+                ChangeEffect::Ignored,
+                ChangeEffect::Ignored,
+                ChangeEffect::Updated { previous: 1.0 },
+            ]
+        );
+    }
+}
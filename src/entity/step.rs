@@ -0,0 +1,156 @@
+use indexmap::IndexMap;
+
+use super::difference::Comparison;
+use super::git::Commit;
+use super::metric::{Metric, MetricHeader};
+
+/// Change of a metric introduced by a commit
+#[derive(Debug)]
+pub(crate) struct Step {
+    pub commit: Commit,
+    pub header: MetricHeader,
+    pub comparison: Comparison,
+    /// The value was collected by executing a command instead of being recorded
+    pub collected: bool,
+}
+
+impl Step {
+    fn size(&self) -> f64 {
+        self.comparison
+            .delta()
+            .map(|delta| delta.absolute.abs())
+            .unwrap_or_default()
+    }
+}
+
+/// Changes of the metrics along the history, from the oldest commit
+#[derive(Debug, Default)]
+pub(crate) struct StepList {
+    /// Latest value of every metric
+    values: IndexMap<MetricHeader, f64>,
+    pub steps: Vec<Step>,
+    /// Commits without any value for the selected metrics
+    pub unmeasured: Vec<Commit>,
+}
+
+impl StepList {
+    /// Starts from the values before the first commit
+    pub fn new(initial: impl Iterator<Item = Metric>) -> Self {
+        Self {
+            values: initial
+                .map(|metric| (metric.header, metric.value))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds the values of the next commit, keeping the previous values of the missing metrics.
+    ///
+    /// The first value of a metric is a step creating it.
+    pub fn push(&mut self, commit: Commit, metrics: Vec<Metric>, collected: bool) {
+        if metrics.is_empty() {
+            self.unmeasured.push(commit);
+            return;
+        }
+        for metric in metrics {
+            let comparison = match self.values.insert(metric.header.clone(), metric.value) {
+                None => Comparison::created(metric.value),
+                Some(previous) if previous != metric.value => {
+                    Comparison::new(previous, Some(metric.value))
+                }
+                Some(_) => continue,
+            };
+            self.steps.push(Step {
+                commit: commit.clone(),
+                header: metric.header,
+                comparison,
+                collected,
+            });
+        }
+    }
+
+    /// Keeps the largest changes, the largest first
+    pub fn largest(mut self, limit: usize) -> Self {
+        self.steps
+            .sort_by(|first, second| second.size().total_cmp(&first.size()));
+        self.steps.truncate(limit);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StepList;
+    use crate::entity::difference::Comparison;
+    use crate::entity::git::Commit;
+    use crate::entity::metric::Metric;
+
+    fn commit(sha: &str) -> Commit {
+        Commit {
+            sha: sha.into(),
+            summary: format!("commit {sha}"),
+            timestamp: 0,
+            author: String::new(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn should_keep_largest_steps() {
+        let mut list = StepList::new(
+            [
+                Metric::new("size", 100.0).with_tag("os", "linux"),
+                Metric::new("size", 100.0).with_tag("os", "macos"),
+            ]
+            .into_iter(),
+        );
+        list.push(
+            commit("aaaaaaa"),
+            vec![Metric::new("size", 110.0).with_tag("os", "linux")],
+            false,
+        );
+        list.push(commit("aaaaaab"), Vec::new(), false);
+        list.push(
+            commit("aaaaaac"),
+            vec![
+                Metric::new("size", 160.0).with_tag("os", "linux"),
+                Metric::new("size", 100.0).with_tag("os", "macos"),
+            ],
+            true,
+        );
+        list.push(
+            commit("aaaaaad"),
+            vec![Metric::new("size", 150.0).with_tag("os", "linux")],
+            false,
+        );
+        let list = list.largest(2);
+        assert_eq!(
+            list.steps
+                .iter()
+                .map(|step| (step.commit.sha.as_str(), step.comparison.previous()))
+                .collect::<Vec<_>>(),
+            vec![("aaaaaac", Some(110.0)), ("aaaaaaa", Some(100.0))]
+        );
+        assert!(list.steps[0].collected);
+        assert_eq!(list.unmeasured.len(), 1);
+        assert_eq!(list.unmeasured[0].sha, "aaaaaab");
+    }
+
+    #[test]
+    fn should_create_first_values() {
+        let mut list = StepList::new(std::iter::empty());
+        list.push(commit("aaaaaaa"), vec![Metric::new("size", 100.0)], false);
+        list.push(commit("aaaaaab"), vec![Metric::new("size", 100.0)], false);
+        list.push(commit("aaaaaac"), vec![Metric::new("size", 120.0)], false);
+        assert_eq!(
+            list.steps
+                .iter()
+                .map(|step| (step.commit.sha.as_str(), &step.comparison))
+                .collect::<Vec<_>>(),
+            vec![
+                ("aaaaaaa", &Comparison::created(100.0)),
+                ("aaaaaac", &Comparison::matching(100.0, 120.0))
+            ]
+        );
+    }
+}
@@ -0,0 +1,626 @@
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexSet;
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+use super::config::{Config, MetricConfig, ParseError, Rule};
+use super::metric::MetricHeader;
+
+#[derive(Clone, Debug, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path of a value in the configuration, like `metrics.binary-size.rules[1].value`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct KeyPath(Vec<PathSegment>);
+
+impl KeyPath {
+    pub fn key(&self, name: impl Into<String>) -> Self {
+        let mut result = self.clone();
+        result.0.push(PathSegment::Key(name.into()));
+        result
+    }
+
+    pub fn index(&self, index: usize) -> Self {
+        let mut result = self.clone();
+        result.0.push(PathSegment::Index(index));
+        result
+    }
+}
+
+impl std::fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Key(name) if index == 0 => f.write_str(name)?,
+                PathSegment::Key(name) => write!(f, ".{name}")?,
+                PathSegment::Index(value) => write!(f, "[{value}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// Position in the configuration file, starting at 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    fn from_offset(source: &str, offset: usize) -> Self {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// Problem found in the configuration
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Diagnostic {
+    pub severity: Severity,
+    /// Configuration file, relative to the root of the repository
+    pub file: Option<PathBuf>,
+    pub location: Option<Location>,
+    pub path: Option<KeyPath>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            file: None,
+            location: None,
+            path: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.severity, Severity::Error)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = self.file.as_ref() {
+            write!(f, "{}: ", file.display())?;
+        }
+        if let Some(Location { line, column }) = self.location {
+            write!(f, "line {line}, column {column}: ")?;
+        }
+        if let Some(path) = self.path.as_ref() {
+            write!(f, "{path}: ")?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+struct Validator<'a> {
+    file: &'a Path,
+    source: &'a str,
+    root: DeTable<'a>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn location(&self, offset: usize) -> Location {
+        Location::from_offset(self.source, offset)
+    }
+
+    /// Finds the location of a value, using the key for the table entries
+    fn locate(&self, path: &KeyPath) -> Option<Location> {
+        find_value(&self.root, path).map(|(offset, _)| self.location(offset))
+    }
+
+    /// Finds the location of the first field quoted in the message that is defined in the table
+    /// starting at the offset, or in one of the tables of the array starting at the offset.
+    ///
+    /// The errors of the tables converted from another type, like the rules, are located
+    /// on the table instead of the field.
+    fn locate_field(&self, offset: usize, message: &str) -> Option<Location> {
+        let paths = find_table_paths(&self.root, offset, &KeyPath::default())?;
+        paths.iter().find_map(|path| {
+            message
+                .split('`')
+                .skip(1)
+                .step_by(2)
+                .find_map(|name| self.locate(&path.key(name)))
+        })
+    }
+
+    fn push(&mut self, severity: Severity, path: KeyPath, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: Some(self.file.to_path_buf()),
+            location: self.locate(&path),
+            path: Some(path),
+            message: message.into(),
+        });
+    }
+
+    fn check_parsing(&mut self, error: ParseError) {
+        match error {
+            ParseError::Toml(inner) => self.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                file: Some(self.file.to_path_buf()),
+                location: inner.span().map(|span| {
+                    self.locate_field(span.start, inner.message())
+                        .unwrap_or_else(|| self.location(span.start))
+                }),
+                path: None,
+                message: inner.message().trim().to_string(),
+            }),
+            ParseError::Value(inner) => self.push(Severity::Error, inner.path, inner.message),
+        }
+    }
+
+    fn check_bounds(&mut self, mins: &[(KeyPath, f64)], maxs: &[(KeyPath, f64)]) {
+        for (path, min) in mins {
+            if let Some((max_path, max)) = maxs.iter().find(|(_, max)| min > max) {
+                self.push(
+                    Severity::Error,
+                    path.clone(),
+                    format!("min {min} is greater than the max {max} of {max_path}"),
+                );
+            }
+        }
+    }
+
+    /// Reports the min rules greater than a max rule of the same metric or subset
+    fn check_contradictions(&mut self, path: &KeyPath, metric: &MetricConfig) {
+        let (mins, maxs) = bounds(path, &metric.rules);
+        self.check_bounds(&mins, &maxs);
+        for (name, subset) in metric.subsets.iter() {
+            let (subset_mins, subset_maxs) =
+                bounds(&path.key("subsets").key(name.as_str()), &subset.rules);
+            self.check_bounds(&subset_mins, &[maxs.as_slice(), &subset_maxs].concat());
+            self.check_bounds(&mins, &subset_maxs);
+        }
+    }
+
+    /// Reports the configured metrics and subsets that don't match any recorded metric
+    fn check_recorded(
+        &mut self,
+        path: &KeyPath,
+        metric: &MetricConfig,
+        headers: &[MetricHeader],
+        missing: &str,
+    ) {
+        if headers.is_empty() {
+            if metric.required || !metric.rules.is_empty() || !metric.subsets.is_empty() {
+                self.push(Severity::Warning, path.clone(), missing);
+            }
+            return;
+        }
+        for (name, subset) in metric.subsets.iter() {
+            if !headers.iter().any(|header| subset.matches(header)) {
+                self.push(
+                    Severity::Warning,
+                    path.key("subsets").key(name.as_str()),
+                    "the subset doesn't match any recorded metric",
+                );
+            }
+        }
+    }
+
+    fn check_config(&mut self, config: &Config, recorded: Option<&IndexSet<MetricHeader>>) {
+        let metrics = KeyPath::default().key("metrics");
+        for (name, metric) in config.metrics.iter() {
+            let path = metrics.key(name.as_str());
+            self.check_contradictions(&path, metric);
+            let Some(recorded) = recorded else {
+                continue;
+            };
+            if config.derived.contains_key(name) {
+                continue;
+            }
+            let headers = recorded
+                .iter()
+                .filter(|header| {
+                    header.name == *name
+                        || metric
+                            .aliases
+                            .iter()
+                            .any(|alias| alias.name() == header.name)
+                })
+                .map(|header| {
+                    config
+                        .canonical_header(header)
+                        .unwrap_or_else(|| header.clone())
+                })
+                .collect::<Vec<_>>();
+            self.check_recorded(
+                &path,
+                metric,
+                &headers,
+                "no metric has been recorded with this name",
+            );
+        }
+        let patterns = KeyPath::default().key("patterns");
+        for (index, pattern) in config.patterns.iter().enumerate() {
+            let path = patterns.index(index);
+            self.check_contradictions(&path, &pattern.metric);
+            let Some(recorded) = recorded else {
+                continue;
+            };
+            let headers = recorded
+                .iter()
+                .filter(|header| pattern.pattern.matches(&header.name))
+                .cloned()
+                .collect::<Vec<_>>();
+            self.check_recorded(
+                &path,
+                &pattern.metric,
+                &headers,
+                "no recorded metric matches the pattern",
+            );
+        }
+    }
+}
+
+fn find_key<'t, 'a>(
+    table: &'t DeTable<'a>,
+    name: &str,
+) -> Option<(usize, &'t Spanned<DeValue<'a>>)> {
+    table
+        .iter()
+        .find(|(key, _)| key.get_ref() == name)
+        .map(|(key, value)| (key.span().start, value))
+}
+
+/// Finds the paths of the deepest tables starting at the offset, the tables of an array
+/// when the offset is the one of the array
+fn find_table_paths(table: &DeTable<'_>, offset: usize, path: &KeyPath) -> Option<Vec<KeyPath>> {
+    table.iter().find_map(|(key, value)| {
+        find_value_table_paths(value, offset, &path.key(key.get_ref().as_ref()))
+    })
+}
+
+fn find_value_table_paths(
+    value: &Spanned<DeValue<'_>>,
+    offset: usize,
+    path: &KeyPath,
+) -> Option<Vec<KeyPath>> {
+    match value.get_ref() {
+        DeValue::Table(table) => find_table_paths(table, offset, path)
+            .or_else(|| (value.span().start == offset).then(|| vec![path.clone()])),
+        DeValue::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| find_value_table_paths(item, offset, &path.index(index)))
+            .or_else(|| {
+                (value.span().start == offset)
+                    .then(|| (0..items.len()).map(|index| path.index(index)).collect())
+            }),
+        _ => None,
+    }
+}
+
+/// Finds the value at the path, with the offset of its key for the table entries
+pub(crate) fn find_value<'t, 'a>(
+    root: &'t DeTable<'a>,
+    path: &KeyPath,
+) -> Option<(usize, &'t Spanned<DeValue<'a>>)> {
+    let mut found: Option<(usize, &'t Spanned<DeValue<'a>>)> = None;
+    for segment in path.0.iter() {
+        found = Some(match (segment, found.map(|(_, value)| value.get_ref())) {
+            (PathSegment::Key(name), None) => find_key(root, name),
+            (PathSegment::Key(name), Some(DeValue::Table(table))) => find_key(table, name),
+            (PathSegment::Index(index), Some(DeValue::Array(items))) => {
+                items.get(*index).map(|item| (item.span().start, item))
+            }
+            _ => None,
+        }?);
+    }
+    found
+}
+
+/// Finds the value at the path in an editable document
+pub(crate) fn find_value_mut<'d>(
+    root: &'d mut toml_edit::Item,
+    path: &KeyPath,
+) -> Option<&'d mut toml_edit::Value> {
+    // indexing mutably inserts the missing keys, so the path is checked first
+    let mut found = &*root;
+    for segment in path.0.iter() {
+        found = match segment {
+            PathSegment::Key(name) => found.get(name.as_str()),
+            PathSegment::Index(index) => found.get(*index),
+        }?;
+    }
+    found.as_value()?;
+    let mut found = root;
+    for segment in path.0.iter() {
+        found = match segment {
+            PathSegment::Key(name) => found.get_mut(name.as_str()),
+            PathSegment::Index(index) => found.get_mut(*index),
+        }?;
+    }
+    found.as_value_mut()
+}
+
+/// Values of the rules with their path
+type Bounds = Vec<(KeyPath, f64)>;
+
+/// Values of the min and max rules
+fn bounds(path: &KeyPath, rules: &[Rule]) -> (Bounds, Bounds) {
+    let mut mins = Vec::new();
+    let mut maxs = Vec::new();
+    for (index, rule) in rules.iter().enumerate() {
+        match rule {
+            Rule::Min(inner) => mins.push((path.key("rules").index(index), inner.value)),
+            Rule::Max(inner) => maxs.push((path.key("rules").index(index), inner.value)),
+            _ => {}
+        }
+    }
+    (mins, maxs)
+}
+
+/// Validates a configuration file with the result of its parsing, comparing its metrics
+/// with the ones recorded in the history when given
+pub(crate) fn validate(
+    file: &Path,
+    source: &str,
+    parsed: Result<Config, ParseError>,
+    recorded: Option<&IndexSet<MetricHeader>>,
+) -> Vec<Diagnostic> {
+    let root = match DeTable::parse(source) {
+        Ok(root) => root.into_inner(),
+        Err(error) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                file: Some(file.to_path_buf()),
+                location: error
+                    .span()
+                    .map(|span| Location::from_offset(source, span.start)),
+                path: None,
+                message: error.message().trim().to_string(),
+            }]
+        }
+    };
+    let mut validator = Validator {
+        file,
+        source,
+        root,
+        diagnostics: Vec::new(),
+    };
+    match parsed {
+        Ok(config) => validator.check_config(&config, recorded),
+        Err(error) => validator.check_parsing(error),
+    }
+    validator.diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use indexmap::IndexSet;
+
+    use super::{Diagnostic, Location, Severity};
+    use crate::entity::config::Config;
+    use crate::entity::metric::MetricHeader;
+
+    fn errors(source: &str, recorded: &[MetricHeader]) -> Vec<String> {
+        let recorded = recorded.iter().cloned().collect::<IndexSet<_>>();
+        super::validate(
+            Path::new(".git-metrics.toml"),
+            source,
+            Config::parse(source),
+            Some(&recorded),
+        )
+        .iter()
+        .map(|item| format!("{}: {item}", item.severity.label()))
+        .collect()
+    }
+
+    #[test]
+    fn should_accept_valid_config() {
+        let diagnostics = errors(
+            r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0
+
+[metrics.first.subsets.linux]
+matching = { "platform.os" = "linux" }
+"#,
+            &[MetricHeader::new("first").with_tag("platform.os", "linux")],
+        );
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn should_locate_syntax_error() {
+        let source = "[metrics.first]\nrequired = tru\n";
+        let diagnostics = super::validate(
+            Path::new(".git-metrics.toml"),
+            source,
+            Config::parse(source),
+            None,
+        );
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert!(matches!(
+            diagnostics[0],
+            Diagnostic {
+                severity: Severity::Error,
+                location: Some(Location { line: 2, .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test_case::test_case(
+        "[metrics.first]\nrule = []\n",
+        "error: .git-metrics.toml: line 2, column 1: unknown field `rule`, expected one of `description`, `aliases`, `required`, `rules`, `subsets`, `unit`";
+        "in metric"
+    )]
+    #[test_case::test_case(
+        "[[metrics.first.rules]]\ntype = \"max\"\nvaleu = 10\n",
+        "error: .git-metrics.toml: line 3, column 1: unknown field `valeu`, expected `value`";
+        "in absolute rule"
+    )]
+    #[test_case::test_case(
+        "[metrics.first]\nrules = [{ type = \"max\", valeu = 10 }]\n",
+        "error: .git-metrics.toml: line 2, column 26: unknown field `valeu`, expected `value`";
+        "in inline rule"
+    )]
+    #[test_case::test_case(
+        "[[metrics.first.rules]]\ntype = \"max-increase\"\nratio = 0.1\nmin_absolut = 10\n",
+        "error: .git-metrics.toml: line 4, column 1: unknown field `min_absolut`, expected one of `value`, `ratio`, `min_absolute`, `ignore_below`, `zero_baseline`";
+        "in change rule"
+    )]
+    #[test_case::test_case(
+        "[[metrics.first.rules]]\ntype = \"max-increase-over\"\nwindow = 5\nratio = 0.1\nscope = 1\n",
+        "error: .git-metrics.toml: line 5, column 1: unknown field `scope`, expected one of `value`, `ratio`, `min_absolute`, `ignore_below`, `zero_baseline`";
+        "in trend rule"
+    )]
+    #[test_case::test_case(
+        "[[patterns]]\nglob = \"coverage.*\"\nrequire = true\n",
+        "error: .git-metrics.toml: line 3, column 1: unknown field `require`, expected one of `description`, `aliases`, `required`, `rules`, `subsets`, `unit`";
+        "in pattern"
+    )]
+    #[test_case::test_case(
+        "[check]\nfail_on_missin = true\n",
+        "error: .git-metrics.toml: line 2, column 1: unknown field `fail_on_missin`, expected `fail_on_missing` or `baseline`";
+        "in check"
+    )]
+    fn should_report_unknown_fields(source: &str, expected: &str) {
+        similar_asserts::assert_eq!(
+            errors(source, &[MetricHeader::new("first")]),
+            vec![expected]
+        );
+    }
+
+    #[test_case::test_case(
+        "[[metrics.first.rules]]\ntype = \"max-increase\"\nvalue = 10\nratio = 0.1\n",
+        "error: .git-metrics.toml: line 3, column 1: expected either `value` or `ratio`, not both";
+        "with both"
+    )]
+    #[test_case::test_case(
+        "[[metrics.first.rules]]\ntype = \"max-increase\"\nvalue = 10\nignore_below = 5\n",
+        "error: .git-metrics.toml: line 4, column 1: `min_absolute`, `ignore_below` and `zero_baseline` only apply with `ratio`";
+        "with relative field"
+    )]
+    fn should_report_invalid_change(source: &str, expected: &str) {
+        similar_asserts::assert_eq!(
+            errors(source, &[MetricHeader::new("first")]),
+            vec![expected]
+        );
+    }
+
+    #[test]
+    fn should_report_unknown_rule_type() {
+        let diagnostics = errors(
+            r#"[[metrics.first.rules]]
+type = "max-increse"
+ratio = 0.1
+"#,
+            &[MetricHeader::new("first")],
+        );
+        similar_asserts::assert_eq!(
+            diagnostics,
+            vec![
+                "error: .git-metrics.toml: line 2, column 8: unknown variant `max-increse`, expected one of `max`, `min`, `max-increase`, `max-decrease`, `max-increase-over`",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_locate_invalid_unit_value() {
+        similar_asserts::assert_eq!(
+            errors(
+                r#"[metrics.size]
+unit = { scale = "binary", suffix = "B" }
+
+[[metrics.size.rules]]
+type = "max"
+value = "10 MB"
+"#,
+                &[MetricHeader::new("size")],
+            ),
+            vec![
+                "error: .git-metrics.toml: line 6, column 1: metrics.size.rules[0].value: unit \"MB\" is not compatible with the metric, expected a binary prefix followed by \"B\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_contradictory_rules() {
+        similar_asserts::assert_eq!(
+            errors(
+                r#"[[metrics.first.rules]]
+type = "max"
+value = 10.0
+
+[[metrics.first.rules]]
+type = "min"
+value = 20.0
+
+[[metrics.first.subsets.linux.rules]]
+type = "max"
+value = 5.0
+
+[metrics.first.subsets.linux.matching]
+"platform.os" = "linux"
+"#,
+                &[MetricHeader::new("first").with_tag("platform.os", "linux")],
+            ),
+            vec![
+                "error: .git-metrics.toml: line 5, column 1: metrics.first.rules[1]: min 20 is greater than the max 10 of metrics.first.rules[0]",
+                "error: .git-metrics.toml: line 5, column 1: metrics.first.rules[1]: min 20 is greater than the max 5 of metrics.first.subsets.linux.rules[0]",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_unknown_metrics_and_subsets() {
+        similar_asserts::assert_eq!(
+            errors(
+                r#"[[metrics.first.rules]]
+type = "max"
+value = 10.0
+
+[metrics.second.subsets.windows]
+matching = { "platform.os" = "windows" }
+
+[metrics.third]
+unit = { scale = "binary" }
+
+[[patterns]]
+glob = "coverage.*"
+required = true
+"#,
+                &[MetricHeader::new("second").with_tag("platform.os", "linux")],
+            ),
+            vec![
+                "warning: .git-metrics.toml: line 1, column 11: metrics.first: no metric has been recorded with this name",
+                "warning: .git-metrics.toml: line 5, column 25: metrics.second.subsets.windows: the subset doesn't match any recorded metric",
+                "warning: .git-metrics.toml: line 11, column 1: patterns[0]: no recorded metric matches the pattern",
+            ]
+        );
+    }
+}
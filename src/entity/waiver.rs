@@ -0,0 +1,162 @@
+use super::config::Unit;
+use super::difference::Comparison;
+
+/// Key of the commit trailers acknowledging a regression
+const TRAILER_KEY: &str = "Metrics-Allow";
+
+/// Change accepted by a waiver
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum WaiverLimit {
+    /// Ratio of the previous value, like `+15%`
+    Relative(f64),
+    /// Value written with the unit of the metric, like `+2 MiB`
+    Absolute(String),
+}
+
+/// Regression acknowledged in a commit message, like `Metrics-Allow: binary-size +15%`
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Waiver {
+    pub metric: String,
+    /// Any change is accepted when not defined
+    pub limit: Option<WaiverLimit>,
+}
+
+impl Waiver {
+    fn parse_value(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (metric, limit) = match value.split_once(char::is_whitespace) {
+            Some((metric, limit)) => (metric, Some(limit.trim())),
+            None => (value, None),
+        };
+        if metric.is_empty() {
+            return None;
+        }
+        let limit = match limit.filter(|limit| !limit.is_empty()) {
+            Some(limit) => match limit.strip_suffix('%') {
+                Some(ratio) => Some(WaiverLimit::Relative(
+                    ratio.trim().parse::<f64>().ok()? / 100.0,
+                )),
+                None => Some(WaiverLimit::Absolute(limit.to_string())),
+            },
+            None => None,
+        };
+        Some(Self {
+            metric: metric.to_string(),
+            limit,
+        })
+    }
+
+    /// Finds the waivers in the trailers of a commit message
+    pub fn from_message(message: &str) -> impl Iterator<Item = Waiver> + '_ {
+        message.lines().filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key.trim().eq_ignore_ascii_case(TRAILER_KEY) {
+                Self::parse_value(value)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Checks if the change of the metric is accepted by the waiver.
+    ///
+    /// A positive limit accepts an increase up to the limit and a negative one a decrease,
+    /// a change in the other direction is not accepted.
+    pub fn allows(&self, comparison: &Comparison, unit: &Unit) -> bool {
+        let Some(limit) = self.limit.as_ref() else {
+            return true;
+        };
+        let Comparison::Matching { delta, .. } = comparison else {
+            return false;
+        };
+        let (change, allowed) = match limit {
+            WaiverLimit::Relative(ratio) => match delta.relative {
+                Some(relative) => (relative, *ratio),
+                None => return false,
+            },
+            WaiverLimit::Absolute(value) => match unit.parse(value) {
+                Ok(value) => (delta.absolute, value),
+                Err(err) => {
+                    tracing::warn!("invalid waiver limit for {}: {err}", self.metric);
+                    return false;
+                }
+            },
+        };
+        if allowed >= 0.0 {
+            (0.0..=allowed).contains(&change)
+        } else {
+            (allowed..=0.0).contains(&change)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Waiver, WaiverLimit};
+    use crate::entity::config::Unit;
+    use crate::entity::difference::Comparison;
+
+    #[test]
+    fn should_parse_trailers() {
+        let waivers = Waiver::from_message(
+            r#"Add the new parser
+
+Metrics-Allow: binary-size +15%
+metrics-allow: coverage
+Metrics-Allow: build-time +2 MiB
+Signed-off-by: Someone <someone@example.com>
+Metrics-Allow:
+"#,
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(
+            waivers,
+            vec![
+                Waiver {
+                    metric: "binary-size".into(),
+                    limit: Some(WaiverLimit::Relative(0.15)),
+                },
+                Waiver {
+                    metric: "coverage".into(),
+                    limit: None,
+                },
+                Waiver {
+                    metric: "build-time".into(),
+                    limit: Some(WaiverLimit::Absolute("+2 MiB".into())),
+                },
+            ]
+        );
+    }
+
+    #[test_case::test_case("binary-size", 100.0, 200.0, true; "without limit")]
+    #[test_case::test_case("binary-size +15%", 100.0, 110.0, true; "relative increase within limit")]
+    #[test_case::test_case("binary-size +15%", 100.0, 120.0, false; "relative increase above limit")]
+    #[test_case::test_case("binary-size -5%", 100.0, 96.0, true; "relative decrease within limit")]
+    #[test_case::test_case("binary-size -5%", 100.0, 90.0, false; "relative decrease above limit")]
+    #[test_case::test_case("binary-size +1 kiB", 1024.0, 2000.0, true; "absolute increase within limit")]
+    #[test_case::test_case("binary-size +1 kiB", 1024.0, 4096.0, false; "absolute increase above limit")]
+    #[test_case::test_case("binary-size +15%", 100.0, 50.0, false; "relative decrease with increase limit")]
+    #[test_case::test_case("binary-size -5%", 100.0, 150.0, false; "relative increase with decrease limit")]
+    #[test_case::test_case("binary-size +1 kiB", 4096.0, 1024.0, false; "absolute decrease with increase limit")]
+    #[test_case::test_case("binary-size -1 kiB", 1024.0, 4096.0, false; "absolute increase with decrease limit")]
+    fn should_allow(trailer: &str, previous: f64, current: f64, expected: bool) {
+        let waiver = Waiver::parse_value(trailer).unwrap();
+        let unit = Unit::binary().with_suffix("B");
+        assert_eq!(
+            waiver.allows(&Comparison::matching(previous, current), &unit),
+            expected
+        );
+    }
+
+    #[test]
+    fn should_only_allow_missing_without_limit() {
+        let unit = Unit::default();
+        let missing = Comparison::Missing { previous: 10.0 };
+        assert!(Waiver::parse_value("coverage")
+            .unwrap()
+            .allows(&missing, &unit));
+        assert!(!Waiver::parse_value("coverage -5%")
+            .unwrap()
+            .allows(&missing, &unit));
+    }
+}
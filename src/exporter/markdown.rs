@@ -1,7 +1,5 @@
 use std::path::Path;
 
-use human_number::Formatter;
-
 use crate::entity::check::{CheckList, MetricCheck, RuleCheck, Status};
 use crate::entity::config::Config;
 use crate::entity::log::LogEntry;
@@ -9,6 +7,7 @@ use crate::entity::metric::MetricHeader;
 use crate::formatter::difference::LongTextComparison;
 use crate::formatter::metric::TextMetricTags;
 use crate::formatter::rule::TextRule;
+use crate::formatter::value::ValueFormatter;
 
 struct MetricCheckTitle<'a>(&'a MetricHeader);
 
@@ -21,12 +20,12 @@ impl std::fmt::Display for MetricCheckTitle<'_> {
 }
 
 struct RuleCheckSection<'a> {
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
     rule: &'a RuleCheck,
 }
 
 impl<'a> RuleCheckSection<'a> {
-    const fn new(formatter: &'a Formatter<'a>, rule: &'a RuleCheck) -> Self {
+    const fn new(formatter: &'a ValueFormatter<'a>, rule: &'a RuleCheck) -> Self {
         Self { formatter, rule }
     }
 }
@@ -57,12 +56,16 @@ impl<'a> CheckSection<'a> {
 impl std::fmt::Display for CheckSection<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let formatter = self.config.formatter(&self.check.diff.header.name);
-        writeln!(
+        write!(
             f,
             "### {} `{}`",
             self.check.status.status().emoji(),
             MetricCheckTitle(&self.check.diff.header),
         )?;
+        match self.check.aggregate.as_ref() {
+            Some(aggregate) => writeln!(f, " ({aggregate})")?,
+            None => writeln!(f)?,
+        };
         writeln!(f)?;
         LongTextComparison::new(&formatter, &self.check.diff.comparison).fmt(f)?;
         writeln!(f)?;
@@ -94,6 +97,21 @@ impl std::fmt::Display for ChecklistSection<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "## Check conclusion")?;
         writeln!(f)?;
+        if let Some(baseline) = self.checklist.baseline.as_deref() {
+            writeln!(f, "Compared with `{baseline}`.")?;
+            writeln!(f)?;
+        }
+        if !self.checklist.overrides.is_empty() {
+            let overrides = self
+                .checklist
+                .overrides
+                .iter()
+                .map(|item| format!("`{item}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "Overrides applied for {overrides}.")?;
+            writeln!(f)?;
+        }
         match self.checklist.status.status() {
             Status::Success => {
                 writeln!(f, "The current target is successful ✅")?;
@@ -104,16 +122,23 @@ impl std::fmt::Display for ChecklistSection<'_> {
             Status::Failed => {
                 writeln!(f, "The current target failed the checklist ⛔️")?;
             }
+            Status::Waived => {
+                writeln!(
+                    f,
+                    "The current target is successful with some waived regressions 🙈"
+                )?;
+            }
         }
         writeln!(f)?;
-        writeln!(f, "| Success    | Skipped    | Failed     |")?;
-        writeln!(f, "|:----------:|:----------:|:----------:|")?;
+        writeln!(f, "| Success    | Skipped    | Failed     | Waived     |")?;
+        writeln!(f, "|:----------:|:----------:|:----------:|:----------:|")?;
         writeln!(
             f,
-            "| {: >10} | {: >10} | {: >10} |",
+            "| {: >10} | {: >10} | {: >10} | {: >10} |",
             self.checklist.status.success,
             self.checklist.status.neutral,
-            self.checklist.status.failed
+            self.checklist.status.failed,
+            self.checklist.status.waived
         )?;
         writeln!(f)?;
         for check in self.checklist.list.iter() {
@@ -245,6 +270,9 @@ mod tests {
                 commit: Commit {
                     sha: "aaaaaaaaaaaaa".into(),
                     summary: "first commit".into(),
+                    timestamp: 0,
+                    author: String::new(),
+                    body: String::new(),
                 },
                 metrics: Vec::new(),
             },
@@ -252,6 +280,9 @@ mod tests {
                 commit: Commit {
                     sha: "bbbbbbbbbbbbb".into(),
                     summary: "second commit".into(),
+                    timestamp: 0,
+                    author: String::new(),
+                    body: String::new(),
                 },
                 metrics: vec![Metric::new("name", 42.0).with_tag("foo", "bar")],
             },
@@ -285,9 +316,9 @@ name{foo="bar"} 42.00
 
 All the elements from the checklist were skipped.
 
-| Success    | Skipped    | Failed     |
-|:----------:|:----------:|:----------:|
-|          0 |          0 |          0 |
+| Success    | Skipped    | Failed     | Waived     |
+|:----------:|:----------:|:----------:|:----------:|
+|          0 |          0 |          0 |          0 |
 
 "#,
             output
@@ -315,9 +346,9 @@ All the elements from the checklist were skipped.
 
 The current target failed the checklist ⛔️
 
-| Success    | Skipped    | Failed     |
-|:----------:|:----------:|:----------:|
-|          0 |          0 |          1 |
+| Success    | Skipped    | Failed     | Waived     |
+|:----------:|:----------:|:----------:|:----------:|
+|          0 |          0 |          1 |          0 |
 
 ### ⏭️ `created`
 
@@ -357,9 +388,9 @@ This metric changed from 21.00 to 42.00, with a difference of +21.00 (+100.00 %)
 
 The current target is successful ✅
 
-| Success    | Skipped    | Failed     |
-|:----------:|:----------:|:----------:|
-|          1 |          0 |          0 |
+| Success    | Skipped    | Failed     | Waived     |
+|:----------:|:----------:|:----------:|:----------:|
+|          1 |          0 |          0 |          0 |
 
 ### ⏭️ `created`
 
@@ -398,6 +429,9 @@ This metric changed from 21.00 to 42.00, with a difference of +21.00 (+100.00 %)
                 commit: Commit {
                     sha: "aaaaaaaaaaaaa".into(),
                     summary: "first commit".into(),
+                    timestamp: 0,
+                    author: String::new(),
+                    body: String::new(),
                 },
                 metrics: Vec::new(),
             },
@@ -405,6 +439,9 @@ This metric changed from 21.00 to 42.00, with a difference of +21.00 (+100.00 %)
                 commit: Commit {
                     sha: "bbbbbbbbbbbbb".into(),
                     summary: "second commit".into(),
+                    timestamp: 0,
+                    author: String::new(),
+                    body: String::new(),
                 },
                 metrics: vec![Metric::new("name", 42.0).with_tag("foo", "bar")],
             },
@@ -421,9 +458,9 @@ Generated for the target `HEAD~4..HEAD`.
 
 The current target is successful ✅
 
-| Success    | Skipped    | Failed     |
-|:----------:|:----------:|:----------:|
-|          1 |          0 |          0 |
+| Success    | Skipped    | Failed     | Waived     |
+|:----------:|:----------:|:----------:|:----------:|
+|          1 |          0 |          0 |          0 |
 
 ### ⏭️ `created`
 
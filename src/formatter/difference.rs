@@ -1,15 +1,14 @@
-use human_number::Formatter;
-
 use super::percent::TextPercent;
 use crate::entity::difference::{Comparison, Delta};
+use crate::formatter::value::ValueFormatter;
 
 pub(crate) struct TextDelta<'a> {
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
     value: &'a Delta,
 }
 
 impl<'a> TextDelta<'a> {
-    pub fn new(formatter: &'a Formatter<'a>, value: &'a Delta) -> Self {
+    pub fn new(formatter: &'a ValueFormatter<'a>, value: &'a Delta) -> Self {
         Self { formatter, value }
     }
 }
@@ -29,13 +28,13 @@ impl std::fmt::Display for TextDelta<'_> {
 }
 
 pub(crate) struct ShortTextComparison<'a> {
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
     value: &'a Comparison,
 }
 
 impl<'a> ShortTextComparison<'a> {
     #[inline]
-    pub const fn new(formatter: &'a Formatter<'a>, value: &'a Comparison) -> Self {
+    pub const fn new(formatter: &'a ValueFormatter<'a>, value: &'a Comparison) -> Self {
         Self { formatter, value }
     }
 }
@@ -50,6 +49,7 @@ impl std::fmt::Display for ShortTextComparison<'_> {
             Comparison::Missing { previous } => {
                 write!(f, "{} (old)", self.formatter.format(*previous))
             }
+            Comparison::Absent => f.write_str("(absent)"),
             Comparison::Matching {
                 previous,
                 current,
@@ -84,13 +84,13 @@ impl std::fmt::Display for ShortTextComparison<'_> {
 }
 
 pub(crate) struct LongTextComparison<'a> {
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
     value: &'a Comparison,
 }
 
 impl<'a> LongTextComparison<'a> {
     #[inline]
-    pub const fn new(formatter: &'a Formatter<'a>, value: &'a Comparison) -> Self {
+    pub const fn new(formatter: &'a ValueFormatter<'a>, value: &'a Comparison) -> Self {
         Self { formatter, value }
     }
 }
@@ -113,6 +113,9 @@ impl std::fmt::Display for LongTextComparison<'_> {
                     self.formatter.format(*previous)
                 )
             }
+            Comparison::Absent => {
+                f.write_str("This metric doesn't exist, neither for the current target nor before.")
+            }
             Comparison::Matching {
                 previous: _,
                 current,
@@ -151,6 +154,7 @@ mod tests {
 
     use super::TextDelta;
     use crate::entity::difference::Delta;
+    use crate::formatter::value::ValueFormatter;
 
     #[test_case::test_case(10.0, 20.0, "+10.00 B (+100.00 %)"; "with increase")]
     #[test_case::test_case(20.0, 10.0, "-10.00 B (-50.00 %)"; "with decrease")]
@@ -159,7 +163,7 @@ mod tests {
     #[test_case::test_case(0.0, 10.0, "+10.00 B"; "increase from 0")]
     #[test_case::test_case(0.0, -10.0, "-10.00 B"; "decrease from 0")]
     fn format_delta(previous: f64, current: f64, expected: &str) {
-        let fmt = Formatter::binary().with_unit("B").with_force_sign(true);
+        let fmt = ValueFormatter::from(Formatter::binary().with_unit("B")).with_force_sign(true);
         let delta = Delta::new(previous, current);
         assert_eq!(expected, TextDelta::new(&fmt, &delta).to_string());
     }
@@ -1,5 +1,6 @@
 use indexmap::IndexMap;
 
+use crate::entity::config::TagMatcher;
 use crate::entity::metric::MetricHeader;
 
 pub struct TextMetricTags<'a> {
@@ -29,6 +30,33 @@ impl std::fmt::Display for TextMetricTags<'_> {
     }
 }
 
+pub struct TextTagMatchers<'a> {
+    value: &'a IndexMap<String, TagMatcher>,
+}
+
+impl<'a> TextTagMatchers<'a> {
+    #[inline]
+    pub const fn new(value: &'a IndexMap<String, TagMatcher>) -> Self {
+        Self { value }
+    }
+}
+
+impl std::fmt::Display for TextTagMatchers<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.value.is_empty() {
+            f.write_str("{")?;
+            for (index, (key, value)) in self.value.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{key}={value}")?;
+            }
+            f.write_str("}")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct TextMetricHeader<'a> {
     value: &'a MetricHeader,
 }
@@ -2,3 +2,4 @@ pub(crate) mod difference;
 pub(crate) mod metric;
 pub(crate) mod percent;
 pub(crate) mod rule;
+pub(crate) mod value;
@@ -1,20 +1,101 @@
-use human_number::Formatter;
-
-use crate::entity::config::{Rule, RuleAbsolute, RuleChange, RuleRelative};
+use crate::entity::config::{
+    Rule, RuleAbsolute, RuleChange, RuleRelative, RuleWindow, ZeroBaseline,
+};
 use crate::formatter::percent::TextPercent;
+use crate::formatter::value::ValueFormatter;
+
+/// Conditions of a relative rule, between parenthesis
+struct TextRelativeGuards<'a> {
+    formatter: &'a ValueFormatter<'a>,
+    value: &'a RuleRelative,
+    direction: &'static str,
+}
+
+impl<'a> TextRelativeGuards<'a> {
+    #[inline]
+    const fn new(
+        formatter: &'a ValueFormatter<'a>,
+        value: &'a RuleRelative,
+        direction: &'static str,
+    ) -> Self {
+        Self {
+            formatter,
+            value,
+            direction,
+        }
+    }
+}
+
+impl std::fmt::Display for TextRelativeGuards<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut guards = Vec::with_capacity(3);
+        if let Some(min) = self.value.min_absolute {
+            guards.push(format!(
+                "{} up to {} accepted",
+                self.direction,
+                self.formatter.format(min)
+            ));
+        }
+        if let Some(limit) = self.value.ignore_below {
+            guards.push(format!("ignored below {}", self.formatter.format(limit)));
+        }
+        match self.value.zero_baseline {
+            ZeroBaseline::Skip => {}
+            ZeroBaseline::Fail => guards.push("any change from zero fails".into()),
+            ZeroBaseline::Infinite => {
+                guards.push(format!("any {} from zero fails", self.direction))
+            }
+        }
+        if guards.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " ({})", guards.join(", "))
+        }
+    }
+}
 
 pub(crate) struct TextRule<'a> {
-    formatter: &'a Formatter<'a>,
+    formatter: &'a ValueFormatter<'a>,
     value: &'a Rule,
 }
 
 impl<'a> TextRule<'a> {
     #[inline]
-    pub const fn new(formatter: &'a Formatter<'a>, value: &'a Rule) -> Self {
+    pub const fn new(formatter: &'a ValueFormatter<'a>, value: &'a Rule) -> Self {
         Self { formatter, value }
     }
 }
 
+impl TextRule<'_> {
+    fn fmt_change(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        subject: &str,
+        direction: &'static str,
+        change: &RuleChange,
+    ) -> std::fmt::Result {
+        match change {
+            RuleChange::Relative(rule) => {
+                write!(
+                    f,
+                    "{subject} should be less than {}",
+                    TextPercent::new(rule.ratio)
+                )?;
+                write!(
+                    f,
+                    "{}",
+                    TextRelativeGuards::new(self.formatter, rule, direction)
+                )
+            }
+            RuleChange::Absolute(RuleAbsolute { value }) => write!(
+                f,
+                "{subject} should be less than {}",
+                self.formatter.format(*value)
+            ),
+        }
+    }
+}
+
 impl std::fmt::Display for TextRule<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.value {
@@ -26,34 +107,58 @@ impl std::fmt::Display for TextRule<'_> {
                 "should be greater than {}",
                 self.formatter.format(*value)
             ),
-            Rule::MaxIncrease(RuleChange::Relative(RuleRelative { ratio })) => {
-                write!(
-                    f,
-                    "increase should be less than {}",
-                    TextPercent::new(*ratio)
-                )
-            }
-            Rule::MaxIncrease(RuleChange::Absolute(RuleAbsolute { value })) => {
-                write!(
-                    f,
-                    "increase should be less than {}",
-                    self.formatter.format(*value)
-                )
-            }
-            Rule::MaxDecrease(RuleChange::Relative(RuleRelative { ratio })) => {
-                write!(
-                    f,
-                    "decrease should be less than {}",
-                    TextPercent::new(*ratio)
-                )
-            }
-            Rule::MaxDecrease(RuleChange::Absolute(RuleAbsolute { value })) => {
-                write!(
-                    f,
-                    "decrease should be less than {}",
-                    self.formatter.format(*value)
-                )
-            }
+            Rule::MaxIncrease(change) => self.fmt_change(f, "increase", "increase", change),
+            Rule::MaxDecrease(change) => self.fmt_change(f, "decrease", "decrease", change),
+            Rule::MaxIncreaseOver(RuleWindow { window, change }) => self.fmt_change(
+                f,
+                &format!("increase over the last {window}"),
+                "increase",
+                change,
+            ),
+            Rule::Required => f.write_str("should be defined"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use human_number::Formatter;
+
+    use super::TextRule;
+    use crate::entity::config::{Rule, RuleChange, RuleRelative, RuleWindow, Window, ZeroBaseline};
+    use crate::formatter::value::ValueFormatter;
+
+    #[test_case::test_case(None, None, ZeroBaseline::Skip, "increase should be less than 10.00 %"; "without guard")]
+    #[test_case::test_case(Some(1024.0), None, ZeroBaseline::Skip, "increase should be less than 10.00 % (increase up to 1.00 kiB accepted)"; "with min absolute")]
+    #[test_case::test_case(None, Some(2048.0), ZeroBaseline::Fail, "increase should be less than 10.00 % (ignored below 2.00 kiB, any change from zero fails)"; "with ignore below")]
+    #[test_case::test_case(None, None, ZeroBaseline::Infinite, "increase should be less than 10.00 % (any increase from zero fails)"; "with infinite")]
+    fn should_format_relative_rule(
+        min_absolute: Option<f64>,
+        ignore_below: Option<f64>,
+        zero_baseline: ZeroBaseline,
+        expected: &str,
+    ) {
+        let formatter = ValueFormatter::from(Formatter::binary().with_unit("B"));
+        let rule = Rule::MaxIncrease(RuleChange::Relative(RuleRelative {
+            ratio: 0.1,
+            min_absolute,
+            ignore_below,
+            zero_baseline,
+        }));
+        assert_eq!(TextRule::new(&formatter, &rule).to_string(), expected);
+    }
+
+    #[test_case::test_case(Window::Commits(50), "increase over the last 50 commits should be less than 10.00 %"; "with commits")]
+    #[test_case::test_case(Window::Days(1), "increase over the last 1 day should be less than 10.00 %"; "with days")]
+    fn should_format_window_rule(window: Window, expected: &str) {
+        let formatter = ValueFormatter::from(Formatter::binary().with_unit("B"));
+        let rule = Rule::MaxIncreaseOver(RuleWindow {
+            window,
+            change: RuleChange::Relative(RuleRelative {
+                ratio: 0.1,
+                ..Default::default()
+            }),
+        });
+        assert_eq!(TextRule::new(&formatter, &rule).to_string(), expected);
+    }
+}
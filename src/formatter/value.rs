@@ -0,0 +1,172 @@
+use crate::entity::config::DurationBase;
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+
+const DEFAULT_DECIMALS: usize = 2;
+
+/// Formatter for the values of a metric, depending on its unit
+#[derive(Clone, Debug)]
+pub(crate) enum ValueFormatter<'a> {
+    Number(human_number::Formatter<'a>),
+    /// Value expressed in the base unit and displayed as a duration, like `12m 34s`
+    Duration {
+        base: DurationBase,
+        decimals: usize,
+        force_sign: bool,
+    },
+    /// Ratio displayed as a percentage, like `85.00 %`
+    Percent {
+        decimals: usize,
+        force_sign: bool,
+    },
+}
+
+impl<'a> From<human_number::Formatter<'a>> for ValueFormatter<'a> {
+    fn from(value: human_number::Formatter<'a>) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl<'a> ValueFormatter<'a> {
+    pub fn duration(base: DurationBase, decimals: Option<usize>) -> Self {
+        Self::Duration {
+            base,
+            decimals: decimals.unwrap_or(DEFAULT_DECIMALS),
+            force_sign: false,
+        }
+    }
+
+    pub fn percent(decimals: Option<usize>) -> Self {
+        Self::Percent {
+            decimals: decimals.unwrap_or(DEFAULT_DECIMALS),
+            force_sign: false,
+        }
+    }
+
+    pub fn with_force_sign(self, force_sign: bool) -> Self {
+        match self {
+            Self::Number(inner) => Self::Number(inner.with_force_sign(force_sign)),
+            Self::Duration { base, decimals, .. } => Self::Duration {
+                base,
+                decimals,
+                force_sign,
+            },
+            Self::Percent { decimals, .. } => Self::Percent {
+                decimals,
+                force_sign,
+            },
+        }
+    }
+
+    #[inline]
+    pub fn format(&self, value: f64) -> FormattedValue<'_, 'a> {
+        FormattedValue {
+            formatter: self,
+            value,
+        }
+    }
+}
+
+pub(crate) struct FormattedValue<'f, 'a> {
+    formatter: &'f ValueFormatter<'a>,
+    value: f64,
+}
+
+fn write_sign(f: &mut std::fmt::Formatter<'_>, value: f64, force_sign: bool) -> std::fmt::Result {
+    if value < 0.0 {
+        f.write_str("-")
+    } else if force_sign {
+        f.write_str("+")
+    } else {
+        Ok(())
+    }
+}
+
+fn write_duration(
+    f: &mut std::fmt::Formatter<'_>,
+    seconds: f64,
+    decimals: usize,
+) -> std::fmt::Result {
+    if seconds == 0.0 {
+        return f.write_str("0s");
+    }
+    if seconds < 1.0e-6 {
+        return write!(f, "{:.decimals$}ns", seconds * 1.0e9);
+    }
+    if seconds < 1.0e-3 {
+        return write!(f, "{:.decimals$}µs", seconds * 1.0e6);
+    }
+    if seconds < 1.0 {
+        return write!(f, "{:.decimals$}ms", seconds * 1.0e3);
+    }
+    if seconds < MINUTE {
+        return write!(f, "{seconds:.decimals$}s");
+    }
+    let mut remaining = seconds.round();
+    let mut first = true;
+    for (factor, suffix) in [(DAY, "d"), (HOUR, "h"), (MINUTE, "m"), (1.0, "s")] {
+        let count = (remaining / factor).floor();
+        remaining -= count * factor;
+        if count > 0.0 {
+            if !first {
+                f.write_str(" ")?;
+            }
+            write!(f, "{count}{suffix}")?;
+            first = false;
+        }
+    }
+    Ok(())
+}
+
+impl std::fmt::Display for FormattedValue<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.formatter {
+            ValueFormatter::Number(inner) => inner.format(self.value).fmt(f),
+            ValueFormatter::Duration {
+                base,
+                decimals,
+                force_sign,
+            } => {
+                write_sign(f, self.value, *force_sign)?;
+                write_duration(f, base.to_seconds(self.value.abs()), *decimals)
+            }
+            ValueFormatter::Percent {
+                decimals,
+                force_sign,
+            } => {
+                write_sign(f, self.value, *force_sign)?;
+                write!(f, "{:.decimals$} %", self.value.abs() * 100.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValueFormatter;
+    use crate::entity::config::DurationBase;
+
+    #[test_case::test_case(DurationBase::S, 754.0, "12m 34s"; "minutes and seconds")]
+    #[test_case::test_case(DurationBase::S, 3600.0, "1h"; "exact hour")]
+    #[test_case::test_case(DurationBase::S, 90061.0, "1d 1h 1m 1s"; "every unit")]
+    #[test_case::test_case(DurationBase::S, 1.5, "1.50s"; "seconds")]
+    #[test_case::test_case(DurationBase::Ms, 250.0, "250.00ms"; "milliseconds")]
+    #[test_case::test_case(DurationBase::Ms, 125_000.0, "2m 5s"; "milliseconds to minutes")]
+    #[test_case::test_case(DurationBase::Ns, 1500.0, "1.50µs"; "nanoseconds to microseconds")]
+    #[test_case::test_case(DurationBase::S, 0.0, "0s"; "zero")]
+    #[test_case::test_case(DurationBase::S, -90.0, "-1m 30s"; "negative")]
+    fn should_format_duration(base: DurationBase, value: f64, expected: &str) {
+        let formatter = ValueFormatter::duration(base, None);
+        assert_eq!(formatter.format(value).to_string(), expected);
+    }
+
+    #[test_case::test_case(0.85, false, "85.00 %"; "ratio")]
+    #[test_case::test_case(0.05, true, "+5.00 %"; "with sign")]
+    #[test_case::test_case(-0.05, true, "-5.00 %"; "negative with sign")]
+    fn should_format_percent(value: f64, sign: bool, expected: &str) {
+        let formatter = ValueFormatter::percent(None).with_force_sign(sign);
+        assert_eq!(formatter.format(value).to_string(), expected);
+    }
+}
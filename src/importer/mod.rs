@@ -2,6 +2,8 @@ use crate::entity::metric::Metric;
 
 #[cfg(feature = "importer-lcov")]
 pub(crate) mod lcov;
+#[cfg(feature = "importer-text")]
+pub(crate) mod text;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -18,6 +20,15 @@ pub enum Error {
     },
 }
 
+impl crate::error::DetailedError for Error {
+    fn details(&self) -> Option<String> {
+        match self {
+            Self::Io { source } => Some(source.to_string()),
+            Self::InvalidFormat { source } => Some(source.to_string()),
+        }
+    }
+}
+
 pub trait Importer {
     fn import(self) -> Result<Vec<Metric>, Error>;
 }
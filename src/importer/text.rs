@@ -0,0 +1,330 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+
+use crate::entity::metric::{Metric, MetricHeader};
+
+/// Error returned when a line doesn't match the `name{key="value"} 12.34` format
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("line {line}, column {column}: {message}\n{content}\n{caret:>column$}")]
+pub(crate) struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+    content: String,
+    caret: &'static str,
+}
+
+/// Error with the byte offset in the line where it happened
+struct LineError(usize, &'static str);
+
+struct LineParser<'a> {
+    line: &'a str,
+    offset: usize,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(line: &'a str) -> Self {
+        Self { line, offset: 0 }
+    }
+
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.line[self.offset..]
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.offset += next.len_utf8();
+        Some(next)
+    }
+
+    fn skip_whitespaces(&mut self) -> usize {
+        let before = self.offset;
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+        self.offset - before
+    }
+
+    fn take_until(&mut self, stop: impl Fn(char) -> bool) -> &'a str {
+        let start = self.offset;
+        while self.peek().is_some_and(|c| !stop(c)) {
+            self.next();
+        }
+        &self.line[start..self.offset]
+    }
+
+    fn expect(&mut self, expected: char, message: &'static str) -> Result<(), LineError> {
+        if self.peek() == Some(expected) {
+            self.next();
+            Ok(())
+        } else {
+            Err(LineError(self.offset, message))
+        }
+    }
+
+    fn name(&mut self) -> Result<String, LineError> {
+        let start = self.offset;
+        let name = self.take_until(|c| c == '{' || c.is_whitespace());
+        if name.is_empty() {
+            Err(LineError(start, "expected a metric name"))
+        } else {
+            Ok(name.to_string())
+        }
+    }
+
+    fn escaped(&mut self) -> Result<char, LineError> {
+        // pointing at the backslash that was just consumed
+        let start = self.offset - 1;
+        match self.next() {
+            Some('"') => Ok('"'),
+            Some('\'') => Ok('\''),
+            Some('\\') => Ok('\\'),
+            Some('n') => Ok('\n'),
+            Some('r') => Ok('\r'),
+            Some('t') => Ok('\t'),
+            Some('0') => Ok('\0'),
+            Some('u') if self.peek() == Some('{') => {
+                self.next();
+                let code = self.take_until(|c| c == '}');
+                self.expect('}', "unterminated unicode escape sequence")?;
+                u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or(LineError(start, "invalid unicode escape sequence"))
+            }
+            _ => Err(LineError(start, "invalid escape sequence")),
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, LineError> {
+        self.expect('"', "expected a tag value between double quotes")?;
+        let mut value = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(value),
+                Some('\\') => value.push(self.escaped()?),
+                Some(other) => value.push(other),
+                None => return Err(LineError(self.offset, "unterminated tag value")),
+            }
+        }
+    }
+
+    fn tags(&mut self) -> Result<IndexMap<String, String>, LineError> {
+        let mut tags = IndexMap::new();
+        if self.peek() != Some('{') {
+            return Ok(tags);
+        }
+        self.next();
+        loop {
+            self.skip_whitespaces();
+            if self.peek() == Some('}') && tags.is_empty() {
+                self.next();
+                return Ok(tags);
+            }
+            let start = self.offset;
+            let key = self.take_until(|c| matches!(c, '=' | ',' | '}')).trim();
+            if key.is_empty() {
+                return Err(LineError(start, "expected a tag name"));
+            }
+            self.expect('=', "expected '=' after the tag name")?;
+            self.skip_whitespaces();
+            let value = self.quoted()?;
+            tags.insert(key.to_string(), value);
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some('}') => {
+                    self.next();
+                    return Ok(tags);
+                }
+                _ => {
+                    return Err(LineError(
+                        self.offset,
+                        "expected ',' or '}' after the tag value",
+                    ))
+                }
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<f64, LineError> {
+        if self.skip_whitespaces() == 0 {
+            return Err(LineError(
+                self.offset,
+                "expected a whitespace before the metric value",
+            ));
+        }
+        let start = self.offset;
+        let value = self.take_until(char::is_whitespace);
+        if value.is_empty() {
+            return Err(LineError(start, "expected a metric value"));
+        }
+        let value = value
+            .parse::<f64>()
+            .map_err(|_| LineError(start, "invalid metric value"))?;
+        self.skip_whitespaces();
+        if self.peek().is_some() {
+            return Err(LineError(
+                self.offset,
+                "unexpected content after the metric value",
+            ));
+        }
+        Ok(value)
+    }
+
+    fn parse(mut self) -> Result<Metric, LineError> {
+        self.skip_whitespaces();
+        let name = self.name()?;
+        let tags = self.tags()?;
+        let value = self.value()?;
+        Ok(Metric {
+            header: MetricHeader { name, tags },
+            value,
+        })
+    }
+}
+
+/// Parses the metrics written with the format used by the `show` command.
+///
+/// Empty lines and lines starting with `#` are ignored.
+pub(crate) fn parse(input: &str) -> Result<Vec<Metric>, ParseError> {
+    let mut result = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let metric = LineParser::new(line)
+            .parse()
+            .map_err(|LineError(offset, message)| {
+                let column = line[..offset].chars().count() + 1;
+                ParseError {
+                    line: index + 1,
+                    column,
+                    message,
+                    content: line.to_string(),
+                    caret: "^",
+                }
+            })?;
+        result.push(metric);
+    }
+    Ok(result)
+}
+
+#[derive(Debug)]
+pub(crate) enum TextSource {
+    Stdin,
+    File(PathBuf),
+}
+
+#[derive(Debug)]
+pub(crate) struct TextImporter {
+    pub source: TextSource,
+}
+
+impl TextImporter {
+    #[inline(always)]
+    pub(crate) fn new(source: TextSource) -> Self {
+        Self { source }
+    }
+
+    fn read(&self) -> std::io::Result<String> {
+        match self.source {
+            TextSource::Stdin => {
+                let mut buffer = String::new();
+                std::io::stdin().read_to_string(&mut buffer)?;
+                Ok(buffer)
+            }
+            TextSource::File(ref path) => std::fs::read_to_string(path),
+        }
+    }
+}
+
+impl super::Importer for TextImporter {
+    fn import(self) -> Result<Vec<Metric>, super::Error> {
+        let content = self.read()?;
+        parse(&content).map_err(|err| super::Error::InvalidFormat {
+            source: Box::new(err),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::metric::Metric;
+
+    #[test]
+    fn should_parse_show_output() {
+        let input = r#"first 1.00
+second{foo="bar"} 12.34
+
+# some comment
+    third{foo="bar", ab="c\"d"} -0.5
+fourth{} 1e3
+"#;
+        let metrics = super::parse(input).unwrap();
+        assert_eq!(
+            metrics,
+            vec![
+                Metric::new("first", 1.0),
+                Metric::new("second", 12.34).with_tag("foo", "bar"),
+                Metric::new("third", -0.5)
+                    .with_tag("foo", "bar")
+                    .with_tag("ab", "c\"d"),
+                Metric::new("fourth", 1000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_parse_escaped_values() {
+        let metrics = super::parse(r#"name{a="\\\n\t\u{e9}"} 1"#).unwrap();
+        assert_eq!(metrics[0].header.tags.get("a").unwrap(), "\\\n\té");
+    }
+
+    #[test]
+    fn should_roundtrip_with_show_format() {
+        use crate::formatter::metric::TextMetricHeader;
+
+        let metric = Metric::new("name", 12.34)
+            .with_tag("with space", "a \"quoted\" value")
+            .with_tag("other", "é\n");
+        let line = format!("{} {}", TextMetricHeader::new(&metric.header), metric.value);
+        assert_eq!(super::parse(&line).unwrap(), vec![metric]);
+    }
+
+    #[test_case::test_case("{foo=\"bar\"} 1", 1, 1, "expected a metric name"; "missing name")]
+    #[test_case::test_case("name{=\"bar\"} 1", 1, 6, "expected a tag name"; "missing tag name")]
+    #[test_case::test_case("name{foo} 1", 1, 9, "expected '=' after the tag name"; "missing equal")]
+    #[test_case::test_case("name{foo=bar} 1", 1, 10, "expected a tag value between double quotes"; "unquoted value")]
+    #[test_case::test_case("name{foo=\"bar} 1", 1, 17, "unterminated tag value"; "unterminated value")]
+    #[test_case::test_case("name{foo=\"b\\ar\"} 1", 1, 12, "invalid escape sequence"; "invalid escape")]
+    #[test_case::test_case("name{foo=\"bar\" 1", 1, 16, "expected ',' or '}' after the tag value"; "unclosed tags")]
+    #[test_case::test_case("name", 1, 5, "expected a whitespace before the metric value"; "missing value")]
+    #[test_case::test_case("name 12.34 MiB", 1, 12, "unexpected content after the metric value"; "value with unit")]
+    #[test_case::test_case("name abc", 1, 6, "invalid metric value"; "invalid value")]
+    #[test_case::test_case("first 1\n\nsecond{é=\"a\"} nope", 3, 15, "invalid metric value"; "on third line")]
+    fn should_fail_parsing(input: &str, line: usize, column: usize, message: &str) {
+        let err = super::parse(input).unwrap_err();
+        assert_eq!((err.line, err.column, err.message), (line, column, message));
+    }
+
+    #[test]
+    fn should_display_error_with_caret() {
+        let err = super::parse("name{foo=bar} 1").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 10: expected a tag value between double quotes\nname{foo=bar} 1\n         ^"
+        );
+    }
+}
@@ -3,6 +3,8 @@ pub(crate) mod tests;
 
 mod backend;
 mod cmd;
+#[cfg(feature = "collector")]
+mod collector;
 mod entity;
 mod error;
 #[cfg(feature = "exporter")]
@@ -19,6 +21,8 @@ use clap::Parser;
 enum ExitCode {
     Success,
     Failure,
+    /// Forwards the exit code of an executed command
+    Code(i32),
 }
 
 impl ExitCode {
@@ -31,6 +35,7 @@ impl ExitCode {
         std::process::exit(match self {
             Self::Success => 0,
             Self::Failure => 1,
+            Self::Code(code) => code,
         })
     }
 }
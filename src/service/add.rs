@@ -1,17 +1,133 @@
 use crate::backend::Backend;
-use crate::entity::metric::{Metric, MetricChange};
+use crate::entity::metric::{Metric, MetricChange, MetricStack};
+use crate::entity::selector::MetricSelector;
 
 #[derive(Debug)]
 pub(crate) struct Options {
+    pub remote: String,
     pub target: String,
+    /// Existing metrics matching any of those selectors are removed before adding the new ones
+    pub replace: Vec<MetricSelector>,
 }
 
 impl<B: Backend> super::Service<B> {
     pub(crate) fn add(&self, metric: Metric, opts: &Options) -> Result<(), super::Error> {
+        self.add_all(vec![metric], opts)
+    }
+
+    /// Adds all the metrics to the target, writing the note only once.
+    pub(crate) fn add_all(&self, metrics: Vec<Metric>, opts: &Options) -> Result<(), super::Error> {
         let mut changes = self.get_metric_changes(&opts.target)?;
-        changes.push(MetricChange::Add(metric));
+        if !opts.replace.is_empty() {
+            let matches = |metric: &Metric| {
+                opts.replace
+                    .iter()
+                    .any(|selector| selector.matches(&metric.header))
+            };
+            // the local additions that will be replaced are just forgotten
+            changes
+                .retain(|change| !matches!(change, MetricChange::Add(metric) if matches(metric)));
+            let existing = MetricStack::from_iter(
+                self.get_remote_metrics(&opts.target, &opts.remote)?
+                    .into_iter(),
+            )
+            .with_changes(changes.iter().cloned());
+            let removals = existing
+                .into_metric_iter()
+                .filter(|metric| matches(metric))
+                .map(MetricChange::Remove)
+                .collect::<Vec<_>>();
+            // avoid stacking the same removals when executing the command again
+            for removal in removals {
+                if !changes.contains(&removal) {
+                    changes.push(removal);
+                }
+            }
+        }
+        changes.extend(metrics.into_iter().map(MetricChange::Add));
         self.set_metric_changes(&opts.target, changes)?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Options;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::metric::Metric;
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    fn options(replace: Vec<MetricSelector>) -> Options {
+        Options {
+            remote: "origin".into(),
+            target: "HEAD".into(),
+            replace,
+        }
+    }
+
+    #[test]
+    fn should_add_all_metrics_at_once() {
+        let backend = MockBackend::default();
+        let svc = Service::new(backend.clone());
+        svc.add_all(
+            vec![Metric::new("first", 1.0), Metric::new("second", 2.0)],
+            &options(Vec::new()),
+        )
+        .unwrap();
+        let metrics = svc.get_metrics("HEAD", "origin").unwrap().into_vec();
+        assert_eq!(
+            metrics,
+            vec![Metric::new("first", 1.0), Metric::new("second", 2.0)]
+        );
+    }
+
+    #[test]
+    fn should_replace_existing_metrics() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = { os = "linux" }
+value = 1.0
+
+[[metrics]]
+name = "first"
+tags = { os = "macos" }
+value = 2.0
+
+[[metrics]]
+name = "second"
+value = 3.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let opts = options(vec![MetricSelector::new("first")]);
+        svc.add_all(
+            vec![Metric::new("first", 10.0).with_tag("os", "linux")],
+            &opts,
+        )
+        .unwrap();
+        let first_run = backend.get_note("HEAD", NoteRef::Changes);
+        // executing it a second time doesn't change anything
+        svc.add_all(
+            vec![Metric::new("first", 10.0).with_tag("os", "linux")],
+            &opts,
+        )
+        .unwrap();
+        assert_eq!(backend.get_note("HEAD", NoteRef::Changes), first_run);
+
+        let metrics = svc.get_metrics("HEAD", "origin").unwrap().into_vec();
+        assert_eq!(
+            metrics,
+            vec![
+                Metric::new("second", 3.0),
+                Metric::new("first", 10.0).with_tag("os", "linux"),
+            ]
+        );
+    }
+}
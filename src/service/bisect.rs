@@ -0,0 +1,171 @@
+use crate::backend::Backend;
+use crate::entity::metric::{Metric, MetricStack};
+use crate::entity::selector::MetricSelector;
+use crate::entity::step::StepList;
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub remote: &'a str,
+    /// Commit before the regression
+    pub good: &'a str,
+    /// Commit with the regression
+    pub bad: &'a str,
+    /// Number of changes to keep
+    pub limit: usize,
+    /// Command collecting the metrics of the commits without recorded values
+    #[cfg_attr(not(feature = "collector-command"), allow(dead_code))]
+    pub exec: Option<&'a [String]>,
+}
+
+impl<B: Backend> super::Service<B> {
+    /// Executes the command in a temporary worktree of the target
+    #[cfg(feature = "collector-command")]
+    fn collect_at(&self, target: &str, command: &[String]) -> Result<Vec<Metric>, super::Error> {
+        use crate::collector::Collector;
+
+        let Some((program, args)) = command.split_first() else {
+            return Ok(Vec::new());
+        };
+        let path = std::env::temp_dir().join(format!(
+            "git-metrics-bisect-{}-{}",
+            std::process::id(),
+            target
+        ));
+        self.backend.add_worktree(&path, target)?;
+        let result = crate::collector::command::CommandCollector::new(
+            program.clone(),
+            args.to_vec(),
+            path.clone(),
+        )
+        .collect();
+        self.backend.remove_worktree(&path)?;
+        Ok(result?)
+    }
+
+    /// Values of the selected metrics on the commit and if they were collected
+    fn measure(
+        &self,
+        selector: &MetricSelector,
+        target: &str,
+        opts: &Options,
+    ) -> Result<(Vec<Metric>, bool), super::Error> {
+        let recorded = selector.select(self.get_metrics(target, opts.remote)?);
+        #[cfg(feature = "collector-command")]
+        if let (true, Some(command)) = (recorded.is_empty(), opts.exec) {
+            let collected = MetricStack::from_iter(self.collect_at(target, command)?.into_iter());
+            return Ok((selector.select(collected), true));
+        }
+        Ok((recorded, false))
+    }
+
+    /// Walks the history between the good and the bad commits to find the largest changes
+    /// of the selected metrics
+    pub(crate) fn bisect(
+        &self,
+        selector: &MetricSelector,
+        opts: &Options,
+    ) -> Result<StepList, super::Error> {
+        let (initial, _) = self.measure(selector, opts.good, opts)?;
+        let mut result = StepList::new(initial.into_iter());
+        // the commits merged in the range are measured through their merge commit
+        let commits = self
+            .backend
+            .get_first_parent_commits(&format!("{}..{}", opts.good, opts.bad))?;
+        for commit in commits {
+            let (metrics, collected) = self.measure(selector, &commit.sha, opts)?;
+            result.push(commit, metrics, collected);
+        }
+        Ok(result.largest(opts.limit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::difference::Comparison;
+    use crate::entity::git::Commit;
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    #[test]
+    fn should_find_largest_steps() {
+        let backend = MockBackend::default();
+        backend.set_commits(["aaaaaaa", "aaaaaab", "aaaaaac"].map(|sha| Commit {
+            sha: sha.into(),
+            summary: format!("commit {sha}"),
+            timestamp: 0,
+            author: String::new(),
+            body: String::new(),
+        }));
+        for (sha, value) in [("aaaaaad", 100.0), ("aaaaaac", 102.0), ("aaaaaaa", 150.0)] {
+            backend.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"size\"\ntags = {{}}\nvalue = {value:?}\n\n[[metrics]]\nname = \"other\"\ntags = {{}}\nvalue = {value:?}\n"),
+            );
+        }
+        let result = Service::new(backend)
+            .bisect(
+                &MetricSelector::new("size"),
+                &super::Options {
+                    remote: "origin",
+                    good: "aaaaaad",
+                    bad: "aaaaaaa",
+                    limit: 5,
+                    exec: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(result.steps.len(), 2);
+        assert_eq!(result.steps[0].commit.sha, "aaaaaaa");
+        assert_eq!(
+            result.steps[0].comparison,
+            Comparison::matching(102.0, 150.0)
+        );
+        assert_eq!(result.steps[1].commit.sha, "aaaaaac");
+        assert_eq!(result.unmeasured.len(), 1);
+        assert_eq!(result.unmeasured[0].sha, "aaaaaab");
+    }
+
+    #[cfg(all(unix, feature = "collector-command"))]
+    #[test]
+    fn should_collect_unmeasured_commits() {
+        let command = ["sh".to_string(), "-c".into(), "echo 'size 140.0'".into()];
+        let backend = MockBackend::default();
+        backend.set_commits(["aaaaaaa", "aaaaaab", "aaaaaac"].map(|sha| Commit {
+            sha: sha.into(),
+            summary: format!("commit {sha}"),
+            timestamp: 0,
+            author: String::new(),
+            body: String::new(),
+        }));
+        for (sha, value) in [("aaaaaad", 100.0), ("aaaaaac", 102.0), ("aaaaaaa", 150.0)] {
+            backend.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"size\"\ntags = {{}}\nvalue = {value:?}\n\n[[metrics]]\nname = \"other\"\ntags = {{}}\nvalue = {value:?}\n"),
+            );
+        }
+        let result = Service::new(backend)
+            .bisect(
+                &MetricSelector::new("size"),
+                &super::Options {
+                    remote: "origin",
+                    good: "aaaaaad",
+                    bad: "aaaaaaa",
+                    limit: 1,
+                    exec: Some(&command),
+                },
+            )
+            .unwrap();
+        assert!(result.unmeasured.is_empty());
+        assert_eq!(result.steps.len(), 1);
+        assert_eq!(result.steps[0].commit.sha, "aaaaaab");
+        assert!(result.steps[0].collected);
+        assert_eq!(
+            result.steps[0].comparison,
+            Comparison::matching(102.0, 140.0)
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::backend::Backend;
+use crate::entity::selector::MetricSelector;
+use crate::entity::step::StepList;
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub remote: &'a str,
+    /// Commit range, like HEAD~10..HEAD
+    pub target: &'a str,
+    /// Orders the changes by magnitude, the largest first, instead of the latest first
+    pub sort: bool,
+    /// Number of changes to keep
+    pub limit: Option<usize>,
+}
+
+impl<B: Backend> super::Service<B> {
+    /// Lists the commits of the range changing the value of the selected metrics
+    ///
+    /// When the range has a beginning, like `v1.0.0..HEAD`, the first commit is compared
+    /// with the values of the beginning. The commit recording the first value of a metric
+    /// is listed as creating it.
+    pub(crate) fn blame(
+        &self,
+        selector: &MetricSelector,
+        opts: &Options,
+    ) -> Result<StepList, super::Error> {
+        let initial = match opts.target.split_once("..") {
+            Some((from, _)) if !from.is_empty() => {
+                selector.select(self.get_metrics(from, opts.remote)?)
+            }
+            _ => Vec::new(),
+        };
+        let mut result = StepList::new(initial.into_iter());
+        // the commits merged in the range are blamed through their merge commit
+        let commits = self.backend.get_first_parent_commits(opts.target)?;
+        for commit in commits {
+            let metrics = selector.select(self.get_metrics(&commit.sha, opts.remote)?);
+            result.push(commit, metrics, false);
+        }
+        let limit = opts.limit.unwrap_or(usize::MAX);
+        if opts.sort {
+            return Ok(result.largest(limit));
+        }
+        result.steps.reverse();
+        result.steps.truncate(limit);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::git::Commit;
+    use crate::entity::metric::MetricHeader;
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    #[test_case::test_case("aaaaaad..aaaaaaa", false, None, &["aaaaaaa", "aaaaaac"]; "latest first")]
+    #[test_case::test_case("aaaaaad..aaaaaaa", true, None, &["aaaaaac", "aaaaaaa"]; "sorted")]
+    #[test_case::test_case("aaaaaad..aaaaaaa", true, Some(1), &["aaaaaac"]; "sorted with limit")]
+    #[test_case::test_case("aaaaaaa", false, None, &["aaaaaaa", "aaaaaac"]; "without beginning")]
+    fn should_list_changing_commits(
+        target: &str,
+        sort: bool,
+        limit: Option<usize>,
+        expected: &[&str],
+    ) {
+        let selector =
+            MetricSelector::new("size").with_tags([("os".into(), "linux".into())].into());
+        let backend = MockBackend::default();
+        backend.set_commits(["aaaaaaa", "aaaaaab", "aaaaaac"].map(|sha| Commit {
+            sha: sha.into(),
+            summary: format!("commit {sha}"),
+            timestamp: 0,
+            author: "Alice".into(),
+            body: String::new(),
+        }));
+        for (sha, value) in [("aaaaaad", 100.0), ("aaaaaac", 120.0), ("aaaaaaa", 130.0)] {
+            backend.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"size\"\ntags = {{ os = \"linux\" }}\nvalue = {value:?}\n\n[[metrics]]\nname = \"size\"\ntags = {{ os = \"macos\" }}\nvalue = 10.0\n"),
+            );
+        }
+        let result = Service::new(backend)
+            .blame(
+                &selector,
+                &super::Options {
+                    remote: "origin",
+                    target,
+                    sort,
+                    limit,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            result
+                .steps
+                .iter()
+                .map(|step| step.commit.sha.as_str())
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert!(result
+            .steps
+            .iter()
+            .all(|step| step.header == MetricHeader::new("size").with_tag("os", "linux")));
+    }
+}
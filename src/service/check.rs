@@ -1,24 +1,69 @@
-use crate::backend::Backend;
+use crate::backend::{Backend, RevParse};
+use crate::entity::baseline::Baseline;
 use crate::entity::check::CheckList;
 use crate::entity::config::Config;
+use crate::entity::waiver::Waiver;
 
 #[derive(Debug)]
 pub(crate) struct Options<'a> {
     pub remote: &'a str,
     pub target: &'a str,
+    pub baseline: Option<&'a Baseline>,
+    /// Branch used to select the overrides, default to the branch of the target
+    pub branch: Option<&'a str>,
 }
 
 impl<B: Backend> super::Service<B> {
+    /// Waivers in the messages of the checked commits, from the baseline when chosen
+    fn waivers(&self, target: &str, baseline: Option<&str>) -> Result<Vec<Waiver>, super::Error> {
+        let commits = match (self.backend.rev_parse(target)?, baseline) {
+            (RevParse::Range(_, head) | RevParse::Single(head), Some(baseline)) => {
+                self.backend.get_commits(&format!("{baseline}..{head}"))?
+            }
+            (range @ RevParse::Range(..), None) => self.backend.get_commits(&range.to_string())?,
+            (RevParse::Single(head), None) => vec![self.backend.get_commit(&head)?],
+        };
+        Ok(commits
+            .iter()
+            .flat_map(|commit| Waiver::from_message(&commit.body))
+            .collect())
+    }
+
+    /// Branch of the target when it names one, the current branch otherwise
+    fn target_branch(&self, target: &str) -> Result<Option<String>, super::Error> {
+        let head = target
+            .rsplit("..")
+            .next()
+            .filter(|item| !item.is_empty() && *item != "HEAD");
+        match head {
+            Some(head) if self.backend.is_branch(head)? => Ok(Some(head.to_string())),
+            _ => Ok(self.backend.current_branch()?),
+        }
+    }
+
     pub(crate) fn check(&self, config: &Config, opts: &Options) -> Result<CheckList, super::Error> {
-        let diff = self
-            .diff(&super::diff::Options {
+        let branch = match opts.branch {
+            Some(branch) => Some(branch.to_string()),
+            None => self.target_branch(opts.target)?,
+        };
+        let baseline = opts.baseline.or(config.check.baseline.as_ref());
+        let (config, overrides) = config
+            .clone()
+            .with_overrides(branch.as_deref(), baseline.map(Baseline::reference));
+        let diff = self.diff(
+            &config,
+            &super::diff::Options {
                 remote: opts.remote,
                 target: opts.target,
-            })?
-            .remove_missing()
-            .into_inner();
+                baseline: opts.baseline,
+            },
+        )?;
+
+        let waivers = self.waivers(opts.target, diff.baseline.as_deref())?;
 
-        Ok(CheckList::evaluate(config, diff))
+        Ok(CheckList::evaluate(&config, diff)
+            .with_overrides(overrides)
+            .with_waivers(&config, &waivers))
     }
 }
 
@@ -27,9 +72,10 @@ mod tests {
     use super::*;
     use crate::backend::mock::MockBackend;
     use crate::backend::{NoteRef, RevParse};
-    use crate::entity::check::{MetricCheck, Status, SubsetCheck};
+    use crate::entity::check::{MetricCheck, RuleCheck, Status, SubsetCheck};
     use crate::entity::config::Rule;
     use crate::entity::difference::{Comparison, MetricDiff};
+    use crate::entity::git::Commit;
     use crate::entity::metric::MetricHeader;
     use crate::service::Service;
 
@@ -77,6 +123,8 @@ value = 80.0
                 &super::Options {
                     remote: "origin",
                     target: "main..HEAD",
+                    baseline: None,
+                    branch: None,
                 },
             )
             .unwrap();
@@ -150,6 +198,8 @@ value = 50.0
                 &super::Options {
                     remote: "origin",
                     target: "main..HEAD",
+                    baseline: None,
+                    branch: None,
                 },
             )
             .unwrap();
@@ -179,4 +229,577 @@ value = 50.0
                 )
         );
     }
+
+    #[test]
+    fn should_compare_with_aliases() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[metrics.artifact]
+aliases = [{ name = "binary-size", tags = { os = "platform.os" } }]
+
+[[metrics.artifact.rules]]
+type = "max-increase"
+ratio = 0.1
+"#,
+        );
+        backend.set_rev_parse(
+            "main..HEAD",
+            RevParse::Range("aaaaaab".into(), "aaaaaaa".into()),
+        );
+        backend.set_rev_list("aaaaaab", ["aaaaaac"]);
+        backend.set_rev_list("aaaaaab..aaaaaaa", ["aaaaaaa"]);
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "artifact"
+tags = { "platform.os" = "linux" }
+value = 120.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaac",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 100.0
+"#,
+        );
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "main..HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        similar_asserts::assert_eq!(
+            res,
+            CheckList::default().with_check(
+                MetricCheck::new(MetricDiff::new(
+                    MetricHeader::new("artifact").with_tag("platform.os", "linux"),
+                    Comparison::matching(100.0, 120.0)
+                ))
+                .with_check(Rule::max_relative_increase(0.1), Status::Failed)
+            )
+        );
+    }
+
+    #[test]
+    fn should_fail_with_missing_required_metrics() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[check]
+fail_on_missing = true
+
+[metrics.coverage]
+required = true
+
+[metrics.binary-size]
+[metrics.binary-size.subsets.macos]
+matching = { "platform.os" = "macos" }
+required = true
+
+[[patterns]]
+glob = "coverage.*"
+required = true
+
+[[patterns]]
+glob = "binary-*"
+required = true
+"#,
+        );
+        backend.set_rev_parse(
+            "main..HEAD",
+            RevParse::Range("aaaaaab".into(), "aaaaaaa".into()),
+        );
+        backend.set_rev_list("aaaaaab", ["aaaaaac"]);
+        backend.set_rev_list("aaaaaab..aaaaaaa", ["aaaaaaa"]);
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { "platform.os" = "linux" }
+value = 100.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaac",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { "platform.os" = "linux" }
+value = 100.0
+
+[[metrics]]
+name = "build-time"
+value = 10.0
+"#,
+        );
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "main..HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        similar_asserts::assert_eq!(
+            res,
+            CheckList::default()
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("coverage"),
+                        Comparison::Absent
+                    ))
+                    .with_check(Rule::Required, Status::Failed)
+                )
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("binary-size"),
+                        Comparison::Absent
+                    ))
+                    .with_subset(
+                        "macos",
+                        SubsetCheck::default()
+                            .with_matching("platform.os", "macos")
+                            .with_check(Rule::Required, Status::Failed)
+                    )
+                )
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("coverage.*"),
+                        Comparison::Absent
+                    ))
+                    .with_check(Rule::Required, Status::Failed)
+                )
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("binary-size").with_tag("platform.os", "linux"),
+                        Comparison::matching(100.0, 100.0)
+                    ))
+                    .with_subset(
+                        "macos",
+                        SubsetCheck::default().with_matching("platform.os", "macos")
+                    )
+                )
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("build-time"),
+                        Comparison::new(10.0, None)
+                    ))
+                    .with_check(Rule::Required, Status::Failed)
+                )
+        );
+    }
+
+    #[test_case::test_case(Some("release/1.0"), "HEAD", RevParse::Single("aaaaaaa".into()); "of current branch")]
+    #[test_case::test_case(None, "release/1.0", RevParse::Single("aaaaaaa".into()); "of target branch")]
+    #[test_case::test_case(Some("main"), "release/1.0", RevParse::Single("aaaaaaa".into()); "of target branch over current")]
+    fn should_apply_overrides(current: Option<&str>, target: &str, rev_parse: RevParse) {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0
+
+[[overrides]]
+branch = "release/*"
+
+[overrides.metrics.first]
+replace = true
+rules = [{ type = "max", value = 50.0 }]
+"#,
+        );
+        if let Some(current) = current {
+            backend.set_current_branch(current);
+        }
+        backend.set_branch("main");
+        backend.set_branch("release/1.0");
+        backend.set_rev_parse(target, rev_parse);
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 80.0
+"#,
+        );
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target,
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        similar_asserts::assert_eq!(
+            res,
+            CheckList::default()
+                .with_overrides(vec!["branch release/*".into()])
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("first"),
+                        Comparison::created(80.0)
+                    ))
+                    .with_check(Rule::max(50.0), Status::Failed)
+                )
+        );
+    }
+
+    #[test]
+    fn should_waive_failed_rules_with_trailers() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[[metrics.first.rules]]
+type = "max-increase"
+ratio = 0.1
+
+[[metrics.second.rules]]
+type = "max-increase"
+ratio = 0.1
+"#,
+        );
+        backend.set_rev_parse(
+            "main..HEAD",
+            RevParse::Range("aaaaaab".into(), "aaaaaaa".into()),
+        );
+        backend.set_rev_list("aaaaaab", ["aaaaaab"]);
+        backend.set_rev_list("aaaaaab..aaaaaaa", ["aaaaaaa"]);
+        backend.set_commits([Commit {
+            sha: "aaaaaaa".into(),
+            summary: "Add a dependency".into(),
+            timestamp: 0,
+            author: String::new(),
+            body: "It's worth it.\n\nMetrics-Allow: first +50%\nMetrics-Allow: second +10%".into(),
+        }]);
+        backend.set_note(
+            "aaaaaab",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 100.0
+
+[[metrics]]
+name = "second"
+tags = {}
+value = 100.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 140.0
+
+[[metrics]]
+name = "second"
+tags = {}
+value = 140.0
+"#,
+        );
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "main..HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.status.waived, 1);
+        assert_eq!(res.status.failed, 1);
+        similar_asserts::assert_eq!(
+            res,
+            CheckList::default()
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("first"),
+                        Comparison::matching(100.0, 140.0)
+                    ))
+                    .with_check(Rule::max_relative_increase(0.1), Status::Waived)
+                )
+                .with_check(
+                    MetricCheck::new(MetricDiff::new(
+                        MetricHeader::new("second"),
+                        Comparison::matching(100.0, 140.0)
+                    ))
+                    .with_check(Rule::max_relative_increase(0.1), Status::Failed)
+                )
+        );
+    }
+
+    #[test_case::test_case("2", Status::Failed; "with commits")]
+    #[test_case::test_case("\"1d\"", Status::Failed; "with days")]
+    #[test_case::test_case("10", Status::Skip; "without enough history")]
+    fn should_check_trend_over_window(window: &str, expected: Status) {
+        let backend = MockBackend::default();
+        backend.set_config(&format!(
+            r#"[[metrics.first.rules]]
+type = "max-increase"
+ratio = 0.1
+
+[[metrics.first.rules]]
+type = "max-increase-over"
+window = {window}
+ratio = 0.1
+"#
+        ));
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("aaaaaaa~1", ["aaaaaab", "aaaaaac", "aaaaaad"]);
+        backend.set_rev_list("aaaaaab", ["aaaaaab", "aaaaaac", "aaaaaad"]);
+        backend.set_rev_list("aaaaaad", ["aaaaaad"]);
+        backend.set_commits(
+            [
+                ("aaaaaab", 200_000),
+                ("aaaaaac", 150_000),
+                ("aaaaaad", 100_000),
+            ]
+            .map(|(sha, timestamp)| Commit {
+                sha: sha.into(),
+                summary: String::new(),
+                timestamp,
+                author: String::new(),
+                body: String::new(),
+            }),
+        );
+        for (sha, value) in [("aaaaaaa", 130.0), ("aaaaaab", 125.0), ("aaaaaad", 100.0)] {
+            backend.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!("[[metrics]]\nname = \"first\"\ntags = {{}}\nvalue = {value:?}\n"),
+            );
+        }
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        similar_asserts::assert_eq!(
+            res,
+            CheckList::default().with_check(
+                MetricCheck::new(MetricDiff::new(
+                    MetricHeader::new("first"),
+                    Comparison::matching(125.0, 130.0)
+                ))
+                .with_check(Rule::max_relative_increase(0.1), Status::Success)
+                .with_check(config.metrics["first"].rules[1], expected)
+            )
+        );
+    }
+
+    #[test]
+    fn should_check_aggregated_subsets() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[metrics."bundle.size".subsets.total]
+matching = { chunk = { exists = true } }
+aggregate = "sum"
+rules = [{ type = "max", value = 250.0 }]
+
+[metrics."bundle.size".subsets.largest]
+matching = { chunk = { exists = true } }
+aggregate = "max"
+rules = [{ type = "max", value = 150.0 }]
+
+[metrics."bundle.size".subsets.main]
+matching = { chunk = "main" }
+rules = [{ type = "max", value = 150.0 }]
+"#,
+        );
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("aaaaaaa~1", ["aaaaaab"]);
+        backend.set_note(
+            "aaaaaab",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "bundle.size"
+tags = { chunk = "main" }
+value = 100.0
+
+[[metrics]]
+name = "bundle.size"
+tags = { chunk = "vendor" }
+value = 100.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "bundle.size"
+tags = { chunk = "main" }
+value = 120.0
+
+[[metrics]]
+name = "bundle.size"
+tags = { chunk = "vendor" }
+value = 140.0
+"#,
+        );
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.list.len(), 4);
+        assert_eq!(res.status.success, 2);
+        assert_eq!(res.status.failed, 1);
+        let total = &res.list[2];
+        assert_eq!(
+            total.aggregate.as_ref().map(ToString::to_string).as_deref(),
+            Some("sum of subset \"total\"")
+        );
+        assert_eq!(total.diff.comparison, Comparison::matching(200.0, 260.0));
+        assert!(total.status.is_failed());
+        let largest = &res.list[3];
+        assert_eq!(largest.diff.comparison, Comparison::matching(100.0, 140.0));
+        assert!(!largest.status.is_failed());
+        assert!(res.list[0].aggregate.is_none());
+        assert_eq!(res.list[0].subsets.keys().collect::<Vec<_>>(), vec!["main"]);
+    }
+
+    #[test]
+    fn should_check_trend_of_aggregated_subsets() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[metrics."bundle.size".subsets.total]
+matching = { chunk = { exists = true } }
+aggregate = "sum"
+rules = [{ type = "max-increase-over", window = 2, ratio = 0.1 }]
+"#,
+        );
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("aaaaaaa~1", ["aaaaaab", "aaaaaac", "aaaaaad"]);
+        backend.set_rev_list("aaaaaab", ["aaaaaab", "aaaaaac", "aaaaaad"]);
+        backend.set_rev_list("aaaaaad", ["aaaaaad"]);
+        backend.set_commits(
+            [
+                ("aaaaaab", 200_000),
+                ("aaaaaac", 150_000),
+                ("aaaaaad", 100_000),
+            ]
+            .map(|(sha, timestamp)| Commit {
+                sha: sha.into(),
+                summary: String::new(),
+                timestamp,
+                author: String::new(),
+                body: String::new(),
+            }),
+        );
+        for (sha, main, vendor) in [
+            ("aaaaaaa", 70.0, 60.0),
+            ("aaaaaab", 60.0, 65.0),
+            ("aaaaaad", 50.0, 50.0),
+        ] {
+            backend.set_note(
+                sha,
+                NoteRef::remote_metrics("origin"),
+                format!(
+                    r#"[[metrics]]
+name = "bundle.size"
+tags = {{ chunk = "main" }}
+value = {main:?}
+
+[[metrics]]
+name = "bundle.size"
+tags = {{ chunk = "vendor" }}
+value = {vendor:?}
+"#
+                ),
+            );
+        }
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        let total = res.list.last().unwrap();
+        assert_eq!(total.diff.comparison, Comparison::matching(125.0, 130.0));
+        // the sum went from 100 to 130 over the window
+        assert_eq!(total.checks.len(), 1);
+        assert_eq!(total.checks[0].status, Status::Failed);
+    }
+
+    #[test_case::test_case(""; "without any value")]
+    #[test_case::test_case("[[metrics]]\nname = \"bundle.size\"\ntags = {}\nvalue = 100.0\n"; "without matching value")]
+    fn should_fail_required_aggregated_subsets(note: &str) {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[metrics."bundle.size".subsets.total]
+matching = { chunk = { exists = true } }
+aggregate = "sum"
+required = true
+rules = [{ type = "max", value = 250.0 }]
+"#,
+        );
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("aaaaaaa~1", ["aaaaaab"]);
+        backend.set_note("aaaaaaa", NoteRef::remote_metrics("origin"), note);
+        let config = backend.get_config();
+        let res = Service::new(backend)
+            .check(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                    branch: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.status.failed, 1);
+        let total = res.list.last().unwrap();
+        assert!(total.aggregate.is_some());
+        assert_eq!(total.diff.comparison, Comparison::Absent);
+        assert_eq!(
+            total.checks,
+            vec![RuleCheck {
+                rule: Rule::Required,
+                status: Status::Failed,
+            }]
+        );
+    }
 }
@@ -1,45 +1,124 @@
 use crate::backend::{Backend, RevParse};
-use crate::entity::difference::MetricDiffList;
+use crate::entity::baseline::Baseline;
+use crate::entity::config::{Config, Window};
+use crate::entity::difference::{History, MetricDiffList};
 use crate::entity::metric::MetricStack;
 
 #[derive(Debug)]
 pub(crate) struct Options<'a> {
     pub remote: &'a str,
     pub target: &'a str,
+    /// Commit to compare with, when not provided the one from the configuration is used
+    /// and then the commit before the target
+    pub baseline: Option<&'a Baseline>,
 }
 
 impl<B: Backend> super::Service<B> {
-    pub(super) fn stack_metrics(
+    /// Stacks the metrics of the commits, the derived metrics are computed on the final stack
+    fn stack_commits(
         &self,
         remote_name: &str,
-        range: &str,
+        mut commits: Vec<String>,
     ) -> Result<MetricStack, super::Error> {
         let mut stack = MetricStack::default();
-        let mut commits = self.backend.rev_list(range)?;
         commits.reverse();
         for commit_sha in commits {
-            let metrics = self.get_metrics(commit_sha.as_str(), remote_name)?;
+            let metrics = self.get_stored_metrics(commit_sha.as_str(), remote_name)?;
             stack.extend(metrics);
         }
-        Ok(stack)
+        Ok(self.open_config()?.with_derived(stack))
     }
 
-    pub(crate) fn diff(&self, opts: &Options<'_>) -> Result<MetricDiffList, super::Error> {
+    fn stack_metrics(&self, remote_name: &str, range: &str) -> Result<MetricStack, super::Error> {
+        let commits = self.backend.rev_list(range)?;
+        self.stack_commits(remote_name, commits)
+    }
+
+    /// Finds the revision to compare the head of the target with
+    fn resolve_baseline(
+        &self,
+        remote_name: &str,
+        baseline: &Baseline,
+        head: &str,
+    ) -> Result<String, super::Error> {
+        match baseline {
+            Baseline::MergeBase(reference) => Ok(self.backend.merge_base(reference, head)?),
+            Baseline::Ref(reference) => Ok(reference.clone()),
+            Baseline::LatestWithMetrics(reference) => {
+                for commit_sha in self.backend.rev_list(reference)? {
+                    if !self
+                        .get_stored_metrics(&commit_sha, remote_name)?
+                        .is_empty()
+                    {
+                        return Ok(commit_sha);
+                    }
+                }
+                Err(super::Error::BaselineNotFound(baseline.to_string()))
+            }
+        }
+    }
+
+    /// Stacks the metrics at the beginning of the windows of the trend rules,
+    /// walking the first parents of the baseline
+    fn history(
+        &self,
+        config: &Config,
+        remote_name: &str,
+        baseline: Option<&str>,
+    ) -> Result<History, super::Error> {
+        let mut history = History::default();
+        let Some(baseline) = baseline else {
+            return Ok(history);
+        };
+        let mut timestamp = None;
+        for window in config.windows() {
+            let start = match window {
+                Window::Commits(count) => self.backend.find_first_parent(baseline, count, None)?,
+                Window::Days(days) => {
+                    if timestamp.is_none() {
+                        timestamp = Some(self.backend.get_commit(baseline)?.timestamp);
+                    }
+                    let limit = timestamp.unwrap_or_default() - (days as i64) * 86400;
+                    self.backend.find_first_parent(baseline, 0, Some(limit))?
+                }
+            };
+            if let Some(start) = start {
+                let stack = self.stack_metrics(remote_name, &start)?;
+                history = history.with_window(config, window, stack);
+            }
+        }
+        Ok(history)
+    }
+
+    pub(crate) fn diff(
+        &self,
+        config: &Config,
+        opts: &Options<'_>,
+    ) -> Result<MetricDiffList, super::Error> {
         let rev_parse = self.backend.rev_parse(opts.target)?;
-        let (before, after) = match rev_parse {
-            RevParse::Range(ref first, _) => {
-                let before = self.stack_metrics(opts.remote, first.as_str())?;
+        let (previous, head, after) = match rev_parse {
+            RevParse::Range(ref first, ref second) => {
                 let after = self.stack_metrics(opts.remote, &rev_parse.to_string())?;
-                (before, after)
+                (first.clone(), second.as_str(), after)
             }
-            RevParse::Single(single) => {
-                let before = self.stack_metrics(opts.remote, &format!("{single}~1"))?;
+            RevParse::Single(ref single) => {
                 let after = self.get_metrics(single.as_str(), opts.remote)?;
-                (before, after)
+                (format!("{single}~1"), single.as_str(), after)
             }
         };
+        let (previous, chosen) = match opts.baseline.or(config.check.baseline.as_ref()) {
+            Some(baseline) => (self.resolve_baseline(opts.remote, baseline, head)?, true),
+            None => (previous, false),
+        };
+        let commits = self.backend.rev_list(&previous)?;
+        // only reported when chosen, otherwise it's obviously the commit before the target
+        let baseline = commits.first().filter(|_| chosen).cloned();
+        let history = self.history(config, opts.remote, commits.first().map(String::as_str))?;
+        let before = self.stack_commits(opts.remote, commits)?;
 
-        Ok(MetricDiffList::new(before, after))
+        Ok(MetricDiffList::new(config, before, after)
+            .with_baseline(baseline)
+            .with_history(history))
     }
 }
 
@@ -47,6 +126,7 @@ impl<B: Backend> super::Service<B> {
 mod tests {
     use crate::backend::mock::MockBackend;
     use crate::backend::{NoteRef, RevParse};
+    use crate::entity::baseline::Baseline;
     use crate::entity::difference::{Comparison, Delta};
     use crate::service::Service;
 
@@ -79,15 +159,19 @@ value = 1.0
 "#,
         );
         let list = Service::new(backend)
-            .diff(&super::Options {
-                remote: "origin",
-                target: "HEAD",
-            })
+            .diff(
+                &Default::default(),
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                },
+            )
             .unwrap();
-        assert_eq!(list.0.len(), 2);
-        assert_eq!(list.0[0].header.name, "first");
+        assert_eq!(list.list.len(), 2);
+        assert_eq!(list.list[0].header.name, "first");
         assert_eq!(
-            list.0[0].comparison,
+            list.list[0].comparison,
             Comparison::Matching {
                 previous: 1.0,
                 current: 2.0,
@@ -97,12 +181,63 @@ value = 1.0
                 },
             }
         );
-        assert_eq!(list.0[1].header.name, "second");
-        assert_eq!(list.0[1].comparison, Comparison::Missing { previous: 1.0 });
+        assert_eq!(list.list[1].header.name, "second");
+        assert_eq!(
+            list.list[1].comparison,
+            Comparison::Missing { previous: 1.0 }
+        );
         let list = list.remove_missing();
         assert_eq!(list.inner().len(), 1);
     }
 
+    #[test]
+    fn should_derive_metrics_from_stacked_commits() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[derived."coverage.ratio"]
+expression = "coverage.hit / coverage.count"
+"#,
+        );
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("aaaaaaa~1", ["aaaaaab", "aaaaaac"]);
+        for (sha, metrics) in [
+            (
+                "aaaaaaa",
+                &[("coverage.hit", 40.0), ("coverage.count", 40.0)][..],
+            ),
+            ("aaaaaab", &[("coverage.hit", 35.0)]),
+            (
+                "aaaaaac",
+                &[("coverage.hit", 30.0), ("coverage.count", 40.0)],
+            ),
+        ] {
+            let note = metrics
+                .iter()
+                .map(|(name, value)| {
+                    format!("[[metrics]]\nname = {name:?}\ntags = {{}}\nvalue = {value:?}\n")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            backend.set_note(sha, NoteRef::remote_metrics("origin"), note);
+        }
+        let list = Service::new(backend)
+            .diff(
+                &Default::default(),
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                },
+            )
+            .unwrap();
+        let ratio = list
+            .list
+            .iter()
+            .find(|item| item.header.name == "coverage.ratio")
+            .unwrap();
+        assert_eq!(ratio.comparison, Comparison::matching(0.875, 1.0));
+    }
+
     #[test]
     fn should_render_diff_with_range_target() {
         let backend = MockBackend::default();
@@ -155,15 +290,19 @@ value = 0.1
 "#,
         );
         let list = Service::new(backend)
-            .diff(&super::Options {
-                remote: "origin",
-                target: "HEAD~3..HEAD",
-            })
+            .diff(
+                &Default::default(),
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD~3..HEAD",
+                    baseline: None,
+                },
+            )
             .unwrap();
-        assert_eq!(list.0.len(), 3);
-        assert_eq!(list.0[0].header.name, "first");
+        assert_eq!(list.list.len(), 3);
+        assert_eq!(list.list[0].header.name, "first");
         assert_eq!(
-            list.0[0].comparison,
+            list.list[0].comparison,
             Comparison::Matching {
                 previous: 0.5,
                 current: 2.0,
@@ -173,9 +312,9 @@ value = 0.1
                 },
             }
         );
-        assert_eq!(list.0[1].header.name, "second");
+        assert_eq!(list.list[1].header.name, "second");
         assert_eq!(
-            list.0[1].comparison,
+            list.list[1].comparison,
             Comparison::Matching {
                 previous: 1.0,
                 current: 1.0,
@@ -185,12 +324,127 @@ value = 0.1
                 },
             }
         );
-        assert_eq!(list.0[2].header.name, "third");
+        assert_eq!(list.list[2].header.name, "third");
         assert_eq!(
-            list.0[2].comparison,
+            list.list[2].comparison,
             Comparison::Missing { previous: 0.1 },
             "{:?}",
-            list.0[2].comparison
+            list.list[2].comparison
+        );
+    }
+
+    #[test]
+    fn should_render_diff_with_merge_base_baseline() {
+        let backend = MockBackend::default();
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_merge_base("origin/main", "aaaaaaa", "aaaaaac");
+        backend.set_rev_list("aaaaaac", ["aaaaaac", "aaaaaad"]);
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 2.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaab",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 1.5
+"#,
+        );
+        backend.set_note(
+            "aaaaaad",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 1.0
+"#,
+        );
+        let baseline = Baseline::MergeBase("origin/main".into());
+        let list = Service::new(backend)
+            .diff(
+                &Default::default(),
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: Some(&baseline),
+                },
+            )
+            .unwrap();
+        assert_eq!(list.baseline.as_deref(), Some("aaaaaac"));
+        assert_eq!(list.list.len(), 1);
+        assert_eq!(list.list[0].comparison, Comparison::matching(1.0, 2.0));
+    }
+
+    #[test]
+    fn should_render_diff_with_latest_baseline_from_config() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[check]
+baseline = "latest-with-metrics:main"
+"#,
+        );
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("main", ["aaaaaab", "aaaaaac", "aaaaaad"]);
+        backend.set_rev_list("aaaaaac", ["aaaaaac", "aaaaaad"]);
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 2.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaac",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 1.0
+"#,
+        );
+        let config = backend.get_config();
+        let list = Service::new(backend)
+            .diff(
+                &config,
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(list.baseline.as_deref(), Some("aaaaaac"));
+        assert_eq!(list.list[0].comparison, Comparison::matching(1.0, 2.0));
+    }
+
+    #[test]
+    fn should_fail_without_commit_with_metrics() {
+        let backend = MockBackend::default();
+        backend.set_rev_parse("HEAD", RevParse::Single("aaaaaaa".into()));
+        backend.set_rev_list("main", ["aaaaaab", "aaaaaac"]);
+        let baseline = Baseline::LatestWithMetrics("main".into());
+        let err = Service::new(backend)
+            .diff(
+                &Default::default(),
+                &super::Options {
+                    remote: "origin",
+                    target: "HEAD",
+                    baseline: Some(&baseline),
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unable to find a baseline for latest-with-metrics:main"
         );
     }
 }
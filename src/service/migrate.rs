@@ -0,0 +1,327 @@
+use indexmap::IndexSet;
+
+use super::MetricList;
+use crate::backend::{Backend, NoteRef};
+use crate::entity::metric::{Metric, MetricChange, MetricStack};
+use crate::entity::migration::Migration;
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub remote: &'a str,
+    /// Only migrate the commits in the range, every commit with metrics otherwise
+    pub range: Option<&'a str>,
+    /// Only returns the commits that would be migrated
+    pub dry_run: bool,
+}
+
+/// Metrics changed by a migration on a given commit
+#[derive(Debug, PartialEq)]
+pub(crate) struct MigratedCommit {
+    pub commit_sha: String,
+    /// Previous and new version of every affected metric
+    pub changes: Vec<(Metric, Metric)>,
+}
+
+/// Applies the migration to the metric, keeping the previous and the new version when affected
+fn migrate_metric(
+    migration: &Migration,
+    metric: Metric,
+    changes: &mut Vec<(Metric, Metric)>,
+) -> Metric {
+    match migration.apply(&metric) {
+        Some(next) => {
+            changes.push((metric, next.clone()));
+            next
+        }
+        None => metric,
+    }
+}
+
+impl<B: Backend> super::Service<B> {
+    /// Rewrites the remote metrics, creating a single commit in the notes reference.
+    ///
+    /// The pending changes are migrated as well, as they are applied on top of the remote
+    /// metrics when pushing.
+    pub(crate) fn migrate(
+        &self,
+        migration: &Migration,
+        opts: &Options,
+    ) -> Result<Vec<MigratedCommit>, super::Error> {
+        let note_ref = NoteRef::remote_metrics(opts.remote);
+        let commits = match opts.range {
+            Some(range) => self.backend.rev_list(range)?,
+            None => self
+                .backend
+                .list_notes(&note_ref)?
+                .into_iter()
+                .chain(self.backend.list_notes(&NoteRef::Changes)?)
+                .map(|note| note.commit_id)
+                .collect::<IndexSet<_>>()
+                .into_iter()
+                .collect(),
+        };
+
+        let mut report = Vec::new();
+        let mut notes = Vec::new();
+        let mut pending_notes = Vec::new();
+        for commit_sha in commits {
+            let metrics = self.get_remote_metrics(&commit_sha, opts.remote)?;
+            let mut changes = Vec::new();
+            let migrated = metrics
+                .into_iter()
+                .map(|metric| migrate_metric(migration, metric, &mut changes))
+                .collect::<Vec<_>>();
+            if !changes.is_empty() {
+                // a renamed metric can override an existing one
+                let migrated = MetricStack::from_iter(migrated.into_iter()).into_vec();
+                notes.push((commit_sha.clone(), MetricList::from(migrated)));
+            }
+
+            let remote_count = changes.len();
+            let pending = self
+                .get_metric_changes(&commit_sha)?
+                .into_iter()
+                .map(|change| match change {
+                    MetricChange::Add(metric) => {
+                        MetricChange::Add(migrate_metric(migration, metric, &mut changes))
+                    }
+                    MetricChange::Remove(metric) => {
+                        MetricChange::Remove(migrate_metric(migration, metric, &mut changes))
+                    }
+                })
+                .collect::<Vec<_>>();
+            if changes.len() > remote_count {
+                pending_notes.push((commit_sha.clone(), pending));
+            }
+
+            if changes.is_empty() {
+                continue;
+            }
+            report.push(MigratedCommit {
+                commit_sha,
+                changes,
+            });
+        }
+
+        if !opts.dry_run && !notes.is_empty() {
+            self.backend
+                .write_notes(&note_ref, &notes, "migrate metrics")?;
+        }
+        if !opts.dry_run {
+            for (commit_sha, pending) in pending_notes {
+                self.set_metric_changes(&commit_sha, pending)?;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Options;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::metric::{Metric, MetricChange};
+    use crate::entity::migration::Migration;
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    fn migration() -> Migration {
+        Migration {
+            selector: MetricSelector::new("binary-size"),
+            rename: Some("artifact.size".into()),
+            scale: Some(0.5),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_migrate_every_commit() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+value = 1024.0
+
+[[metrics]]
+name = "coverage"
+value = 0.8
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "coverage"
+value = 0.9
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let report = svc
+            .migrate(
+                &migration(),
+                &Options {
+                    remote: "origin",
+                    range: None,
+                    dry_run: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].commit_sha, "aaaaaaa");
+        assert_eq!(
+            svc.get_remote_metrics("aaaaaaa", "origin").unwrap(),
+            vec![
+                Metric::new("artifact.size", 512.0),
+                Metric::new("coverage", 0.8)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_write_on_dry_run() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+value = 1024.0
+
+[[metrics]]
+name = "coverage"
+value = 0.8
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "coverage"
+value = 0.9
+"#,
+        );
+        let before = backend.get_note("aaaaaaa", NoteRef::remote_metrics("origin"));
+        let svc = Service::new(backend.clone());
+        let report = svc
+            .migrate(
+                &migration(),
+                &Options {
+                    remote: "origin",
+                    range: None,
+                    dry_run: true,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            report[0].changes,
+            vec![(
+                Metric::new("binary-size", 1024.0),
+                Metric::new("artifact.size", 512.0)
+            )]
+        );
+        assert_eq!(
+            backend.get_note("aaaaaaa", NoteRef::remote_metrics("origin")),
+            before
+        );
+    }
+
+    #[test]
+    fn should_only_migrate_the_range() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+value = 1024.0
+
+[[metrics]]
+name = "coverage"
+value = 0.8
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "coverage"
+value = 0.9
+"#,
+        );
+        backend.set_rev_list("HEAD~1..HEAD", ["bbbbbbb"]);
+        let svc = Service::new(backend.clone());
+        let report = svc
+            .migrate(
+                &migration(),
+                &Options {
+                    remote: "origin",
+                    range: Some("HEAD~1..HEAD"),
+                    dry_run: false,
+                },
+            )
+            .unwrap();
+        assert!(report.is_empty());
+    }
+
+    #[test]
+    fn should_migrate_pending_changes() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+value = 1024.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "binary-size"
+value = 2048.0
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "remove"
+name = "binary-size"
+value = 1024.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let report = svc
+            .migrate(
+                &migration(),
+                &Options {
+                    remote: "origin",
+                    range: None,
+                    dry_run: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            report
+                .iter()
+                .map(|item| (item.commit_sha.as_str(), item.changes.len()))
+                .collect::<Vec<_>>(),
+            vec![("aaaaaaa", 2), ("bbbbbbb", 1)]
+        );
+        // the pending changes are applied on the migrated metrics
+        assert_eq!(
+            svc.get_metrics("aaaaaaa", "origin").unwrap().into_vec(),
+            vec![Metric::new("artifact.size", 1024.0)]
+        );
+        assert_eq!(
+            svc.get_metric_changes("bbbbbbb").unwrap(),
+            vec![MetricChange::Remove(Metric::new("artifact.size", 512.0))]
+        );
+    }
+}
@@ -1,15 +1,22 @@
 use crate::backend::{Backend, NoteRef};
-use crate::entity::config::Config;
+use crate::entity::config::{Config, NESTED_PATHSPEC};
 use crate::entity::metric::{Metric, MetricChange, MetricStack};
 
 pub(crate) mod add;
+pub(crate) mod bisect;
+pub(crate) mod blame;
 pub(crate) mod check;
 pub(crate) mod diff;
 pub(crate) mod log;
+pub(crate) mod migrate;
 pub(crate) mod pull;
 pub(crate) mod push;
+pub(crate) mod ratchet;
 pub(crate) mod remove;
+pub(crate) mod reset;
 pub(crate) mod show;
+pub(crate) mod status;
+pub(crate) mod validate;
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
@@ -17,6 +24,13 @@ pub(crate) enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Backend(crate::backend::Error),
+    #[error("unable to find a baseline for {0}")]
+    BaselineNotFound(String),
+    #[error("invalid configuration")]
+    InvalidConfig(Vec<crate::entity::validation::Diagnostic>),
+    #[cfg(feature = "collector")]
+    #[error(transparent)]
+    Collector(#[from] crate::collector::Error),
     #[cfg(feature = "importer")]
     #[error(transparent)]
     Importer(#[from] crate::importer::Error),
@@ -36,14 +50,37 @@ impl crate::error::DetailedError for Error {
         match self {
             Self::Io(inner) => Some(inner.to_string()),
             Self::Backend(inner) => inner.details(),
+            Self::BaselineNotFound(_) => None,
+            Self::InvalidConfig(diagnostics) => Some(
+                diagnostics
+                    .iter()
+                    .map(|item| format!("{}: {item}", item.severity.label()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            #[cfg(feature = "collector")]
+            Self::Collector(inner) => inner.details(),
             #[cfg(feature = "importer")]
-            Self::Importer(inner) => Some(inner.to_string()),
+            Self::Importer(inner) => inner.details(),
             #[cfg(feature = "exporter")]
             Self::Exporter(inner) => Some(inner.to_string()),
         }
     }
 }
 
+impl Error {
+    pub(crate) fn exit_code(&self) -> crate::ExitCode {
+        match self {
+            #[cfg(feature = "collector")]
+            Self::Collector(inner) => inner
+                .exit_code()
+                .map(crate::ExitCode::Code)
+                .unwrap_or(crate::ExitCode::Failure),
+            _ => crate::ExitCode::Failure,
+        }
+    }
+}
+
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 struct MetricList {
     #[serde(default)]
@@ -64,16 +101,34 @@ struct ChangeList {
 
 pub(crate) struct Service<B> {
     backend: B,
+    config: std::cell::OnceCell<Config>,
 }
 
 impl<B: Backend> Service<B> {
     pub(crate) fn new(backend: B) -> Self {
-        Self { backend }
+        Self {
+            backend,
+            config: Default::default(),
+        }
     }
 
     pub(crate) fn open_config(&self) -> Result<Config, Error> {
+        if let Some(config) = self.config.get() {
+            return Ok(config.clone());
+        }
+        let config = self.load_config()?;
+        Ok(self.config.get_or_init(|| config).clone())
+    }
+
+    /// Loads the configuration with the included files and the tracked nested files
+    fn load_config(&self) -> Result<Config, Error> {
         let root = self.backend.root_path()?;
-        Config::from_root_path(&root).map_err(Error::from)
+        let config = Config::from_root_path(&root).map_err(Error::from)?;
+        if !config.discover {
+            return Ok(config);
+        }
+        let files = self.backend.list_files(NESTED_PATHSPEC)?;
+        config.with_nested(&root, &files).map_err(Error::from)
     }
 
     pub(crate) fn set_metric_changes(
@@ -95,23 +150,42 @@ impl<B: Backend> Service<B> {
             .unwrap_or_default())
     }
 
-    pub(crate) fn get_metrics(
+    pub(crate) fn get_remote_metrics(
         &self,
         commit_sha: &str,
         remote_name: &str,
-    ) -> Result<MetricStack, Error> {
-        let remote_metrics = self
+    ) -> Result<Vec<Metric>, Error> {
+        Ok(self
             .backend
             .read_note::<MetricList>(commit_sha, &NoteRef::remote_metrics(remote_name))?
             .map(|list| list.metrics)
-            .unwrap_or_default();
+            .unwrap_or_default())
+    }
 
+    /// Metrics stored for the commit, including the local changes
+    pub(crate) fn get_stored_metrics(
+        &self,
+        commit_sha: &str,
+        remote_name: &str,
+    ) -> Result<MetricStack, Error> {
+        let remote_metrics = self.get_remote_metrics(commit_sha, remote_name)?;
         let diff_metrics = self.get_metric_changes(commit_sha)?;
 
         Ok(MetricStack::from_iter(remote_metrics.into_iter())
             .with_changes(diff_metrics.into_iter()))
     }
 
+    /// Metrics stored for the commit with the derived metrics
+    pub(crate) fn get_metrics(
+        &self,
+        commit_sha: &str,
+        remote_name: &str,
+    ) -> Result<MetricStack, Error> {
+        let stack = self.get_stored_metrics(commit_sha, remote_name)?;
+        let config = self.open_config()?;
+        Ok(config.with_derived(stack))
+    }
+
     pub(crate) fn set_metrics_for_ref(
         &self,
         commit_sha: &str,
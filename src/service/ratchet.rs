@@ -0,0 +1,118 @@
+use crate::backend::Backend;
+use crate::entity::config::Config;
+use crate::entity::ratchet::{LineChange, Tightening};
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub remote: &'a str,
+    pub target: &'a str,
+    /// Ratio of the current value added to the tightened rules
+    pub margin: f64,
+    /// Only computes the changes, without writing the configuration
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Ratchet {
+    pub changes: Vec<Tightening>,
+    pub lines: Vec<LineChange>,
+}
+
+impl<B: Backend> super::Service<B> {
+    /// Tightens the min and max rules of the configuration file to the metrics of the target
+    pub(crate) fn ratchet(&self, opts: &Options) -> Result<Ratchet, super::Error> {
+        let path = Config::config_path(&self.backend.root_path()?);
+        if !path.is_file() {
+            return Ok(Ratchet::default());
+        }
+        let source = std::fs::read_to_string(&path)?;
+        let metrics = self.get_metrics(opts.target, opts.remote)?;
+        let (result, changes) = crate::entity::ratchet::ratchet(&source, &metrics, opts.margin)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        if !opts.dry_run && !changes.is_empty() {
+            std::fs::write(&path, &result)?;
+        }
+        Ok(Ratchet {
+            changes,
+            lines: crate::entity::ratchet::changed_lines(&source, &result),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::config::Rule;
+    use crate::service::Service;
+
+    const CONFIG: &str = r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0 # budget
+
+[[metrics.first.rules]]
+type = "min"
+value = 10.0
+"#;
+
+    #[test]
+    fn should_write_tightened_rules() {
+        let backend = MockBackend::default();
+        backend.set_config(CONFIG);
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 80.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let result = svc
+            .ratchet(&super::Options {
+                remote: "origin",
+                target: "HEAD",
+                margin: 0.05,
+                dry_run: false,
+            })
+            .unwrap();
+        assert_eq!(result.changes.len(), 2);
+        assert_eq!(result.lines.len(), 2);
+        assert_eq!(result.lines[0].line, 3);
+        assert_eq!(result.lines[0].after, "value = 84.0 # budget");
+        assert_eq!(
+            backend.get_config().metrics["first"].rules,
+            vec![Rule::max(84.0), Rule::min(76.0)]
+        );
+    }
+
+    #[test]
+    fn should_keep_config_with_dry_run() {
+        let backend = MockBackend::default();
+        backend.set_config(CONFIG);
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 80.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let result = svc
+            .ratchet(&super::Options {
+                remote: "origin",
+                target: "HEAD",
+                margin: 0.0,
+                dry_run: true,
+            })
+            .unwrap();
+        assert_eq!(result.changes.len(), 2);
+        assert_eq!(
+            backend.get_config().metrics["first"].rules,
+            vec![Rule::max(100.0), Rule::min(10.0)]
+        );
+    }
+}
@@ -1,24 +1,123 @@
 use crate::backend::Backend;
 use crate::entity::metric::{Metric, MetricChange};
+use crate::entity::selector::MetricSelector;
 
 #[derive(Debug)]
 pub(crate) struct Options<'a> {
     pub remote: &'a str,
     pub target: &'a str,
+    /// Only returns the metrics that would be removed
+    pub dry_run: bool,
 }
 
 impl<B: Backend> super::Service<B> {
-    pub(crate) fn remove(&self, index: usize, opts: &Options) -> Result<(), super::Error> {
-        let metrics = self.get_metrics(opts.target, opts.remote)?;
-        if let Some((header, value)) = metrics.at(index) {
+    /// Removes the metrics matching the selector and returns them.
+    pub(crate) fn remove(
+        &self,
+        selector: &MetricSelector,
+        opts: &Options,
+    ) -> Result<Vec<Metric>, super::Error> {
+        let removed = self
+            .get_stored_metrics(opts.target, opts.remote)?
+            .into_metric_iter()
+            .filter(|metric| selector.matches(&metric.header))
+            .collect::<Vec<_>>();
+
+        if !opts.dry_run && !removed.is_empty() {
             let mut changes = self.get_metric_changes(opts.target)?;
-            changes.push(MetricChange::Remove(Metric {
-                header: header.clone(),
-                value,
-            }));
+            changes.extend(removed.iter().cloned().map(MetricChange::Remove));
             self.set_metric_changes(opts.target, changes)?;
         }
 
-        Ok(())
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Options;
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::metric::Metric;
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    #[test]
+    fn should_remove_matching_metrics() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1.0
+
+[[metrics]]
+name = "binary-size"
+tags = { os = "macos" }
+value = 2.0
+
+[[metrics]]
+name = "build-time"
+tags = { os = "linux" }
+value = 3.0
+"#,
+        );
+        let svc = Service::new(backend);
+        let opts = Options {
+            remote: "origin",
+            target: "HEAD",
+            dry_run: false,
+        };
+        let selector = MetricSelector::new("b*").with_tags([("os".into(), "linux".into())].into());
+        let removed = svc.remove(&selector, &opts).unwrap();
+        assert_eq!(
+            removed,
+            vec![
+                Metric::new("binary-size", 1.0).with_tag("os", "linux"),
+                Metric::new("build-time", 3.0).with_tag("os", "linux"),
+            ]
+        );
+        let metrics = svc.get_metrics("HEAD", "origin").unwrap().into_vec();
+        assert_eq!(
+            metrics,
+            vec![Metric::new("binary-size", 2.0).with_tag("os", "macos")]
+        );
+    }
+
+    #[test]
+    fn should_not_remove_with_dry_run() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "HEAD",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "binary-size"
+tags = { os = "linux" }
+value = 1.0
+
+[[metrics]]
+name = "binary-size"
+tags = { os = "macos" }
+value = 2.0
+
+[[metrics]]
+name = "build-time"
+tags = { os = "linux" }
+value = 3.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let opts = Options {
+            remote: "origin",
+            target: "HEAD",
+            dry_run: true,
+        };
+        let removed = svc
+            .remove(&MetricSelector::new("binary-size"), &opts)
+            .unwrap();
+        assert_eq!(removed.len(), 2);
+        assert_eq!(backend.get_note("HEAD", NoteRef::Changes), None);
     }
 }
@@ -0,0 +1,182 @@
+use crate::backend::{Backend, NoteRef};
+use crate::entity::metric::MetricChange;
+use crate::entity::selector::MetricSelector;
+
+#[derive(Debug)]
+pub(crate) enum Scope<'a> {
+    /// Only discard the changes of the target
+    Target(&'a str),
+    /// Discard the changes of every commit
+    All,
+}
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub scope: Scope<'a>,
+    /// Only discard the changes related to the metrics matching the selector
+    pub selector: Option<&'a MetricSelector>,
+}
+
+/// Changes discarded for a given commit
+#[derive(Debug, PartialEq)]
+pub(crate) struct Discarded {
+    pub target: String,
+    pub changes: Vec<MetricChange>,
+}
+
+impl<B: Backend> super::Service<B> {
+    fn reset_target(
+        &self,
+        target: &str,
+        selector: Option<&MetricSelector>,
+    ) -> Result<Vec<MetricChange>, super::Error> {
+        let changes = self.get_metric_changes(target)?;
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+        let (discarded, kept): (Vec<_>, Vec<_>) = changes.into_iter().partition(|change| {
+            let (MetricChange::Add(metric) | MetricChange::Remove(metric)) = change;
+            selector.is_none_or(|selector| selector.matches(&metric.header))
+        });
+        if discarded.is_empty() {
+            return Ok(discarded);
+        }
+        if kept.is_empty() {
+            self.backend.remove_note(target, &NoteRef::Changes)?;
+        } else {
+            self.set_metric_changes(target, kept)?;
+        }
+        Ok(discarded)
+    }
+
+    /// Discards the local changes and returns them for every affected commit.
+    pub(crate) fn reset(&self, opts: &Options) -> Result<Vec<Discarded>, super::Error> {
+        let targets = match opts.scope {
+            Scope::Target(target) => vec![target.to_string()],
+            Scope::All => self
+                .backend
+                .list_notes(&NoteRef::Changes)?
+                .into_iter()
+                .map(|note| note.commit_id)
+                .collect(),
+        };
+        let mut result = Vec::new();
+        for target in targets {
+            let changes = self.reset_target(&target, opts.selector)?;
+            if !changes.is_empty() {
+                result.push(Discarded { target, changes });
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Options, Scope};
+    use crate::backend::mock::MockBackend;
+    use crate::backend::NoteRef;
+    use crate::entity::metric::{Metric, MetricChange};
+    use crate::entity::selector::MetricSelector;
+    use crate::service::Service;
+
+    #[test]
+    fn should_reset_a_single_target() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 1.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 3.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let discarded = svc
+            .reset(&Options {
+                scope: Scope::Target("aaaaaaa"),
+                selector: None,
+            })
+            .unwrap();
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(discarded[0].changes.len(), 2);
+        assert!(backend.get_note("aaaaaaa", NoteRef::Changes).is_none());
+        assert!(backend.get_note("bbbbbbb", NoteRef::Changes).is_some());
+    }
+
+    #[test]
+    fn should_reset_matching_changes_everywhere() {
+        let backend = MockBackend::default();
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 1.0
+
+[[changes]]
+action = "remove"
+name = "second"
+value = 2.0
+"#,
+        );
+        backend.set_note(
+            "bbbbbbb",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "first"
+value = 3.0
+"#,
+        );
+        let svc = Service::new(backend.clone());
+        let selector = MetricSelector::new("first");
+        let discarded = svc
+            .reset(&Options {
+                scope: Scope::All,
+                selector: Some(&selector),
+            })
+            .unwrap();
+        assert_eq!(
+            discarded
+                .iter()
+                .map(|item| item.target.as_str())
+                .collect::<Vec<_>>(),
+            vec!["aaaaaaa", "bbbbbbb"]
+        );
+        assert_eq!(
+            svc.get_metric_changes("aaaaaaa").unwrap(),
+            vec![MetricChange::Remove(Metric::new("second", 2.0))]
+        );
+        assert!(backend.get_note("bbbbbbb", NoteRef::Changes).is_none());
+    }
+
+    #[test]
+    fn should_do_nothing_without_changes() {
+        let backend = MockBackend::default();
+        let svc = Service::new(backend);
+        let discarded = svc
+            .reset(&Options {
+                scope: Scope::Target("HEAD"),
+                selector: None,
+            })
+            .unwrap();
+        assert!(discarded.is_empty());
+    }
+}
@@ -5,10 +5,16 @@ use crate::entity::metric::MetricStack;
 pub(crate) struct Options<'a> {
     pub remote: &'a str,
     pub target: &'a str,
+    /// Includes the metrics derived from the configuration
+    pub derived: bool,
 }
 
 impl<B: Backend> super::Service<B> {
     pub(crate) fn show(&self, opts: &Options) -> Result<MetricStack, super::Error> {
-        self.get_metrics(opts.target, opts.remote)
+        if opts.derived {
+            self.get_metrics(opts.target, opts.remote)
+        } else {
+            self.get_stored_metrics(opts.target, opts.remote)
+        }
     }
 }
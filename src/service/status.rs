@@ -0,0 +1,67 @@
+use crate::backend::{Backend, Note, NoteRef};
+use crate::entity::metric::MetricStack;
+use crate::entity::status::CommitStatus;
+
+#[derive(Debug)]
+pub(crate) struct Options<'a> {
+    pub remote: &'a str,
+}
+
+impl<B: Backend> super::Service<B> {
+    /// Sorts the notes from the oldest commit, the commits of the same second by ancestry
+    fn sort_by_commit(&self, notes: Vec<Note>) -> Result<Vec<Note>, super::Error> {
+        let mut timestamps = Vec::with_capacity(notes.len());
+        for note in notes.iter() {
+            timestamps.push(self.backend.get_commit(&note.commit_id)?.timestamp);
+        }
+        // number of the commits of the same second that are ancestors of the commit
+        let depths = notes
+            .iter()
+            .zip(timestamps.iter())
+            .map(|(note, timestamp)| {
+                notes
+                    .iter()
+                    .zip(timestamps.iter())
+                    .filter(|(other, other_timestamp)| {
+                        other.commit_id != note.commit_id
+                            && *other_timestamp == timestamp
+                            && self
+                                .backend
+                                .merge_base(&other.commit_id, &note.commit_id)
+                                .is_ok_and(|base| base == other.commit_id)
+                    })
+                    .count()
+            })
+            .collect::<Vec<_>>();
+        let mut result = notes
+            .into_iter()
+            .zip(timestamps.into_iter().zip(depths))
+            .collect::<Vec<_>>();
+        result.sort_by_key(|(_, key)| *key);
+        Ok(result.into_iter().map(|(note, _)| note).collect())
+    }
+
+    /// Lists the commits with local changes that are not pushed yet, from the oldest
+    pub(crate) fn status(&self, opts: &Options) -> Result<Vec<CommitStatus>, super::Error> {
+        let notes = self.sort_by_commit(self.backend.list_notes(&NoteRef::Changes)?)?;
+        let mut result = Vec::with_capacity(notes.len());
+        for note in notes {
+            let changes = self.get_metric_changes(&note.commit_id)?;
+            if changes.is_empty() {
+                continue;
+            }
+            let remote = MetricStack::from_iter(
+                self.get_remote_metrics(&note.commit_id, opts.remote)?
+                    .into_iter(),
+            );
+            let reachable = self.backend.is_reachable(&note.commit_id)?;
+            result.push(CommitStatus::new(
+                note.commit_id,
+                reachable,
+                remote,
+                changes,
+            ));
+        }
+        Ok(result)
+    }
+}
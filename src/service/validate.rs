@@ -0,0 +1,221 @@
+use indexmap::IndexSet;
+
+use crate::backend::{Backend, NoteRef};
+use crate::entity::config::{Config, NESTED_PATHSPEC};
+use crate::entity::metric::{MetricChange, MetricHeader};
+use crate::entity::validation::{validate, Diagnostic};
+
+impl<B: Backend> super::Service<B> {
+    /// Headers of all the metrics recorded in the history, remote or local
+    fn recorded_headers(&self, remote: &str) -> Result<IndexSet<MetricHeader>, super::Error> {
+        let mut result = IndexSet::new();
+        for note in self.backend.list_notes(&NoteRef::remote_metrics(remote))? {
+            result.extend(
+                self.get_remote_metrics(&note.commit_id, remote)?
+                    .into_iter()
+                    .map(|metric| metric.header),
+            );
+        }
+        for note in self.backend.list_notes(&NoteRef::Changes)? {
+            result.extend(
+                self.get_metric_changes(&note.commit_id)?
+                    .into_iter()
+                    .filter_map(|change| match change {
+                        MetricChange::Add(metric) => Some(metric.header),
+                        MetricChange::Remove(_) => None,
+                    }),
+            );
+        }
+        Ok(result)
+    }
+
+    /// Validates the configuration file, when it exists, and the files it loads
+    ///
+    /// The configured metrics are compared with the ones recorded in the history of the remote,
+    /// when given.
+    pub(crate) fn validate_config(
+        &self,
+        remote: Option<&str>,
+    ) -> Result<Vec<Diagnostic>, super::Error> {
+        let root = self.backend.root_path()?;
+        let path = Config::config_path(&root);
+        if !path.is_file() {
+            return Ok(Vec::new());
+        }
+        let recorded = remote
+            .map(|remote| self.recorded_headers(remote))
+            .transpose()?;
+        let source = std::fs::read_to_string(&path)?;
+        let parsed = Config::parse(&source);
+        let config = parsed.as_ref().ok().cloned();
+        let mut diagnostics = validate(
+            path.strip_prefix(&root).unwrap_or(&path),
+            &source,
+            parsed,
+            recorded.as_ref(),
+        );
+        let Some(config) = config else {
+            return Ok(diagnostics);
+        };
+        for path in config.included_files(&root)? {
+            let source = std::fs::read_to_string(&path)?;
+            // the derived metrics of the root configuration can be configured in the included files
+            let parsed = Config::parse_included(&source).map(|mut included| {
+                included.derived.extend(config.derived.clone());
+                included
+            });
+            diagnostics.extend(validate(
+                path.strip_prefix(&root).unwrap_or(&path),
+                &source,
+                parsed,
+                recorded.as_ref(),
+            ));
+        }
+        if config.discover {
+            for path in self.backend.list_files(NESTED_PATHSPEC)? {
+                if path
+                    .parent()
+                    .is_none_or(|parent| parent.as_os_str().is_empty())
+                {
+                    continue;
+                }
+                let source = std::fs::read_to_string(root.join(&path))?;
+                // the nested metrics only apply to the metrics with a path tag
+                diagnostics.extend(validate(&path, &source, config.parse_nested(&source), None));
+            }
+        }
+        if diagnostics.iter().all(|item| !item.is_error()) {
+            // the conflicts between the files are only found when merging them
+            match self.load_config() {
+                Err(super::Error::Io(err)) => diagnostics.push(Diagnostic::error(err.to_string())),
+                Err(err) => return Err(err),
+                Ok(_) => {}
+            }
+        }
+        Ok(diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::mock::MockBackend;
+    use crate::backend::{Backend, NoteRef};
+    use crate::service::Service;
+
+    #[test]
+    fn should_validate_with_recorded_metrics() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0
+
+[[metrics.second.rules]]
+type = "max"
+value = 100.0
+
+[[metrics.third.rules]]
+type = "max"
+value = 100.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaaa",
+            NoteRef::remote_metrics("origin"),
+            r#"[[metrics]]
+name = "first"
+tags = {}
+value = 80.0
+"#,
+        );
+        backend.set_note(
+            "aaaaaab",
+            NoteRef::Changes,
+            r#"[[changes]]
+action = "add"
+name = "second"
+tags = {}
+value = 80.0
+"#,
+        );
+        let diagnostics = Service::new(backend)
+            .validate_config(Some("origin"))
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            ".git-metrics.toml: line 9, column 11: metrics.third: no metric has been recorded with this name"
+        );
+    }
+
+    #[test]
+    fn should_validate_without_history() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"[[metrics.first.rules]]
+type = "max"
+value = 100.0
+"#,
+        );
+        let diagnostics = Service::new(backend).validate_config(None).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn should_validate_loaded_files() {
+        let backend = MockBackend::default();
+        backend.set_config(
+            r#"include = ["other.toml"]
+discover = true
+"#,
+        );
+        backend.set_file("other.toml", "[metrics.first]\nrule = []\n");
+        backend.set_file(
+            "services/api/.git-metrics.toml",
+            "[metrics.second]\nunit = { scale = \"binary\" }\n",
+        );
+        let diagnostics = Service::new(backend).validate_config(None).unwrap();
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "other.toml: line 2, column 1: unknown field `rule`, expected one of `description`, `aliases`, `required`, `rules`, `subsets`, `unit`",
+                "services/api/.git-metrics.toml: line 2, column 1: metrics.second.unit: can only be defined in the root configuration",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_only_load_tracked_nested_files() {
+        let backend = MockBackend::default();
+        backend.set_config("discover = true\n");
+        backend.set_file(
+            "services/api/.git-metrics.toml",
+            "[[metrics.first.rules]]\ntype = \"max\"\nvalue = 10.0\n",
+        );
+        let untracked = backend
+            .root_path()
+            .unwrap()
+            .join("target/.git-metrics.toml");
+        std::fs::create_dir_all(untracked.parent().unwrap()).unwrap();
+        std::fs::write(untracked, "[metrics.first]\nrule = []\n").unwrap();
+        let service = Service::new(backend);
+        assert!(service.validate_config(None).unwrap().is_empty());
+        let config = service.open_config().unwrap();
+        assert_eq!(
+            config.metrics["first"].subsets.keys().collect::<Vec<_>>(),
+            vec!["services/api"]
+        );
+    }
+
+    #[test]
+    fn should_validate_without_config() {
+        let backend = MockBackend::default();
+        let diagnostics = Service::new(backend)
+            .validate_config(Some("origin"))
+            .unwrap();
+        assert!(diagnostics.is_empty());
+    }
+}
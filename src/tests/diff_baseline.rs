@@ -0,0 +1,73 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn git<const N: usize>(repo: &GitRepo, args: [&str; N]) -> String {
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    //
+    client.commit("First commit");
+    client.push();
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    client.metrics(["push"], assert_success!());
+    let first = git(&client, ["rev-parse", "HEAD"]);
+    // working on a feature branch
+    git(&client, ["checkout", "-b", "feature"]);
+    client.commit("Feature commit");
+    client.metrics(["add", "binary-size", "150.0"], assert_success!());
+    client.commit("Other feature commit");
+    client.metrics(["add", "binary-size", "200.0"], assert_success!());
+    //
+    client.metrics(["diff"], |stdout, stderr, code| {
+        similar_asserts::assert_eq!(
+            stdout,
+            "- binary-size 150.00\n+ binary-size 200.00 (+33.33 %)\n"
+        );
+        assert_eq!(stderr, "");
+        assert!(code.is_success());
+    });
+    client.metrics(
+        ["diff", "--baseline", "merge-base:origin/main"],
+        |stdout, stderr, code| {
+            similar_asserts::assert_eq!(
+                stdout,
+                format!(
+                    "compared with {first}\n- binary-size 100.00\n+ binary-size 200.00 (+100.00 %)\n"
+                )
+            );
+            assert_eq!(stderr, "");
+            assert!(code.is_success());
+        },
+    );
+    client.metrics(
+        ["check", "--baseline", "ref:main"],
+        |stdout, stderr, code| {
+            similar_asserts::assert_eq!(
+                stdout,
+                format!("compared with {first}\n[SKIP] binary-size 100.00 => 200.00 Δ +100.00 (+100.00 %)\n")
+            );
+            assert_eq!(stderr, "");
+            assert!(code.is_success());
+        },
+    );
+}
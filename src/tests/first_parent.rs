@@ -0,0 +1,83 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn git<const N: usize>(repo: &GitRepo, args: [&str; N]) -> String {
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    //
+    client.commit("First commit");
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    let first = git(&client, ["rev-parse", "--short", "HEAD"]);
+    // the commits of the merged branch are only measured through the merge commit
+    git(&client, ["checkout", "-b", "feature"]);
+    client.commit("Feature commit");
+    client.metrics(["add", "binary-size", "300.0"], assert_success!());
+    client.commit("Fix feature");
+    client.metrics(["add", "binary-size", "120.0"], assert_success!());
+    git(&client, ["checkout", "-"]);
+    client.commit("Main commit");
+    client.metrics(["add", "binary-size", "110.0"], assert_success!());
+    let main = git(&client, ["rev-parse", "--short", "HEAD"]);
+    git(
+        &client,
+        ["merge", "--no-ff", "-m", "Merge feature", "feature"],
+    );
+    client.metrics(["add", "binary-size", "130.0"], assert_success!());
+    let merge = git(&client, ["rev-parse", "--short", "HEAD"]);
+    let author = git(&client, ["log", "-1", "--format=%an"]);
+    //
+    client.metrics(["blame", "binary-size"], |stdout, stderr, exit| {
+        similar_asserts::assert_eq!(
+            stdout,
+            format!(
+                r#"* {merge} Merge feature ({author})
+    binary-size +20.00 (+18.18 %)
+* {main} Main commit ({author})
+    binary-size +10.00 (+10.00 %)
+* {first} First commit ({author})
+    binary-size 100.00 (new)
+"#
+            )
+        );
+        similar_asserts::assert_eq!(stderr, "");
+        assert!(exit.is_success());
+    });
+    client.metrics(
+        ["bisect", "--good", first.as_str(), "binary-size"],
+        |stdout, stderr, exit| {
+            similar_asserts::assert_eq!(
+                stdout,
+                format!(
+                    r#"* {merge} Merge feature
+    binary-size 110.00 => 130.00 Δ +20.00 (+18.18 %)
+* {main} Main commit
+    binary-size 100.00 => 110.00 Δ +10.00 (+10.00 %)
+"#
+                )
+            );
+            similar_asserts::assert_eq!(stderr, "");
+            assert!(exit.is_success());
+        },
+    );
+}
@@ -0,0 +1,63 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn count_notes_commits(repo: &GitRepo) -> usize {
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .arg("rev-list")
+        .arg("--count")
+        .arg("refs/notes/metrics-remote-origin")
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .unwrap()
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    //
+    client.commit("First commit");
+    client.metrics(["add", "binary-size", "1024.0"], assert_success!());
+    client.commit("Second commit");
+    client.metrics(["add", "binary-size", "2048.0"], assert_success!());
+    client.metrics(["add", "coverage", "0.5"], assert_success!());
+    client.push();
+    client.metrics(["push"], assert_success!());
+    //
+    let before = count_notes_commits(&client);
+    client.metrics(
+        [
+            "migrate",
+            "binary-size",
+            "--rename",
+            "artifact.size",
+            "--scale",
+            "0.5",
+        ],
+        |stdout, stderr, code| {
+            assert!(code.is_success(), "stderr: {stderr:?}");
+            assert!(stdout.ends_with("2 commits migrated\n"), "{stdout:?}");
+        },
+    );
+    assert_eq!(count_notes_commits(&client), before + 1);
+    //
+    client.metrics(
+        ["show"],
+        assert_success!("artifact.size 1024.00\ncoverage 0.50\n"),
+    );
+    client.metrics(
+        ["show", "--target", "HEAD~1"],
+        assert_success!("artifact.size 512.00\n"),
+    );
+    client.metrics(["push"], assert_success!());
+}
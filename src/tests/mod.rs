@@ -3,8 +3,15 @@ use std::process::Command;
 
 mod check_budget;
 mod conflict_different;
+mod diff_baseline;
 mod display_diff;
+mod first_parent;
+mod migrate_history;
+mod nested_config;
+mod pending_status;
 mod simple_use_case;
+mod trend_window;
+mod waive_regression;
 
 fn init_logs() {
     let _ = tracing_subscriber::fmt()
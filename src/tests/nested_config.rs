@@ -0,0 +1,54 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn write(repo: &GitRepo, path: &str, content: &str) {
+    let path = repo.path.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    write(&client, ".git-metrics.toml", "discover = true\n");
+    write(
+        &client,
+        "services/api/.git-metrics.toml",
+        "[metrics.binary-size]\nrule = []\n",
+    );
+    // the untracked files are ignored
+    write(
+        &client,
+        "target/.git-metrics.toml",
+        "[metrics.binary-size]\nunit = {}\n",
+    );
+    let output = Command::new("git")
+        .current_dir(client.path.as_path())
+        .args(["add", ".git-metrics.toml", "services"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    //
+    client.metrics(["config", "validate"], |stdout, stderr, code| {
+        similar_asserts::assert_eq!(
+            stdout,
+            "error: services/api/.git-metrics.toml: line 2, column 1: unknown field `rule`, expected one of `description`, `aliases`, `required`, `rules`, `subsets`, `unit`\n"
+        );
+        similar_asserts::assert_eq!(stderr, "");
+        assert!(!code.is_success());
+    });
+    //
+    write(
+        &client,
+        "services/api/.git-metrics.toml",
+        "[[metrics.binary-size.rules]]\ntype = \"max\"\nvalue = 10.0\n",
+    );
+    client.metrics(["config", "validate"], assert_success!());
+}
@@ -0,0 +1,61 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn head_sha(repo: &GitRepo) -> String {
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .arg("rev-parse")
+        .arg("HEAD")
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    //
+    client.commit("First commit");
+    client.push();
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    client.metrics(["push"], assert_success!());
+    client.metrics(["add", "binary-size", "120.0"], assert_success!());
+    let first = head_sha(&client);
+    //
+    client.commit("Second commit");
+    client.metrics(["add", "binary-size", "90.0"], assert_success!());
+    let second = head_sha(&client);
+    // rewriting the commit makes the previous one unreachable
+    let output = Command::new("git")
+        .current_dir(client.path.as_path())
+        .arg("commit")
+        .arg("--amend")
+        .arg("--allow-empty")
+        .arg("-m")
+        .arg("Amended commit")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    //
+    let expected = [
+        format!("* {}\n    + binary-size 120.00 (was 100.00)\n", &first[..7]),
+        format!(
+            "* {} warning: not reachable from any branch\n    + binary-size 90.00 (new)\n",
+            &second[..7]
+        ),
+    ];
+    client.metrics(["status"], assert_success!(expected.concat()));
+    //
+    client.metrics(["reset", "--all", "binary-size"], |stdout, stderr, code| {
+        assert!(code.is_success(), "stderr: {stderr:?}");
+        assert_eq!(stdout.matches("discarded 1 change on ").count(), 2);
+    });
+    client.metrics(["status"], assert_success!());
+}
@@ -0,0 +1,117 @@
+use std::process::Command;
+
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+fn git<const N: usize>(repo: &GitRepo, args: [&str; N]) {
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn commit_at(repo: &GitRepo, message: &str, days_ago: i64) {
+    let date = format!("@{} +0000", 1_700_000_000 - days_ago * 86400);
+    let output = Command::new("git")
+        .current_dir(repo.path.as_path())
+        .env("GIT_AUTHOR_DATE", &date)
+        .env("GIT_COMMITTER_DATE", &date)
+        .args(["commit", "--allow-empty", "-m", message])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "stderr: {:?}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    std::fs::write(
+        client.path.join(".git-metrics.toml"),
+        r#"[[metrics.binary-size.rules]]
+type = "max-increase-over"
+window = 2
+ratio = 0.25
+"#,
+    )
+    .unwrap();
+    //
+    client.commit("First commit");
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    client.commit("Second commit");
+    client.metrics(["add", "binary-size", "110.0"], assert_success!());
+    // the commits of the merged branch don't count in the window
+    git(&client, ["checkout", "-b", "feature"]);
+    client.commit("First feature commit");
+    client.commit("Second feature commit");
+    client.commit("Third feature commit");
+    git(&client, ["checkout", "-"]);
+    git(
+        &client,
+        ["merge", "--no-ff", "-m", "Merge feature", "feature"],
+    );
+    client.commit("Last commit");
+    client.metrics(["add", "binary-size", "130.0"], assert_success!());
+    client.metrics(["check", "HEAD"], |stdout, stderr, exit| {
+        // compared with the first commit, two commits before the merge on the branch
+        similar_asserts::assert_eq!(
+            stdout,
+            r#"[FAILURE] binary-size 110.00 => 130.00 Δ +20.00 (+18.18 %)
+    increase over the last 2 commits should be less than 25.00 % ... failed
+"#
+        );
+        similar_asserts::assert_eq!(stderr, "");
+        assert!(!exit.is_success());
+    });
+}
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute_with_days(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    std::fs::write(
+        client.path.join(".git-metrics.toml"),
+        r#"[[metrics.binary-size.rules]]
+type = "max-increase-over"
+window = "3d"
+ratio = 0.25
+"#,
+    )
+    .unwrap();
+    //
+    commit_at(&client, "First commit", 10);
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    commit_at(&client, "Second commit", 5);
+    client.metrics(["add", "binary-size", "110.0"], assert_success!());
+    commit_at(&client, "Last commit", 0);
+    client.metrics(["add", "binary-size", "130.0"], assert_success!());
+    client.metrics(["check", "HEAD"], |stdout, stderr, exit| {
+        // compared with the first commit, the latest one committed 3 days before the baseline
+        similar_asserts::assert_eq!(
+            stdout,
+            r#"[FAILURE] binary-size 110.00 => 130.00 Δ +20.00 (+18.18 %)
+    increase over the last 3 days should be less than 25.00 % ... failed
+"#
+        );
+        similar_asserts::assert_eq!(stderr, "");
+        assert!(!exit.is_success());
+    });
+}
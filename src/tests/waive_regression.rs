@@ -0,0 +1,42 @@
+use crate::assert_success;
+use crate::tests::GitRepo;
+
+#[test_case::test_case("git2"; "with git2 backend")]
+#[test_case::test_case("command"; "with command backend")]
+fn execute(backend: &'static str) {
+    super::init_logs();
+
+    let root = tempfile::tempdir().unwrap();
+    let server = GitRepo::create(backend, root.path().join("server"));
+    let client = GitRepo::clone(&server, root.path().join("client"));
+    std::fs::write(
+        client.path.join(".git-metrics.toml"),
+        r#"[[metrics.binary-size.rules]]
+type = "max-increase"
+ratio = 0.2
+"#,
+    )
+    .unwrap();
+    //
+    client.commit("First commit");
+    client.metrics(["add", "binary-size", "100.0"], assert_success!());
+    //
+    client.commit("Second commit\n\nMetrics-Allow: binary-size +60%");
+    client.metrics(["add", "binary-size", "150.0"], assert_success!());
+    client.metrics(["check", "HEAD"], |stdout, stderr, exit| {
+        similar_asserts::assert_eq!(
+            stdout,
+            r#"[WAIVED] binary-size 100.00 => 150.00 Δ +50.00 (+50.00 %)
+    increase should be less than 20.00 % ... waived
+"#
+        );
+        similar_asserts::assert_eq!(stderr, "");
+        assert!(exit.is_success());
+    });
+    //
+    client.commit("Third commit");
+    client.metrics(["add", "binary-size", "200.0"], assert_success!());
+    client.metrics(["check", "HEAD"], |_stdout, _stderr, exit| {
+        assert!(!exit.is_success());
+    });
+}